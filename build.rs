@@ -0,0 +1,36 @@
+use std::{env, process::Command};
+
+// Run the command and get its trimmed output if it has succeeded.
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let output = String::from_utf8(output.stdout).ok()?;
+    Some(output.trim().to_string()).filter(|output| !output.is_empty())
+}
+
+fn main() {
+    let commit = output("git", &["rev-parse", "--short", "HEAD"]);
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+
+    println!(
+        "cargo:rustc-env=NTA_COMMIT_HASH={}",
+        commit.as_deref().unwrap_or("unknown")
+    );
+    println!(
+        "cargo:rustc-env=NTA_BUILD_DATE={}",
+        chrono::Utc::now().format("%Y-%m-%d")
+    );
+    println!(
+        "cargo:rustc-env=NTA_RUSTC_VERSION={}",
+        output(rustc.as_str(), &["--version"])
+            .as_deref()
+            .unwrap_or("unknown")
+    );
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
@@ -1,33 +1,64 @@
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
+    env,
     ffi::OsStr,
-    io::{self, Cursor},
+    fmt, io,
     iter::repeat_with,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::SystemTime,
 };
 
-use chrono::{Datelike, NaiveDate};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use fern::colors::ColoredLevelConfig;
 use futures::stream::{self, StreamExt};
+use globset::{Glob, GlobSetBuilder};
 use prettytable::{row, Table};
 use regex::Regex;
 use tokio::{
     fs::{self, File},
     io::{AsyncReadExt, AsyncWriteExt},
+    process, signal, time,
 };
-use url::Url;
 use uuid::Uuid;
-use walkdir::WalkDir;
 
 use crate::{
-    cli::{Annex, Arguments, Command, Info, Note},
-    config::{Config, Options},
+    cli::{Annex, Arguments, Command, Info, Note, Setting, Target},
+    config::{self, Config, Identity, Options},
     error::Error,
 };
 
+pub(crate) mod age;
 pub(crate) mod apod;
+pub(crate) mod calendar;
+pub(crate) mod daily;
 pub(crate) mod entry;
+pub(crate) mod format;
+pub(crate) mod git;
+pub(crate) mod grabber;
+pub(crate) mod graph;
+pub(crate) mod hooks;
+pub(crate) mod http;
+pub(crate) mod images;
+pub(crate) mod index;
+pub(crate) mod journal;
+pub(crate) mod lang;
+pub(crate) mod links;
+pub(crate) mod manifest;
+pub(crate) mod markdown;
+pub(crate) mod meta;
+pub(crate) mod opener;
+pub(crate) mod pager;
+pub(crate) mod raindrop;
+pub(crate) mod state;
+pub(crate) mod summary;
+pub(crate) mod template;
+pub(crate) mod tidy;
+pub(crate) mod toc;
 pub(crate) mod twir;
+pub(crate) mod walk;
+pub(crate) mod watch;
 
 ///
 /// The command line application.
@@ -35,6 +66,18 @@ pub(crate) mod twir;
 #[derive(Debug)]
 pub struct Application {
     config: Config,
+    journal: journal::Journal,
+    changes: git::Changes,
+    index_file: Option<PathBuf>,
+    state_file: PathBuf,
+    client: reqwest::Client,
+    daily: daily::DailyNotes,
+    cache_path: PathBuf,
+    config_file: PathBuf,
+    log_file: PathBuf,
+    identity: Identity,
+    include_hidden: bool,
+    color: bool,
 }
 
 impl Application {
@@ -42,36 +85,50 @@ impl Application {
     pub(crate) const AUTHOR: &'static str = "new-divos";
     pub(crate) const NAME: &'static str = "nta";
     pub(crate) const DESCRIPTION: &'static str = "A Very simple Notes Attendant";
+    pub(crate) const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+    pub(crate) const COMMIT_HASH: &'static str = env!("NTA_COMMIT_HASH");
+    pub(crate) const BUILD_DATE: &'static str = env!("NTA_BUILD_DATE");
+    pub(crate) const RUSTC_VERSION: &'static str = env!("NTA_RUSTC_VERSION");
+    pub(crate) const COMMIT_MESSAGE: &'static str = "Update the notes set";
+    pub(crate) const TIMESTAMP_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S";
 
     ///
     /// Create command line application with configuration.
     ///
-    pub fn new(config: Config) -> Self {
-        Self { config }
+    pub fn new(config: Config, options: &Options) -> Self {
+        Self {
+            config,
+            journal: journal::Journal::new(options.journal_file(), options.backup_path()),
+            changes: git::Changes::default(),
+            index_file: (!options.no_index()).then(|| PathBuf::from(options.index_file())),
+            state_file: PathBuf::from(options.state_file()),
+            client: http::client(),
+            daily: daily::DailyNotes::default(),
+            cache_path: PathBuf::from(options.cache_path()),
+            config_file: PathBuf::from(options.config_file()),
+            log_file: PathBuf::from(options.log_file()),
+            identity: options.identity().clone(),
+            include_hidden: options.include_hidden(),
+            color: options.color(),
+        }
     }
 
     ///
     /// Setup the application logger.
     ///
     pub fn setup_logger(args: &Arguments, options: &Options) -> Result<(), Error> {
+        let colors = options.color().then(ColoredLevelConfig::new);
+
         fern::Dispatch::new()
-            .format(|out, message, record| {
-                out.finish(format_args!(
-                    "{}[{}][{}] {}",
-                    chrono::Local::now().format("[%Y-%m-%d][%H:%M:%S]"),
-                    record.target(),
-                    record.level(),
-                    message
-                ))
-            })
             .chain(
                 fern::Dispatch::new()
+                    .format(log_format(colors))
                     .level(log::LevelFilter::Warn)
                     .level_for("nta", log::LevelFilter::Info)
                     .chain(io::stdout()),
             )
             .chain({
-                let mut file_config = fern::Dispatch::new();
+                let mut file_config = fern::Dispatch::new().format(log_format(None));
 
                 file_config = match args.verbosity {
                     0 => file_config
@@ -91,6 +148,7 @@ impl Application {
             })
             .chain(
                 fern::Dispatch::new()
+                    .format(log_format(colors))
                     .level(log::LevelFilter::Error)
                     .chain(io::stderr()),
             )
@@ -99,75 +157,621 @@ impl Application {
         Ok(())
     }
 
+    ///
+    /// Report the failed command error to stderr in the requested format.
+    ///
+    pub fn report_error(args: &Arguments, error: &Error) {
+        match args.format {
+            format::Format::Text => eprintln!("Error: {}", error),
+            format::Format::Json => eprintln!("{}", error.to_json()),
+        }
+    }
+
+    ///
+    /// Print the table to stdout, the titles are bold if the output is colored.
+    ///
+    fn print_table(&self, table: &Table) -> Result<(), Error> {
+        if self.color {
+            table.printstd();
+        } else {
+            table.print(&mut io::stdout())?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Print the requested configuration and log file paths.
+    /// Returns `true` if any path has been printed.
+    ///
+    pub fn print_paths(args: &Arguments, options: &Options) -> bool {
+        if args.print_config_path {
+            println!("{}", options.config_file().display());
+        }
+        if args.print_log_path {
+            println!("{}", options.log_file().display());
+        }
+
+        args.print_config_path || args.print_log_path
+    }
+
+    ///
+    /// Run the command which does not require the loaded configuration.
+    /// Returns `true` if the command has been processed.
+    ///
+    pub async fn run_standalone(args: &Arguments, options: &Options) -> Result<bool, Error> {
+        let command = match args.command {
+            Some(ref command) => command,
+            None => return Ok(true),
+        };
+
+        match *command {
+            // Change the application configuration.
+            Command::Config { ref setting } => {
+                let property = match setting {
+                    Setting::Set { property, .. } => property,
+                    Setting::Unset { property } => property,
+                };
+                if !Config::is_property(property) {
+                    return Err(Error::IllegalConfigProperty(property.clone()));
+                }
+
+                match setting {
+                    Setting::Set { property, value } => {
+                        Config::set(options.config_file(), property, value).await?
+                    }
+                    Setting::Unset { property } => {
+                        Config::unset(options.config_file(), property).await?
+                    }
+                }
+            }
+
+            // Initialize the application configuration and the notes set.
+            Command::Init {
+                ref root,
+                ref nasa_key,
+                force,
+            } => Self::init(options, root.as_deref(), nasa_key.as_deref(), force).await?,
+
+            // Print the version information.
+            Command::Version { build_info } => {
+                println!("{} {}", Self::NAME, Self::VERSION);
+                if build_info {
+                    println!("commit: {}", Self::COMMIT_HASH);
+                    println!("build date: {}", Self::BUILD_DATE);
+                    println!("compiler: {}", Self::RUSTC_VERSION);
+                }
+            }
+
+            // Upgrade the configuration file.
+            Command::Migrate => {
+                let migrated = Config::migrate(options.config_file()).await?;
+
+                if !args.quiet {
+                    match migrated {
+                        Some(version) => println!(
+                            "Migrated the configuration from version {} to {}",
+                            version,
+                            Config::VERSION
+                        ),
+                        None => println!("The configuration is up to date"),
+                    }
+                }
+            }
+
+            // Undo the last repair of the notes set.
+            Command::Undo => {
+                let journal = journal::Journal::new(options.journal_file(), options.backup_path());
+                let undone = journal.undo().await?;
+
+                if !args.quiet {
+                    let mut summary = summary::Summary::default();
+                    summary.count("undone", undone, "file operation");
+                    println!("{}", summary);
+                }
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    ///
+    /// Initialize the application configuration and the notes set.
+    ///
+    async fn init(
+        options: &Options,
+        root: Option<&Path>,
+        nasa_key: Option<&str>,
+        force: bool,
+    ) -> Result<(), Error> {
+        if options.config_file().exists() && !force {
+            return Err(Error::ConfigAlreadyExists(PathBuf::from(
+                options.config_file(),
+            )));
+        }
+
+        // Prompt for the missing values only in the interactive mode.
+        let (root, nasa_key) = match root {
+            Some(root) => (PathBuf::from(root), nasa_key.map(String::from)),
+            None => (
+                PathBuf::from(config::prompt("Enter the notes root path: ")?),
+                match nasa_key {
+                    Some(nasa_key) => Some(nasa_key.to_string()),
+                    None => Some(config::prompt(
+                        "Enter the NASA Astronomy Picture of the Day API key: ",
+                    )?),
+                },
+            ),
+        };
+        let nasa_key = nasa_key.filter(|key| !key.is_empty());
+
+        if let Some(ref nasa_key) = nasa_key {
+            apod::verify_key(&http::client(), nasa_key).await?;
+            log::info!("The NASA Astronomy Picture of the Day API key has been verified");
+        }
+
+        let config = Config::scaffold(root, nasa_key);
+        config.create_dirs().await?;
+        config.write(options.config_file()).await?;
+
+        Ok(())
+    }
+
+    ///
+    /// Walk the directory of the notes set.
+    ///
+    fn walk<P: AsRef<Path>>(&self, path: P) -> walk::Entries {
+        self.entries(self.walker(path))
+    }
+
+    ///
+    /// Create the walker of the directory of the notes set to configure it.
+    ///
+    fn walker<P: AsRef<Path>>(&self, path: P) -> walkdir::WalkDir {
+        walk::dir(path, self.config.max_depth(), self.config.follow_links())
+    }
+
+    ///
+    /// Get the entries of the configured walker skipping the hidden ones
+    /// unless they are included.
+    ///
+    fn entries(&self, walker: walkdir::WalkDir) -> walk::Entries {
+        walk::entries(walker, self.include_hidden)
+    }
+
     ///
     /// Run the application.
     ///
     pub async fn run(&self, args: &Arguments) -> Result<(), Error> {
-        match args.command {
+        let command = match args.command {
+            Some(ref command) => command,
+            None => return Ok(()),
+        };
+        let mut summary = summary::Summary::default();
+
+        match *command {
             // Repair notes set.
             Command::Repair {
                 wiki_refs,
                 remove_unused_files,
+                older_than,
                 rename_files,
+                reshard,
+                canonical_uuid,
+                apply_banners,
+                tidy,
+                final_newline,
+                normalize_tags_block,
+                fix_embeds,
+                fix_apod_iframes,
+                touch_modified,
+                dedup_keys,
+                fix,
+                fix_daily_links,
+                apply,
+                ref prefix,
+                ref suffix,
+                find_duplicates,
+                prune_apod,
+                keep,
+                yes,
+            } => {
+                self.journal.begin().await;
+
+                let result: Result<(), Error> = async {
+                    if wiki_refs {
+                        let repaired = self.repair_wiki_refs().await?;
+                        summary.count("repaired", repaired, "file");
+                    }
+
+                    if remove_unused_files {
+                        let deleted = self.remove_unused_files(older_than).await?;
+                        summary.count("deleted", deleted, "unused file");
+                    }
+
+                    if rename_files {
+                        let renamed = self.rename_attached_files().await?;
+                        summary.count("renamed", renamed, "attached file");
+                    }
+
+                    if reshard {
+                        let moved = self.reshard_files().await?;
+                        summary.count("moved", moved, "attached file");
+                    }
+
+                    if canonical_uuid {
+                        let renamed = self.canonical_uuid_files().await?;
+                        summary.count("lowercased", renamed, "attached file name");
+                    }
+
+                    if apply_banners {
+                        let applied = self.apply_banners().await?;
+                        summary.count("applied", applied, "banner");
+                    }
+
+                    if tidy {
+                        let tidied = self.tidy_notes().await?;
+                        summary.count("tidied", tidied, "note");
+                    }
+
+                    if final_newline {
+                        let fixed = self.fix_final_newlines().await?;
+                        summary.count("fixed the final line break in", fixed, "note");
+                    }
+
+                    if normalize_tags_block {
+                        let normalized = self.normalize_tags().await?;
+                        summary.count("normalized the tags of", normalized, "note");
+                    }
+
+                    if fix_embeds {
+                        let fixed = self.fix_embeds().await?;
+                        summary.count("fixed the embeds in", fixed, "note");
+                    }
+
+                    if fix_apod_iframes {
+                        let fixed = self.fix_apod_iframes().await?;
+                        summary.count("fixed the videos in", fixed, "APoD note");
+                    }
+
+                    if dedup_keys {
+                        let (found, fixed) = self.dedup_keys(fix).await?;
+                        if fix {
+                            summary.count("fixed the duplicate keys in", fixed, "note");
+                        } else {
+                            summary.count("found the duplicate keys in", found, "note");
+                        }
+                    }
+
+                    if fix_daily_links {
+                        let affix = entry::NameAffix::new(prefix.as_deref(), suffix.as_deref());
+                        let (found, fixed) = self.fix_daily_links(apply, &affix).await?;
+                        if apply {
+                            summary.count("fixed the back-links in", fixed, "daily note");
+                        } else {
+                            summary.count("found the broken back-links in", found, "daily note");
+                        }
+                    }
+
+                    if find_duplicates {
+                        let found = self.find_duplicates().await?;
+                        summary.count("found", found, "duplicate note group");
+                    }
+
+                    if prune_apod {
+                        let pruned = self.prune_apod(keep, yes).await?;
+                        if yes {
+                            summary.count("pruned", pruned, "APoD note");
+                        } else {
+                            summary.count("found to prune", pruned, "APoD note");
+                        }
+                    }
+
+                    // The notes are touched after all the other repairs.
+                    if touch_modified {
+                        let touched = self.touch_modified().await?;
+                        summary.count("touched", touched, "modified note");
+                    }
+
+                    Ok(())
+                }
+                .await;
+
+                // Keep the journal of the partially completed repair too.
+                self.journal.save().await?;
+                result?;
+            }
+
+            // Grab note into notes set.
+            Command::Grab {
+                ref template_dir,
+                open,
+                since_last,
+                ref prefix,
+                ref suffix,
+                ref manifest,
+                ref note,
             } => {
-                if wiki_refs {
-                    self.repair_wiki_refs().await?;
+                let templates = match template_dir {
+                    Some(path) => template::Templates::new(path),
+                    None => template::Templates::new(self.config.templates_path()),
+                };
+
+                let tasks = match manifest {
+                    Some(path) => manifest::read(path).await?,
+                    None => Vec::new(),
+                };
+                let notes: Vec<&Note> = tasks.iter().chain(note.iter()).collect();
+                if notes.is_empty() && manifest.is_none() {
+                    return Err(Error::MissingGrabNote);
                 }
 
-                if remove_unused_files {
-                    self.remove_unused_files().await?;
+                // The hook timeout is checked before anything is grabbed.
+                let hook_timeout = self.config.hook_timeout()?;
+                let affix = entry::NameAffix::new(prefix.as_deref(), suffix.as_deref());
+
+                // The manifest tasks are run in sequence, the failed ones
+                // are reported after all of them.
+                let mut last_path: Option<PathBuf> = None;
+                let mut errors: Vec<Error> = Vec::new();
+                for note in notes {
+                    let grabbed = self
+                        .grab_note(&templates, note, since_last, affix.clone(), hook_timeout)
+                        .await;
+                    match grabbed {
+                        Ok((path, report)) => {
+                            last_path = path.or(last_path);
+                            summary.append(report);
+                        }
+                        Err(e) if manifest.is_none() => return Err(e),
+                        Err(e) => {
+                            log::error!("The grab task has failed: {}", e);
+                            errors.push(e);
+                        }
+                    }
                 }
 
-                if rename_files {
-                    self.rename_attached_files().await?;
+                // Open only the latest note of the grabbed ones.
+                if let Some(path) = last_path.filter(|_| open) {
+                    opener::open(path.as_path()).await?;
+                }
+                if !errors.is_empty() {
+                    return Err(Error::multiple(errors));
                 }
             }
 
-            // Grab note into notes set.
-            Command::Grab { ref note } => match note {
-                // Grab NASA Astronomy Picture of the Day note.
-                Note::APoD { update_daily } => self.grab_apod(*update_daily).await?,
-
-                // Grab This Week in Rust note.
-                Note::TWiR {
-                    issues,
-                    update_daily,
-                } => self.grab_twir(issues, *update_daily).await?,
-            },
+            // Open the note with the editor.
+            Command::Open { ref target } => {
+                let path = match target {
+                    Target::APoD { date } => self
+                        .config
+                        .apod_note_path(self.config.format_date(*date).as_str()),
+                    Target::TWiR { issue } => self.config.twir_note_path(*issue),
+                };
+                if !path.is_file() {
+                    return Err(Error::IllegalPath(format!("{}", path.display())));
+                }
+
+                opener::open(path.as_path()).await?;
+            }
 
             // Show additional information.
             Command::Show { ref info } => match info {
                 // Show This Week in Rust issues.
-                Info::TWiR { last } => self.show_twir(*last).await?,
+                Info::TWiR {
+                    open: Some(number), ..
+                } => {
+                    self.open_twir(*number).await?;
+                    summary.count("opened", 1, "TWiR issue");
+                }
+
+                Info::TWiR {
+                    last,
+                    page,
+                    per_page,
+                    missing,
+                    prefix,
+                    suffix,
+                    format,
+                    ..
+                } => {
+                    let page = page.map(|page| (page, *per_page));
+                    let affix = entry::NameAffix::new(prefix.as_deref(), suffix.as_deref());
+                    let missing = if *missing { Some(&affix) } else { None };
+                    let shown = self.show_twir(*last, page, missing, *format).await?;
+
+                    // The summary would break the CSV output.
+                    if *format == twir::TableFormat::Table {
+                        summary.count("shown", shown, "TWiR issue");
+                    }
+                }
+
+                // Show unused files.
+                Info::Unused { older_than } => {
+                    let found = self.show_unused_files(*older_than).await?;
+                    summary.count("found", found, "unused file");
+                }
+
+                // Show orphan notes.
+                Info::Orphans { exclude } => {
+                    let found = self.show_orphans(exclude).await?;
+                    summary.count("found", found, "orphan note");
+                }
             },
 
             // Add the additional information to the notes set.
             Command::Add { ref annex } => match annex {
-                // Add the calendar to the monthly note.
-                Annex::Calendar { year, month } => self.add_calendar(*year, *month).await?,
+                // Add the calendar to the monthly, quarterly or annual note.
+                Annex::Calendar {
+                    year,
+                    month,
+                    quarter,
+                    whole_year,
+                    style,
+                    all_links,
+                } => {
+                    let period = match quarter {
+                        _ if *whole_year => calendar::Period::Year,
+                        Some(quarter) => calendar::Period::Quarter(*quarter),
+                        None => calendar::Period::Month(*month),
+                    };
+                    self.add_calendar(*year, period, *style, *all_links).await?;
+                    summary.push(format!("added the calendar for {}", period.name(*year)));
+                }
+
+                // Add the creation time to the notes front matter.
+                Annex::Created {
+                    force,
+                    no_git,
+                    diff,
+                } => {
+                    let added = self.add_timestamps(true, *force, *no_git, *diff).await?;
+                    if *diff {
+                        summary.count("found to add", added, "creation time");
+                    } else {
+                        summary.count("added", added, "creation time");
+                    }
+                }
+
+                // Add the modification time to the notes front matter.
+                Annex::Modified { no_git, diff } => {
+                    let added = self.add_timestamps(false, true, *no_git, *diff).await?;
+                    if *diff {
+                        summary.count("found to add", added, "modification time");
+                    } else {
+                        summary.count("added", added, "modification time");
+                    }
+                }
             },
+
+            // Remove the generated artifacts.
+            Command::Clean { empty_dirs, yes } => {
+                if empty_dirs && !yes {
+                    return Err(Error::NotConfirmed(
+                        "removing of the notes set directories".to_string(),
+                    ));
+                }
+
+                let removed = self.clean(empty_dirs).await?;
+                summary.count("removed", removed, "artifact");
+            }
+
+            // Print the link graph of the notes set.
+            Command::Graph {
+                attachments,
+                format,
+            } => {
+                let graph = self.link_graph(attachments).await?;
+                match format {
+                    graph::GraphFormat::Dot => println!("{}", graph.to_dot()),
+                    graph::GraphFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&graph)?)
+                    }
+                }
+            }
+
+            // Export the notes into the directory.
+            Command::Export {
+                strip_frontmatter,
+                ref destination,
+            } => {
+                let exported = self.export(destination, strip_frontmatter).await?;
+                summary.count("exported", exported, "note");
+            }
+
+            // Print the effective paths of the features.
+            Command::CheckConfig => {
+                let (checked, missing) = self.check_config()?;
+                summary.count("checked", checked, "path");
+                if missing > 0 {
+                    summary.count("found", missing, "missing path");
+                }
+            }
+
+            // Run the configured tasks on their schedules.
+            Command::Watch { run_now } => {
+                let runs = self.watch(run_now).await?;
+                summary.count("run", runs, "watched task");
+            }
+
+            // The standalone commands have been processed before.
+            Command::Config { .. }
+            | Command::Init { .. }
+            | Command::Migrate
+            | Command::Undo
+            | Command::Version { .. } => {}
+        }
+
+        // Commit the changes of the notes set written by the command only.
+        // The dry run writes nothing, it is never committed to be sure.
+        let dry_run = matches!(
+            command,
+            Command::Grab {
+                note: Some(Note::TWiR { dry_run: true, .. }),
+                ..
+            }
+        );
+        let message = match args.commit {
+            _ if dry_run => None,
+            Some(ref message) => Some(message.as_str()),
+            None if self.config.git_auto_commit() => Some(Self::COMMIT_MESSAGE),
+            None => None,
+        };
+        if let Some(message) = message {
+            let mut paths = self.journal.paths();
+            paths.extend(self.changes.paths());
+
+            let committed = git::commit(self.config.root(), message, paths).await?;
+            if !committed.is_empty() {
+                summary.count("committed", committed.len(), "file");
+            }
+        }
+
+        if !args.quiet && !summary.is_empty() {
+            println!("{}", summary);
         }
 
         Ok(())
     }
 
+    ///
+    /// Check if the file is the note by its extension.
+    ///
+    #[inline]
+    fn is_note(&self, path: &Path) -> bool {
+        walk::is_note(path, &self.config.note_extensions())
+    }
+
+    ///
+    /// Read the note to rewrite it, `None` if the note cannot be decoded.
+    ///
+    async fn read_note(&self, path: &Path) -> io::Result<Option<String>> {
+        walk::read_note(path, self.config.fallback_encoding()).await
+    }
+
+    ///
+    /// Read the note to scan it for the references.
+    ///
+    async fn scan_note(&self, path: &Path) -> io::Result<String> {
+        walk::scan_note(path, self.config.fallback_encoding()).await
+    }
+
     ///
     /// Repair wiki references.
     ///
-    async fn repair_wiki_refs(&self) -> Result<(), Error> {
+    async fn repair_wiki_refs(&self) -> Result<usize, Error> {
         let re = Arc::new(
             Regex::new(
                 r"\[\[\s*(?P<file>[A-Za-z\d\-\.]+(?:\s+[\w\d\-_\.\(\)]+)*)\s*\|\s+(?P<descr>.[^\[\]]+)\s*?\]\]",
             )
             .unwrap(),
         );
-        let errors = stream::iter(WalkDir::new(self.config.root()).into_iter())
+        let mix = stream::iter(self.walk(self.config.root()))
             .filter_map(|e| async move {
                 if let Ok(e) = e {
-                    if e.path().exists()
-                        && e.path().is_file()
-                        && e.path().extension().and_then(OsStr::to_str) == Some("md")
-                    {
+                    if e.path().exists() && e.path().is_file() && self.is_note(e.path()) {
                         return Some(e);
                     }
                 }
@@ -175,599 +779,2006 @@ impl Application {
                 None
             })
             .zip(stream::iter(repeat_with(|| re.clone())))
-            .then(|(e, re)| async move {
+            .map(|(e, re)| async move {
                 log::trace!("Start processing of the file \"{}\"", e.path().display());
-                let mut buffer = String::new();
-                {
-                    let mut file = File::open(e.path()).await?;
-                    file.read_to_string(&mut buffer).await?;
-                }
+                let buffer = match self.read_note(e.path()).await? {
+                    Some(buffer) => buffer,
+                    None => return Ok(false),
+                };
 
                 let content = re.replace_all(&buffer, "[[$file|$descr]]");
-                {
+                let repaired = matches!(content, Cow::Owned(_));
+                if repaired {
+                    self.journal.record_write(e.path()).await?;
                     let mut file = File::create(e.path()).await?;
                     file.write_all(content.as_bytes()).await?;
                 }
 
                 log::trace!("Finish processing of the file \"{}\"", e.path().display());
-                Ok(()) as Result<(), Error>
+                Ok(repaired) as Result<bool, Error>
             })
-            .filter_map(|r| async move { r.err() })
+            .buffer_unordered(self.config.io_jobs())
             .collect::<Vec<_>>()
             .await;
 
+        let mut repaired = 0;
+        let mut errors: Vec<Error> = Vec::new();
+        for r in mix.into_iter() {
+            match r {
+                Ok(true) => repaired += 1,
+                Ok(false) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
         if errors.is_empty() {
-            Ok(())
+            Ok(repaired)
         } else {
-            Err(Error::MultipleExecutorsError(errors))
+            Err(Error::multiple(errors))
         }
     }
 
     ///
-    /// Remove unused files.
+    /// Remove unused files which are older than the given age.
     ///
-    async fn remove_unused_files(&self) -> Result<(), Error> {
-        let files = Arc::new(
-            stream::iter(WalkDir::new(self.config.files_path()).into_iter())
-                .filter_map(|e| async move {
-                    if let Ok(e) = e {
-                        if e.path().exists() && e.path().is_file() {
-                            if let Some(file_name) = e.path().file_name().and_then(OsStr::to_str) {
-                                return Some((file_name.to_string(), PathBuf::from(e.path())));
-                            }
+    async fn remove_unused_files(&self, older_than: Option<age::Age>) -> Result<usize, Error> {
+        let unused = self.find_unused_files(older_than).await?;
+        if !unused.is_empty() {
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Unused Files"]);
+            for path in &unused {
+                table.add_row(row![path.display()]);
+                self.journal.record_delete(path.as_path()).await?;
+                tokio::fs::remove_file(path.as_path()).await?;
+            }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
+        }
+
+        Ok(unused.len())
+    }
+
+    ///
+    /// Print the absolute paths the features read and write with all the
+    /// defaults applied. Returns the numbers of the checked and the missing paths.
+    ///
+    fn check_config(&self) -> Result<(usize, usize), Error> {
+        let paths = [
+            ("Root", Cow::Borrowed(self.config.root())),
+            ("Files", self.config.files_path()),
+            ("Daily", self.config.daily_path()),
+            ("APoD", self.config.apod_path()),
+            ("TWiR", self.config.twir_path()),
+            ("Raindrop", self.config.raindrop_path()),
+            ("Omnivore", self.config.omnivore_path()),
+            ("Templates", self.config.templates_path()),
+            ("Cache", Cow::Borrowed(self.cache_path.as_path())),
+        ];
+
+        // Create the table.
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        table.set_titles(row![b => "Feature", "Path", "Status"]);
+        let mut missing = 0;
+        for (feature, path) in paths.iter() {
+            let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+            let status = if path.is_dir() {
+                "ok"
+            } else {
+                missing += 1;
+                "missing"
+            };
+            table.add_row(row![feature, path.display(), status]);
+        }
+
+        // Print the table to stdout
+        self.print_table(&table)?;
+
+        Ok((paths.len(), missing))
+    }
+
+    ///
+    /// Build the link graph of the notes set, the attachments are included on demand.
+    ///
+    async fn link_graph(&self, attachments: bool) -> Result<graph::Graph, Error> {
+        let root = self.config.root();
+
+        // The nodes are sorted for the stable output.
+        let mut graph = graph::Graph::new(&self.config.note_extensions());
+        let mut notes: Vec<(String, PathBuf)> = Vec::new();
+        for e in self
+            .entries(self.walker(root).sort_by_file_name())
+            .filter_map(Result::ok)
+        {
+            if !e.path().is_file() {
+                continue;
+            }
+
+            let id = graph::node_id(root, e.path());
+            if self.is_note(e.path()) {
+                graph.add_node(id.as_str(), graph::NodeKind::Note);
+                notes.push((id, PathBuf::from(e.path())));
+            } else if attachments && !id.split('/').any(|name| name.starts_with('.')) {
+                graph.add_node(id.as_str(), graph::NodeKind::Attachment);
+            }
+        }
+
+        let mix = stream::iter(notes)
+            .map(|(id, path)| async move {
+                let content = self.scan_note(path.as_path()).await?;
+                Ok((id, content)) as Result<(String, String), Error>
+            })
+            .buffer_unordered(self.config.io_jobs())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut errors: Vec<Error> = Vec::new();
+        for r in mix.into_iter() {
+            match r {
+                Ok((id, content)) => {
+                    for target in graph::wiki_links(content.as_str()) {
+                        if !graph.add_link(id.as_str(), target) {
+                            log::debug!(
+                                "The link \"{}\" of the note \"{}\" is not resolved",
+                                target,
+                                id
+                            );
                         }
                     }
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(graph)
+        } else {
+            Err(Error::multiple(errors))
+        }
+    }
+
+    ///
+    /// Copy the notes into the destination directory keeping their relative
+    /// paths, the front matter is stripped on demand. Returns the number of
+    /// the exported notes.
+    ///
+    async fn export(&self, destination: &Path, strip_frontmatter: bool) -> Result<usize, Error> {
+        let root = self.config.root();
+        let destination = std::env::current_dir()?.join(destination);
+        if destination.starts_with(root) {
+            return Err(Error::IllegalPath(destination.display().to_string()));
+        }
+
+        let notes: Vec<PathBuf> = self
+            .walk(root)
+            .filter_map(Result::ok)
+            .filter(|e| e.path().is_file() && self.is_note(e.path()))
+            .map(|e| PathBuf::from(e.path()))
+            .collect();
+
+        let destination = &destination;
+        let mix = stream::iter(notes)
+            .map(|path| async move {
+                let content = match self.read_note(path.as_path()).await? {
+                    Some(content) => content,
+                    None => return Ok(false),
+                };
+                let content = if strip_frontmatter {
+                    meta::Metadata::strip(content.as_str())
+                } else {
+                    content.as_str()
+                };
+
+                let target = destination.join(path.strip_prefix(root).unwrap_or(&path));
+                if let Some(parent) = target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(target.as_path(), content).await?;
+                log::trace!(
+                    "The note \"{}\" has been exported to \"{}\"",
+                    path.display(),
+                    target.display()
+                );
+
+                Ok(true) as Result<bool, Error>
+            })
+            .buffer_unordered(self.config.io_jobs())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut exported = 0;
+        let mut errors: Vec<Error> = Vec::new();
+        for r in mix.into_iter() {
+            match r {
+                Ok(true) => exported += 1,
+                Ok(false) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(exported)
+        } else {
+            Err(Error::multiple(errors))
+        }
+    }
+
+    ///
+    /// Show the notes having no links except the excluded ones.
+    ///
+    async fn show_orphans(&self, exclude: &[String]) -> Result<usize, Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in exclude {
+            let glob = Glob::new(pattern).map_err(|_| Error::IllegalGlob(pattern.clone()))?;
+            builder.add(glob);
+        }
+        let exclude = builder
+            .build()
+            .map_err(|_| Error::IllegalGlob(exclude.join(", ")))?;
+
+        // The links to the attachments are the outbound ones too.
+        let graph = self.link_graph(true).await?;
+        let orphans: Vec<&str> = graph
+            .orphans()
+            .into_iter()
+            .filter(|id| !exclude.is_match(id))
+            .collect();
+
+        if !orphans.is_empty() {
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Orphan Notes"]);
+            for id in orphans.iter() {
+                table.add_row(row![id]);
+            }
+
+            pager::page(table.to_string().as_str()).await?;
+        }
+
+        Ok(orphans.len())
+    }
+
+    ///
+    /// Show unused files without removing them.
+    ///
+    async fn show_unused_files(&self, older_than: Option<age::Age>) -> Result<usize, Error> {
+        let unused = self.find_unused_files(older_than).await?;
+        if !unused.is_empty() {
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Unused Files"]);
+            for path in &unused {
+                table.add_row(row![path.display()]);
+            }
+
+            pager::page(table.to_string().as_str()).await?;
+        }
+
+        Ok(unused.len())
+    }
+
+    ///
+    /// Find the files which are not referred by the notes.
+    ///
+    async fn find_unused_files(&self, older_than: Option<age::Age>) -> Result<Vec<PathBuf>, Error> {
+        let files = Arc::new(
+            stream::iter(self.walk(self.config.files_path()))
+                .filter_map(|e| async move {
+                    if let Ok(e) = e {
+                        if e.path().exists() && e.path().is_file() {
+                            if let Some(file_name) = e.path().file_name().and_then(OsStr::to_str) {
+                                return Some((file_name.to_string(), PathBuf::from(e.path())));
+                            }
+                        }
+                    }
+
+                    None
+                })
+                .collect::<HashMap<String, PathBuf>>()
+                .await,
+        );
+
+        // Reuse the references of the unchanged notes.
+        let index = match self.index_file {
+            Some(ref index_file) => index::NoteIndex::load(index_file).await?,
+            None => index::NoteIndex::new(PathBuf::new()),
+        };
+        let index = Arc::new(index);
+        let cached = index.covers(files.keys().map(String::as_str));
+
+        let mix = stream::iter(self.walk(self.config.root()))
+            .filter_map(|e| async move {
+                if let Ok(e) = e {
+                    if e.path().exists() && e.path().is_file() && self.is_note(e.path()) {
+                        return Some(e);
+                    }
+                }
+
+                None
+            })
+            .zip(stream::iter(repeat_with(|| (files.clone(), index.clone()))))
+            .map(|(e, (files, index))| async move {
+                let modified = fs::metadata(e.path()).await?.modified()?;
+                if let Some(links) = index.links(e.path(), modified).filter(|_| cached) {
+                    log::trace!(
+                        "Reuse the references of the file \"{}\"",
+                        e.path().display()
+                    );
+                    return Ok((PathBuf::from(e.path()), modified, links.to_vec()));
+                }
+
+                log::trace!("Start processing of the file \"{}\"", e.path().display());
+                let content = self.scan_note(e.path()).await?;
+
+                let mut links: Vec<String> = Vec::new();
+                for (file_name, _) in files.iter() {
+                    if content.contains(file_name.as_str()) {
+                        links.push(file_name.clone());
+                    }
+                }
+                links.shrink_to_fit();
+
+                log::trace!("Finish processing of the file \"{}\"", e.path().display());
+                Ok((PathBuf::from(e.path()), modified, links))
+                    as Result<(PathBuf, SystemTime, Vec<String>), Error>
+            })
+            .buffer_unordered(self.config.io_jobs())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut links: HashSet<String> = HashSet::new();
+        let mut errors: Vec<Error> = Vec::new();
+        let mut scanned = self.index_file.as_ref().map(index::NoteIndex::new);
+        for r in mix.into_iter() {
+            match r {
+                Ok((path, modified, l)) => {
+                    links.extend(l.iter().cloned());
+                    if let Some(ref mut scanned) = scanned {
+                        scanned.insert(path, modified, l);
+                    }
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if errors.is_empty() {
+            if let Some(mut scanned) = scanned {
+                scanned.set_files(files.keys().cloned());
+                scanned.save().await?;
+            }
+
+            let mut unused: Vec<PathBuf> = Vec::new();
+            for (name, path) in files.iter() {
+                if links.contains(name) {
+                    continue;
+                }
+
+                if let Some(age) = older_than {
+                    // The files modified in the future are considered as fresh ones.
+                    let modified = fs::metadata(path.as_path()).await?.modified()?;
+                    if modified.elapsed().unwrap_or_default() < age.duration() {
+                        log::debug!(
+                            "The unused file \"{}\" is too fresh to be removed",
+                            path.display()
+                        );
+                        continue;
+                    }
+                }
+
+                unused.push(PathBuf::from(path));
+            }
+            unused.sort();
+
+            return Ok(unused);
+        }
+
+        Err(Error::multiple(errors))
+    }
+
+    ///
+    /// Rename attached files.
+    ///
+    async fn rename_attached_files(&self) -> Result<usize, Error> {
+        self.rename_attached_files_with(|_| Uuid::new_v4()).await
+    }
+
+    // Rename the attached files to the UUIDs generated for their paths.
+    async fn rename_attached_files_with<G>(&self, uuid: G) -> Result<usize, Error>
+    where
+        G: Fn(&Path) -> Uuid,
+    {
+        let uuid = &uuid;
+        let re = Arc::new(
+            Regex::new(
+                r"^[\dA-Fa-f]{8}\-[\dA-Fa-f]{4}\-[\dA-Fa-f]{4}\-[\dA-Fa-f]{4}-[\dA-Fa-f]{12}$",
+            )
+            .unwrap(),
+        );
+
+        let files_path = self.config.files_path();
+        let files_path = files_path.as_ref();
+        let files: HashMap<String, entry::FileEntry> = stream::iter(self.walk(files_path))
+            .filter_map(|e| async move {
+                if let Ok(e) = e {
+                    if e.path().exists() && e.path().is_file() {
+                        return Some(e);
+                    }
+                };
+
+                None
+            })
+            .zip(stream::iter(repeat_with(|| re.clone())))
+            .filter_map(|(e, re)| async move {
+                let stem = e.path().file_stem().and_then(OsStr::to_str);
+                if let Some(stem) = stem {
+                    if !re.is_match(stem) {
+                        if let Some(entry) = entry::FileEntry::new(e.path(), uuid(e.path())) {
+                            let entry = entry.shard(files_path, self.config.files_shard());
+                            return Some((stem.to_string(), entry));
+                        }
+                    }
+                }
+
+                None
+            })
+            .collect()
+            .await;
+
+        // The files are renamed before the notes are rewritten, so the links
+        // are changed only for the files which have been actually renamed.
+        let renames = stream::iter(files)
+            .map(|(stem, fe)| async move {
+                fe.rename().await?;
+                self.journal.record_rename(fe.old_path(), fe.new_path());
+                Ok((stem, fe)) as Result<(String, entry::FileEntry), Error>
+            })
+            .buffer_unordered(self.config.io_jobs())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut renamed: HashMap<String, entry::FileEntry> = HashMap::new();
+        let mut errors: Vec<Error> = Vec::new();
+        for r in renames.into_iter() {
+            match r {
+                Ok((stem, fe)) => {
+                    renamed.insert(stem, fe);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        let files = Arc::new(renamed);
+
+        let rewrites = stream::iter(self.walk(self.config.root()))
+            .filter_map(|e| async move {
+                if let Ok(e) = e {
+                    if e.path().exists() && e.path().is_file() && self.is_note(e.path()) {
+                        return Some(e);
+                    }
+                }
+
+                None
+            })
+            .zip(stream::iter(repeat_with(|| files.clone())))
+            .map(|(e, files)| async move {
+                log::trace!("Start processing of the file \"{}\"", e.path().display());
+                let buffer = match self.read_note(e.path()).await? {
+                    Some(buffer) => buffer,
+                    None => return Ok(()),
+                };
+
+                let mut content = buffer.clone();
+                let mut dirty = false;
+                for (stem, fe) in files.iter() {
+                    if content.contains(stem) {
+                        content = content.replace(fe.old_name(), fe.new_name().as_ref());
+                        dirty = true;
+                    }
+                }
+
+                if dirty {
+                    self.journal.record_write(e.path()).await?;
+                    let mut file = File::create(e.path()).await?;
+                    file.write_all(content.as_bytes()).await?;
+                }
+
+                log::trace!("Finish processing of the file \"{}\"", e.path().display());
+                Ok(()) as Result<(), Error>
+            })
+            .buffer_unordered(self.config.io_jobs())
+            .filter_map(|r| async move { r.err() })
+            .collect::<Vec<_>>()
+            .await;
+        errors.extend(rewrites);
+
+        if errors.is_empty() {
+            Ok(files.len())
+        } else {
+            Err(Error::multiple(errors))
+        }
+    }
+
+    ///
+    /// Move the attached files into the configured shard subdirectories and
+    /// rewrite the wiki links to them. Returns the number of the moved files.
+    ///
+    async fn reshard_files(&self) -> Result<usize, Error> {
+        let files_path = self.config.files_path();
+        let shard = self.config.files_shard();
+
+        // Only the files named by the UUIDs are moved, the other
+        // subdirectories of the files directory are kept as they are.
+        let mut moves: Vec<(PathBuf, PathBuf, String, String)> = Vec::new();
+        let mut new_names: HashSet<String> = HashSet::new();
+        for e in self.walk(&files_path).filter_map(Result::ok) {
+            let file_name = match e.file_name().to_str() {
+                Some(file_name) if e.file_type().is_file() && !file_name.starts_with('.') => {
+                    file_name
+                }
+                _ => continue,
+            };
+            let old_name = match e.path().strip_prefix(&files_path) {
+                Ok(path) if entry::is_shardable(path) => graph::node_id(&files_path, e.path()),
+                _ => continue,
+            };
+
+            let new_name = entry::shard_name(file_name, shard);
+            let new_path = files_path.join(new_name.as_str());
+            if new_path == e.path() {
+                continue;
+            }
+            if new_path.exists() || new_names.contains(new_name.as_str()) {
+                log::warn!(
+                    "The file \"{}\" cannot be moved to \"{}\", the name is taken",
+                    e.path().display(),
+                    new_path.display()
+                );
+                continue;
+            }
+
+            new_names.insert(new_name.clone());
+            moves.push((PathBuf::from(e.path()), new_path, old_name, new_name));
+        }
+
+        // The files are moved before the notes are rewritten, so the links
+        // are changed only for the files which have been actually moved.
+        let mut moved: HashMap<String, String> = HashMap::new();
+        let mut errors: Vec<Error> = Vec::new();
+        for (old_path, new_path, old_name, new_name) in moves.into_iter() {
+            let result: Result<(), Error> = async {
+                if let Some(parent) = new_path.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                fs::rename(old_path.as_path(), new_path.as_path()).await?;
+                Ok(())
+            }
+            .await;
+            if let Err(e) = result {
+                errors.push(e);
+                continue;
+            }
+
+            self.journal
+                .record_rename(old_path.as_path(), new_path.as_path());
+            log::debug!(
+                "The file \"{}\" has been moved to \"{}\"",
+                old_path.display(),
+                new_path.display()
+            );
+            moved.insert(old_name, new_name);
+        }
+
+        if !moved.is_empty() {
+            let files_dir = graph::node_id(self.config.root(), &files_path);
+            self.rewrite_notes(|_, content| {
+                match entry::reshard_links(content, files_dir.as_str(), &moved) {
+                    Cow::Owned(content) => Some(content),
+                    Cow::Borrowed(_) => None,
+                }
+            })
+            .await?;
+        }
+
+        if errors.is_empty() {
+            Ok(moved.len())
+        } else {
+            Err(Error::multiple(errors))
+        }
+    }
+
+    ///
+    /// Lowercase the names of the attached files named by the uppercase UUIDs
+    /// and rewrite the wiki links to them. Returns the number of the renamed files.
+    ///
+    async fn canonical_uuid_files(&self) -> Result<usize, Error> {
+        let files_path = self.config.files_path();
+        let files: Vec<entry::FileEntry> = self
+            .walk(&files_path)
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| entry::FileEntry::canonical(e.path()))
+            .collect();
+
+        // The links are rewritten only for the files which have been renamed.
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        let mut errors: Vec<Error> = Vec::new();
+        for fe in files.iter() {
+            if let Err(e) = fe.rename().await {
+                errors.push(e);
+                continue;
+            }
+
+            self.journal.record_rename(fe.old_path(), fe.new_path());
+            let new_name = fe
+                .new_path()
+                .strip_prefix(&files_path)
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_else(|_| fe.new_name().to_string());
+            log::debug!(
+                "The file \"{}\" has been renamed to \"{}\"",
+                fe.old_path().display(),
+                new_name
+            );
+            renamed.insert(graph::node_id(&files_path, fe.old_path()), new_name);
+        }
+
+        if !renamed.is_empty() {
+            let files_dir = graph::node_id(self.config.root(), &files_path);
+            self.rewrite_notes(|_, content| {
+                match entry::reshard_links(content, files_dir.as_str(), &renamed) {
+                    Cow::Owned(content) => Some(content),
+                    Cow::Borrowed(_) => None,
+                }
+            })
+            .await?;
+        }
+
+        if errors.is_empty() {
+            Ok(renamed.len())
+        } else {
+            Err(Error::multiple(errors))
+        }
+    }
+
+    ///
+    /// Set the default banners of the notes which have no banner.
+    ///
+    async fn apply_banners(&self) -> Result<usize, Error> {
+        if !self.config.has_banners() {
+            log::warn!("There are no default banners in the configuration");
+            return Ok(0);
+        }
+
+        let keys = self.config.keys();
+        let select = |_: &Path, metadata: &meta::Metadata| {
+            metadata.banner(keys).is_none()
+                && metadata
+                    .note_type(keys)
+                    .and_then(|note_type| self.config.banner(note_type))
+                    .is_some()
+        };
+        self.rewrite_selected_notes(self.config.root(), Some(select), |path, content| {
+            let (mut metadata, body) = meta::Metadata::split(content);
+            let banner = match metadata.note_type(keys) {
+                Some(note_type) if metadata.banner(keys).is_none() => {
+                    self.config.banner(note_type)?
+                }
+                _ => return None,
+            };
+
+            metadata.set_banner(keys, banner.as_ref());
+            log::debug!(
+                "The banner \"{}\" has been applied to the note \"{}\"",
+                banner,
+                path.display()
+            );
+
+            Some(metadata.embed(body))
+        })
+        .await
+    }
+
+    ///
+    /// Collapse the runs of the blank lines in the notes.
+    ///
+    async fn tidy_notes(&self) -> Result<usize, Error> {
+        self.rewrite_notes(|_, content| match tidy::collapse_blank_lines(content) {
+            Cow::Owned(content) => Some(content),
+            Cow::Borrowed(_) => None,
+        })
+        .await
+    }
+
+    ///
+    /// End the notes with exactly one line break. Returns the number of
+    /// the fixed notes.
+    ///
+    async fn fix_final_newlines(&self) -> Result<usize, Error> {
+        self.rewrite_notes(|_, content| match tidy::final_newline(content) {
+            Cow::Owned(content) => Some(content),
+            Cow::Borrowed(_) => None,
+        })
+        .await
+    }
+
+    ///
+    /// Rewrite the front matter tags of the notes in the configured style.
+    /// Returns the number of the rewritten notes.
+    ///
+    async fn normalize_tags(&self) -> Result<usize, Error> {
+        let key = self.config.keys().tags.as_str();
+        let style = self.config.tags_style();
+
+        let select = |_: &Path, metadata: &meta::Metadata| metadata.list(key).is_some();
+        self.rewrite_selected_notes(self.config.root(), Some(select), |_, content| {
+            let (mut metadata, body) = meta::Metadata::split(content);
+            metadata
+                .normalize_list(key, style)
+                .then(|| metadata.embed(body))
+        })
+        .await
+    }
+
+    ///
+    /// Turn the wiki links to the attached files into the embeds reporting
+    /// every turned link. Returns the number of the fixed notes.
+    ///
+    async fn fix_embeds(&self) -> Result<usize, Error> {
+        let root = self.config.root();
+        let mut attachments: HashSet<String> = HashSet::new();
+        for entry in self.walk(self.config.files_path()).flatten() {
+            if entry.file_type().is_file() && !self.is_note(entry.path()) {
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                attachments.insert(name);
+                attachments.insert(graph::node_id(root, entry.path()).to_lowercase());
+            }
+        }
+
+        let fixed: Mutex<Vec<(PathBuf, String)>> = Mutex::new(Vec::new());
+        let count = self
+            .rewrite_notes(|path, content| {
+                let (content, targets) = tidy::embed_attachments(content, |target| {
+                    let target = target.trim_start_matches('/');
+                    Path::new(target).extension().is_some()
+                        && !self.is_note(Path::new(target))
+                        && attachments.contains(target.to_lowercase().as_str())
+                });
+
+                let mut fixed = fixed.lock().unwrap();
+                for target in targets {
+                    fixed.push((PathBuf::from(path), target));
+                }
+
+                match content {
+                    Cow::Owned(content) => Some(content),
+                    Cow::Borrowed(_) => None,
+                }
+            })
+            .await?;
+
+        let mut fixed = fixed.into_inner().unwrap();
+        if !fixed.is_empty() {
+            fixed.sort();
+
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Note", "Embedded File"]);
+            for (path, target) in &fixed {
+                table.add_row(row![path.display(), target]);
+            }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
+        }
+
+        Ok(count)
+    }
+
+    ///
+    /// Rewrite the YouTube iframes of the Astronomy Picture of the Day notes
+    /// in the configured video style reporting every changed note. Returns
+    /// the number of the changed notes.
+    ///
+    async fn fix_apod_iframes(&self) -> Result<usize, Error> {
+        let apod_path = self.config.apod_path();
+        let style = self.config.apod_video_embed();
+
+        let fixed: Mutex<Vec<(PathBuf, usize)>> = Mutex::new(Vec::new());
+        let count = self
+            .rewrite_notes_in(apod_path.as_ref(), |path, content| {
+                match style.normalize(content) {
+                    (Cow::Owned(content), videos) => {
+                        fixed.lock().unwrap().push((PathBuf::from(path), videos));
+                        Some(content)
+                    }
+                    (Cow::Borrowed(_), _) => None,
+                }
+            })
+            .await?;
+
+        let mut fixed = fixed.into_inner().unwrap();
+        if !fixed.is_empty() {
+            fixed.sort();
+
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Note", "Videos"]);
+            for (path, videos) in &fixed {
+                table.add_row(row![path.display(), r -> videos]);
+            }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
+        }
+
+        Ok(count)
+    }
+
+    ///
+    /// Report the notes with the duplicate front matter keys and optionally
+    /// fix them. Returns the numbers of the found and the fixed notes.
+    ///
+    async fn dedup_keys(&self, fix: bool) -> Result<(usize, usize), Error> {
+        let found: Mutex<Vec<(PathBuf, String)>> = Mutex::new(Vec::new());
+        let fixed = self
+            .rewrite_notes(|path, content| {
+                let (mut metadata, body) = meta::Metadata::split(content);
+                let duplicates = metadata.duplicate_keys().join(", ");
+                if duplicates.is_empty() {
+                    return None;
+                }
+
+                found
+                    .lock()
+                    .unwrap()
+                    .push((PathBuf::from(path), duplicates));
+                if !fix {
+                    return None;
+                }
+
+                metadata.dedup_keys();
+                Some(metadata.embed(body))
+            })
+            .await?;
+
+        let mut found = found.into_inner().unwrap();
+        if !found.is_empty() {
+            found.sort();
+
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Note", "Duplicate Keys"]);
+            for (path, duplicates) in &found {
+                table.add_row(row![path.display(), duplicates]);
+            }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
+        }
+
+        Ok((found.len(), fixed))
+    }
+
+    ///
+    /// Report the back-links of the daily notes which do not resolve to the
+    /// APoD and TWiR notes and optionally fix the ones differing from the note
+    /// names in case or spacing only. The notes are named with the affix.
+    /// Returns the numbers of the found and the fixed daily notes.
+    ///
+    async fn fix_daily_links(
+        &self,
+        apply: bool,
+        affix: &entry::NameAffix,
+    ) -> Result<(usize, usize), Error> {
+        // The back-links are compared ignoring the case and the spacing.
+        let key = |name: &str| {
+            name.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        };
+        let stem_key = |path: PathBuf| {
+            key(affix
+                .apply(path)
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default())
+        };
+
+        // The APoD back-link refers to the note of the daily note date, the
+        // TWiR one to the note of any issue.
+        let twir_key = stem_key(
+            self.config
+                .twir_path()
+                .join(format!("{}{{number}}.md", self.config.twir_prefix())),
+        );
+        let (twir_prefix, twir_suffix) = twir_key.split_once("{number}").unwrap_or_default();
+        let twir_re = Regex::new(
+            format!(
+                r"^{}\d+{}$",
+                regex::escape(twir_prefix),
+                regex::escape(twir_suffix)
+            )
+            .as_str(),
+        )
+        .unwrap();
+
+        let mut names: HashMap<String, String> = HashMap::new();
+        for path in [self.config.apod_path(), self.config.twir_path()] {
+            for e in self.walk(&path).filter_map(Result::ok) {
+                if e.file_type().is_file() && self.is_note(e.path()) {
+                    if let Some(stem) = e.path().file_stem().and_then(OsStr::to_str) {
+                        names.insert(key(stem), stem.to_string());
+                    }
+                }
+            }
+        }
+
+        let daily_path = self.config.daily_path();
+        let found: Mutex<Vec<(PathBuf, String, Option<String>)>> = Mutex::new(Vec::new());
+        let fixed = self
+            .rewrite_notes(|path, content| {
+                if !path.starts_with(&daily_path) {
+                    return None;
+                }
+
+                let apod_key = path
+                    .file_stem()
+                    .and_then(OsStr::to_str)
+                    .map(|date| stem_key(self.config.apod_note_path(date)));
+                let (content, replaced) = tidy::relink(content, |target| {
+                    let name = target.rsplit('/').next().unwrap_or(target);
+                    let name = name.strip_suffix(".md").unwrap_or(name);
+                    let name_key = key(name);
+                    match names.get(name_key.as_str()) {
+                        Some(stem) => Some(stem.clone()),
+                        None => {
+                            if apod_key.as_deref() == Some(name_key.as_str())
+                                || twir_re.is_match(name_key.as_str())
+                            {
+                                found.lock().unwrap().push((
+                                    PathBuf::from(path),
+                                    target.to_string(),
+                                    None,
+                                ));
+                            }
+                            None
+                        }
+                    }
+                });
+
+                let mut found = found.lock().unwrap();
+                for (target, stem) in replaced {
+                    found.push((PathBuf::from(path), target, Some(stem)));
+                }
+
+                match content {
+                    Cow::Owned(content) if apply => Some(content),
+                    _ => None,
+                }
+            })
+            .await?;
+
+        let mut found = found.into_inner().unwrap();
+        found.sort();
+        let mut notes: Vec<&Path> = found.iter().map(|(path, _, _)| path.as_path()).collect();
+        notes.dedup();
+        let notes = notes.len();
+
+        if !found.is_empty() {
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Daily Note", "Link", "Fix"]);
+            for (path, target, stem) in &found {
+                let fix = stem.as_deref().unwrap_or("unresolved");
+                table.add_row(row![path.display(), target, fix]);
+            }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
+        }
+
+        Ok((notes, fixed))
+    }
+
+    ///
+    /// Report the groups of the notes with the same body ignoring the front
+    /// matter and the whitespaces. Returns the number of the groups.
+    ///
+    async fn find_duplicates(&self) -> Result<usize, Error> {
+        // Collect the candidates by the body hash, the notes are only read.
+        let mut hashes: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for e in self.walk(self.config.root()).flatten() {
+            if !e.path().is_file() || !self.is_note(e.path()) {
+                continue;
+            }
+
+            let content = match self.read_note(e.path()).await? {
+                Some(content) => content,
+                None => continue,
+            };
+            if let Some(hash) = tidy::body_hash(content.as_str()) {
+                hashes
+                    .entry(hash)
+                    .or_default()
+                    .push(PathBuf::from(e.path()));
+            }
+        }
+
+        // Compare the bodies of the candidates, so the hash collisions are dropped.
+        let mut groups: Vec<Vec<PathBuf>> = Vec::new();
+        for paths in hashes.into_values().filter(|paths| paths.len() > 1) {
+            let mut notes: Vec<(PathBuf, String)> = Vec::new();
+            for path in paths {
+                if let Some(content) = self.read_note(path.as_path()).await? {
+                    notes.push((path, content));
+                }
+            }
+            groups.extend(tidy::duplicates(&notes));
+        }
+        if !groups.is_empty() {
+            groups.iter_mut().for_each(|paths| paths.sort());
+            groups.sort();
+
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+            table.set_titles(row![b => "Group", "Duplicate Notes"]);
+            for (number, paths) in groups.iter().enumerate() {
+                for path in paths {
+                    table.add_row(row![number + 1, path.display()]);
+                }
+            }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
+        }
+
+        Ok(groups.len())
+    }
+
+    ///
+    /// Prune the Astronomy Picture of the Day notes keeping the given number
+    /// of the most recent ones. The images are removed with the notes unless
+    /// they are referred by the other notes. Nothing is removed without the
+    /// confirmation. Returns the number of the pruned notes.
+    ///
+    async fn prune_apod(&self, keep: usize, confirmed: bool) -> Result<usize, Error> {
+        let keys = self.config.keys();
+
+        // Order the notes by the date of the front matter.
+        let mut notes: Vec<(NaiveDate, PathBuf, String)> = Vec::new();
+        for e in self.walk(self.config.apod_path()).flatten() {
+            if !e.path().is_file() || !self.is_note(e.path()) {
+                continue;
+            }
+
+            let content = self.scan_note(e.path()).await?;
+            let date = meta::Metadata::split(content.as_str())
+                .0
+                .get(keys.date.as_str())
+                .and_then(|date| {
+                    NaiveDate::parse_from_str(date, self.config.date_format())
+                        .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+                        .ok()
+                });
+            match date {
+                Some(date) => notes.push((date, PathBuf::from(e.path()), content)),
+                None => log::warn!("The note \"{}\" has no date", e.path().display()),
+            }
+        }
+        notes.sort_by_key(|(date, _, _)| std::cmp::Reverse(*date));
+        let pruned: Vec<(NaiveDate, PathBuf, String)> = notes.into_iter().skip(keep).collect();
+        if pruned.is_empty() {
+            return Ok(0);
+        }
+
+        // Find the images referred by the pruned notes only.
+        let files: HashMap<String, PathBuf> = self
+            .walk(self.config.files_path())
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| {
+                let file_name = e.path().file_name().and_then(OsStr::to_str)?;
+                Some((file_name.to_string(), PathBuf::from(e.path())))
+            })
+            .collect();
+        let linked: HashSet<&str> = pruned
+            .iter()
+            .flat_map(|(_, _, content)| graph::linked_names(content.as_str()))
+            .collect();
+        let mut images: HashMap<&str, &Path> = files
+            .iter()
+            .filter(|(name, _)| linked.contains(name.as_str()))
+            .map(|(name, path)| (name.as_str(), path.as_path()))
+            .collect();
+
+        let pruned_paths: HashSet<&Path> =
+            pruned.iter().map(|(_, path, _)| path.as_path()).collect();
+        for e in self.walk(self.config.root()).flatten() {
+            if images.is_empty() {
+                break;
+            }
+            if !e.path().is_file() || !self.is_note(e.path()) || pruned_paths.contains(e.path()) {
+                continue;
+            }
+
+            let content = self.scan_note(e.path()).await?;
+            let linked = graph::linked_names(content.as_str());
+            images.retain(|name, _| !linked.contains(name));
+        }
+        let mut images: Vec<&Path> = images.into_values().collect();
+        images.sort();
+
+        // Create the table.
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        table.set_titles(row![b => "Date", "Pruned Files"]);
+        for (date, path, _) in &pruned {
+            table.add_row(row![date, path.display()]);
+        }
+        for path in &images {
+            table.add_row(row!["", path.display()]);
+        }
+
+        if confirmed {
+            for path in pruned_paths.iter().chain(images.iter()) {
+                self.journal.record_delete(path).await?;
+                fs::remove_file(path).await?;
+            }
+        }
+
+        // Print the table to stdout
+        self.print_table(&table)?;
+
+        Ok(pruned.len())
+    }
+
+    ///
+    /// Add the creation or the modification time to the front matter of
+    /// the notes. The times are taken from the git history of the notes set,
+    /// the untracked notes and the notes set outside of the repository use
+    /// the file times. Returns the number of the updated notes.
+    ///
+    async fn add_timestamps(
+        &self,
+        created: bool,
+        force: bool,
+        no_git: bool,
+        diff: bool,
+    ) -> Result<usize, Error> {
+        let history = if no_git {
+            git::History::default()
+        } else {
+            git::history(self.config.root()).await?
+        };
 
-                    None
-                })
-                .collect::<HashMap<String, PathBuf>>()
-                .await,
-        );
+        let keys = self.config.keys();
+        let key = if created {
+            keys.created.as_str()
+        } else {
+            keys.modified.as_str()
+        };
 
-        let mix = stream::iter(WalkDir::new(self.config.root()).into_iter())
-            .filter_map(|e| async move {
-                if let Ok(e) = e {
-                    if e.path().exists()
-                        && e.path().is_file()
-                        && e.path().extension().and_then(OsStr::to_str) == Some("md")
-                    {
-                        return Some(e);
-                    }
+        // The previewed notes are not written, so the journal is kept.
+        let diffs: Mutex<Vec<(PathBuf, String)>> = Mutex::new(Vec::new());
+        if !diff {
+            self.journal.begin().await;
+        }
+        let select = |_: &Path, metadata: &meta::Metadata| force || metadata.get(key).is_none();
+        let result = self
+            .rewrite_selected_notes(self.config.root(), Some(select), |path, content| {
+                let (mut metadata, body) = meta::Metadata::split(content);
+                if !force && metadata.get(key).is_some() {
+                    return None;
                 }
 
-                None
-            })
-            .zip(stream::iter(repeat_with(|| files.clone())))
-            .then(|(e, files)| async move {
-                log::trace!("Start processing of the file \"{}\"", e.path().display());
-                let mut content = String::new();
-                {
-                    let mut file = File::open(e.path()).await?;
-                    file.read_to_string(&mut content).await?;
+                let seconds = if created {
+                    history.created(path)
+                } else {
+                    history.modified(path)
+                };
+                let datetime = match seconds {
+                    Some(seconds) => Local.timestamp(seconds, 0),
+                    None => {
+                        let metadata = std::fs::metadata(path).ok()?;
+                        let time = if created {
+                            metadata.created().or_else(|_| metadata.modified())
+                        } else {
+                            metadata.modified()
+                        };
+                        DateTime::<Local>::from(time.ok()?)
+                    }
+                };
+
+                let value = datetime.format(Self::TIMESTAMP_FORMAT).to_string();
+                if metadata.get(key) == Some(value.as_str()) {
+                    return None;
                 }
+                metadata.set(key, value.as_str());
 
-                let mut links: Vec<String> = Vec::new();
-                for (file_name, _) in files.iter() {
-                    if content.contains(file_name.as_str()) {
-                        links.push(file_name.clone());
-                    }
+                let rewritten = metadata.embed(body);
+                if !diff {
+                    return Some(rewritten);
                 }
-                links.shrink_to_fit();
 
-                log::trace!("Finish processing of the file \"{}\"", e.path().display());
-                Ok(links) as Result<Vec<String>, Error>
+                let name = graph::node_id(self.config.root(), path);
+                let text = unified_diff(name.as_str(), content, rewritten.as_str());
+                diffs.lock().unwrap().push((PathBuf::from(path), text));
+
+                None
             })
-            .collect::<Vec<_>>()
             .await;
+        if !diff {
+            self.journal.save().await?;
+            return result;
+        }
+        result?;
 
-        let mut links: HashSet<String> = HashSet::new();
-        let mut errors: Vec<Error> = Vec::new();
-        for r in mix.into_iter() {
-            match r {
-                Ok(l) => links.extend(l),
-                Err(e) => errors.push(e),
-            }
+        let mut diffs = diffs.into_inner().unwrap();
+        diffs.sort();
+        for (_, text) in diffs.iter() {
+            print!("{}", text);
         }
 
-        if errors.is_empty() {
-            let unused: Vec<_> = files
-                .iter()
-                .filter_map(|(name, path)| {
-                    if links.contains(name) {
-                        None
-                    } else {
-                        Some(PathBuf::from(path))
-                    }
-                })
-                .collect();
+        Ok(diffs.len())
+    }
 
-            if !unused.is_empty() {
-                // Create the table.
-                let mut table = Table::new();
-                table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+    ///
+    /// Set the modification time of the notes changed by the current repair
+    /// to their file modification time. The note is written only if the time
+    /// differs. Returns the number of the touched notes.
+    ///
+    async fn touch_modified(&self) -> Result<usize, Error> {
+        let keys = self.config.keys();
 
-                table.set_titles(row!["Unused Files"]);
-                for path in &unused {
-                    table.add_row(row![path.display()]);
-                    tokio::fs::remove_file(path.as_path()).await?;
-                }
+        let mut touched = 0;
+        for path in self.journal.written_paths() {
+            if !path.is_file() || !self.is_note(path.as_path()) {
+                continue;
+            }
 
-                // Print the table to stdout
-                table.printstd();
+            let content = match self.read_note(path.as_path()).await? {
+                Some(content) => content,
+                None => continue,
+            };
+            let modified = tokio::fs::metadata(path.as_path()).await?.modified()?;
+            let value = DateTime::<Local>::from(modified)
+                .format(Self::TIMESTAMP_FORMAT)
+                .to_string();
+
+            let (mut metadata, body) = meta::Metadata::split(content.as_str());
+            if metadata.modified(keys) == Some(value.as_str()) {
+                continue;
             }
+            metadata.set_modified(keys, value.as_str());
 
-            return Ok(());
+            self.journal.record_write(path.as_path()).await?;
+            tokio::fs::write(path.as_path(), metadata.embed(body)).await?;
+            touched += 1;
         }
 
-        Err(Error::MultipleExecutorsError(errors))
+        Ok(touched)
     }
 
     ///
-    /// Rename attached files.
+    /// Rewrite the notes of the notes set, the rewriting function returns
+    /// `None` if the note is left intact. Returns the number of rewritten notes.
     ///
-    async fn rename_attached_files(&self) -> Result<(), Error> {
-        let re = Arc::new(
-            Regex::new(
-                r"^[\dA-Fa-f]{8}\-[\dA-Fa-f]{4}\-[\dA-Fa-f]{4}\-[\dA-Fa-f]{4}-[\dA-Fa-f]{12}$",
-            )
-            .unwrap(),
-        );
-
-        let files = Arc::new(
-            stream::iter(WalkDir::new(self.config.files_path()).into_iter())
-                .filter_map(|e| async move {
-                    if let Ok(e) = e {
-                        if e.path().exists() && e.path().is_file() {
-                            return Some(e);
-                        }
-                    };
-
-                    None
-                })
-                .zip(stream::iter(repeat_with(|| re.clone())))
-                .filter_map(|(e, re)| async move {
-                    let stem = e.path().file_stem().and_then(OsStr::to_str);
-                    if let Some(stem) = stem {
-                        if !re.is_match(stem) {
-                            if let Some(entry) = entry::FileEntry::new(e.path(), Uuid::new_v4()) {
-                                return Some((stem.to_string(), entry));
-                            }
-                        }
-                    }
+    async fn rewrite_notes<F>(&self, rewrite: F) -> Result<usize, Error>
+    where
+        F: Fn(&Path, &str) -> Option<String>,
+    {
+        self.rewrite_notes_in(self.config.root(), rewrite).await
+    }
 
-                    None
-                })
-                .collect::<HashMap<_, _>>()
-                .await,
-        );
+    ///
+    /// Rewrite the notes placed in the directory only, the rewriting function
+    /// returns `None` if the note is left intact. Returns the number of
+    /// rewritten notes.
+    ///
+    async fn rewrite_notes_in<F>(&self, root: &Path, rewrite: F) -> Result<usize, Error>
+    where
+        F: Fn(&Path, &str) -> Option<String>,
+    {
+        self.rewrite_selected_notes(root, None::<fn(&Path, &meta::Metadata) -> bool>, rewrite)
+            .await
+    }
 
-        let mut errors = stream::iter(WalkDir::new(self.config.root()).into_iter())
+    ///
+    /// Rewrite the notes selected by their front matter metadata. Only the
+    /// metadata of the notes is read to select them, so the notes which are
+    /// left as is are never read whole.
+    ///
+    async fn rewrite_selected_notes<S, F>(
+        &self,
+        root: &Path,
+        select: Option<S>,
+        rewrite: F,
+    ) -> Result<usize, Error>
+    where
+        S: Fn(&Path, &meta::Metadata) -> bool,
+        F: Fn(&Path, &str) -> Option<String>,
+    {
+        let select = &select;
+        let rewrite = &rewrite;
+        let mix = stream::iter(self.walk(root))
             .filter_map(|e| async move {
                 if let Ok(e) = e {
-                    if e.path().exists()
-                        && e.path().is_file()
-                        && e.path().extension().and_then(OsStr::to_str) == Some("md")
-                    {
+                    if e.path().exists() && e.path().is_file() && self.is_note(e.path()) {
                         return Some(e);
                     }
                 }
 
                 None
             })
-            .zip(stream::iter(repeat_with(|| files.clone())))
-            .then(|(e, files)| async move {
+            .map(|e| async move {
                 log::trace!("Start processing of the file \"{}\"", e.path().display());
-                let mut content = String::new();
-                {
-                    let mut file = File::open(e.path()).await?;
-                    file.read_to_string(&mut content).await?;
-                }
-
-                let mut dirty = false;
-                for (stem, fe) in files.iter() {
-                    if content.contains(stem) {
-                        content = content.replace(fe.old_name(), fe.new_name().as_ref());
-                        dirty = true;
+                if let Some(select) = select {
+                    let metadata = walk::read_metadata(e.path()).await?;
+                    if !select(e.path(), &metadata) {
+                        return Ok(false);
                     }
                 }
 
-                if dirty {
+                let buffer = match self.read_note(e.path()).await? {
+                    Some(buffer) => buffer,
+                    None => return Ok(false),
+                };
+
+                let content = rewrite(e.path(), buffer.as_str());
+                let rewritten = content.is_some();
+                if let Some(content) = content {
+                    self.journal.record_write(e.path()).await?;
                     let mut file = File::create(e.path()).await?;
                     file.write_all(content.as_bytes()).await?;
                 }
 
                 log::trace!("Finish processing of the file \"{}\"", e.path().display());
-                Ok(()) as Result<(), Error>
+                Ok(rewritten) as Result<bool, Error>
             })
-            .filter_map(|r| async move { r.err() })
+            .buffer_unordered(self.config.io_jobs())
             .collect::<Vec<_>>()
             .await;
 
-        errors.extend(
-            stream::iter(files.iter())
-                .then(|(_, fe)| async move {
-                    fs::rename(fe.old_path(), fe.new_path()).await?;
-                    Ok(()) as Result<(), Error>
-                })
-                .filter_map(|r| async move { r.err() })
-                .collect::<Vec<_>>()
-                .await,
-        );
+        let mut rewritten = 0;
+        let mut errors: Vec<Error> = Vec::new();
+        for r in mix.into_iter() {
+            match r {
+                Ok(true) => rewritten += 1,
+                Ok(false) => {}
+                Err(e) => errors.push(e),
+            }
+        }
 
         if errors.is_empty() {
-            Ok(())
+            Ok(rewritten)
         } else {
-            Err(Error::MultipleExecutorsError(errors))
+            Err(Error::multiple(errors))
         }
     }
 
     ///
-    /// Grab NASA Astronomy Picture of the Day.
+    /// Run the configured tasks on their schedules until interrupted, every
+    /// task runs in the separate process. Returns the number of the runs.
     ///
-    async fn grab_apod(&self, update_daily: bool) -> Result<(), Error> {
-        let nasa_key = self.config.apod_key().ok_or(Error::IllegalNASAKey)?;
-        let url = format!("https://api.nasa.gov/planetary/apod?api_key={}", nasa_key);
-
-        let response = reqwest::get(url).await?.json::<apod::Info>().await?;
-
-        let files_path = self.config.files_path();
-        tokio::fs::create_dir_all(&files_path).await?;
-        let apod_path = self.config.apod_path();
-        tokio::fs::create_dir_all(&apod_path).await?;
-
-        let media_ref: String;
-        match response.media_type() {
-            apod::MediaType::Image => {
-                let image_url = Url::parse(response.url())?;
-                let image_path = PathBuf::from(
-                    image_url
-                        .path_segments()
-                        .ok_or_else(|| Error::IllegalURL(image_url.clone()))?
-                        .into_iter()
-                        .last()
-                        .ok_or_else(|| Error::IllegalURL(image_url.clone()))?,
-                );
-
-                let mut new_image_path = files_path.join(format!("{}", Uuid::new_v4()));
-                if let Some(image_extension) = image_path.extension() {
-                    new_image_path = new_image_path.with_extension(image_extension);
-                }
-
-                // Download the image file.
-                {
-                    let response = reqwest::get(image_url.as_str()).await?;
-                    let mut file = File::create(new_image_path.as_path()).await?;
-                    let mut content = Cursor::new(response.bytes().await?);
-                    tokio::io::copy(&mut content, &mut file).await?;
-                    log::trace!(
-                        "The image was downloaded from {} into the file \"{}\"",
-                        image_url,
-                        new_image_path.display()
-                    );
-                }
-
-                // Get the reference to the media file.
-                media_ref = format!(
-                    "![[{}]]",
-                    new_image_path.file_name().and_then(OsStr::to_str).unwrap()
-                );
-            }
-
-            apod::MediaType::Video => {
-                let src = format!("src=\"{}\"", response.url());
-                media_ref = vec![
-                    "<iframe width=\"100%\" height=\"450\"",
-                    src.as_str(),
-                    "title=\"YouTube video player\"",
-                    "frameborder=\"0\"",
-                    "allow=\"accelerometer; autoplay; clipboard-write;",
-                    "encrypted-media; gyroscope; picture-in-picture\"",
-                    "allowfullscreen></iframe>",
-                ]
-                .join(" ");
-            }
-
-            apod::MediaType::Unknown => {
-                return Err(Error::UnknownMediaType);
-            }
+    async fn watch(&self, run_now: bool) -> Result<usize, Error> {
+        let mut tasks = self
+            .config
+            .watch_tasks()
+            .iter()
+            .map(|task| watch::Task::new(task, self.config.time_zone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if tasks.is_empty() {
+            log::warn!("There are no watched tasks in the configuration");
+            return Ok(0);
         }
 
-        let date = response.date().format("%Y-%m-%d").to_string();
-        let daily_path = self.config.daily_path().join(format!("{}.md", date));
-
-        let mut content = vec![
-            "---\ntype: news".to_string(),
-            format!("name: \"{}\"", response.title()),
-            "issue: APoD".to_string(),
-            format!("date: {}", date),
-            "tags:\n- news/apod\n- science/astronomy\n---\n".to_string(),
-            if update_daily && daily_path.exists() && daily_path.is_file() {
-                format!("[[{}]]\n", date)
+        let started = Local::now();
+        for task in tasks.iter_mut() {
+            if run_now {
+                task.run_at(started);
             } else {
-                if update_daily {
-                    log::warn!("Irrelevant daily path \"{}\"", daily_path.display());
-                }
-
-                format!("{}\n", date)
-            },
-            format!("# {}\n", response.title()),
-            format!("{}\n", media_ref),
-            format!("**Explanation:** {}\n", response.explanation()),
-        ];
-
-        if let Some(copyright) = response.copyright() {
-            content.push(format!("*Image copyright:* {}©\n", copyright));
-        }
-
-        let content = content.join("\n");
-        let note_path = apod_path.join(format!("APoD {}.md", date));
-        {
-            let mut file = File::create(note_path.as_path()).await?;
-            file.write_all(content.as_bytes()).await?;
-            log::trace!(
-                "The Astronomy Picture of the Day note \"{}\" has been created",
-                note_path.display()
-            );
+                task.reschedule(&started);
+            }
+            log::info!("The task \"{}\" is watched", task.command());
         }
 
-        if update_daily && daily_path.exists() && daily_path.is_file() {
-            // Read content of the daily note.
-            let mut buffer = String::new();
-            {
-                let mut file = File::open(daily_path.as_path()).await?;
-                file.read_to_string(&mut buffer).await?;
+        let program = env::current_exe()?;
+        let mut runs = 0;
+        while let Some(next) = tasks.iter().filter_map(watch::Task::next).min() {
+            let delay = (next - Local::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = time::sleep(delay) => {}
+                _ = signal::ctrl_c() => {
+                    log::info!("The watch has been interrupted");
+                    return Ok(runs);
+                }
             }
 
-            let line = format!(
-                "\n\n`rir:Star` [[APoD {}|Astronomy Picture of the Day]]\n",
-                date
-            );
-            buffer.push_str(line.as_str());
+            let now = Local::now();
+            for task in tasks.iter_mut().filter(|task| task.is_due(&now)) {
+                log::info!("Start the task \"{}\"", task.command());
+                let child = process::Command::new(program.as_path())
+                    .args(self.identity.args())
+                    .arg("--config")
+                    .arg(self.config_file.as_path())
+                    .args(task.args())
+                    .stdin(std::process::Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn();
+
+                // The running task is interrupted with the watch.
+                let status = match child {
+                    Ok(mut child) => tokio::select! {
+                        status = child.wait() => status,
+                        _ = signal::ctrl_c() => {
+                            log::info!("The watch has been interrupted");
+                            let _ = child.kill().await;
+                            return Ok(runs);
+                        }
+                    },
+                    Err(e) => Err(e),
+                };
 
-            // Write updated content of the daily note.
-            {
-                let mut file = File::create(daily_path.as_path()).await?;
-                file.write_all(buffer.as_bytes()).await?;
-                log::trace!(
-                    "The daily note \"{}\" has been updated",
-                    daily_path.display()
-                );
+                match status {
+                    Ok(status) if status.success() => {
+                        log::info!("The task \"{}\" has succeeded", task.command())
+                    }
+                    Ok(status) => log::error!(
+                        "The task \"{}\" has failed with the {}",
+                        task.command(),
+                        status
+                    ),
+                    Err(e) => {
+                        log::error!("The task \"{}\" cannot be started: {}", task.command(), e)
+                    }
+                }
+
+                runs += 1;
+                task.reschedule(&Local::now());
             }
         }
 
-        Ok(())
+        log::warn!("There are no more scheduled runs of the watched tasks");
+        Ok(runs)
     }
 
     ///
-    /// Grab This Week in Rust single note.
+    /// Remove the caches, the rotated logs and optionally the empty
+    /// subdirectories of the notes set files directory.
     ///
-    async fn grab_twir_note(
-        &self,
-        number: u32,
-        notes: Arc<twir::Notes>,
-        path: &Path,
-        update_daily: bool,
-    ) -> Result<(), Error> {
-        let note = notes.find(number)?;
-        let html_content = reqwest::get(note.url()).await?.text().await?;
-        let document = scraper::Html::parse_document(&html_content);
-
-        let article_selector = scraper::Selector::parse("article.post-content").unwrap();
-        let article = document
-            .select(&article_selector)
-            .next()
-            .ok_or(Error::IllegalHTMLContent)?;
-        let md_content = html2md::parse_html(article.inner_html().as_str());
-
-        let date = note.datetime().format("%Y-%m-%d").to_string();
-
-        let mut content = vec![
-            format!("---\ntype: news\nissue: {}", number),
-            format!("date: {}\ntags:\n- rust\n- news/twir\naliases:", date),
-            format!("- \"{}\"", note.title()),
-            format!("- \"TWiR {} This Week in Rust {}\"", date, number),
-            format!("url: {}\n---\n", note.url()),
-        ];
+    async fn clean(&self, empty_dirs: bool) -> Result<usize, Error> {
+        let mut removed: Vec<PathBuf> = Vec::new();
 
-        let next = number + 1;
-        if number > 1 {
-            let prev = number - 1;
-            content.push(format!(
-                "<< [[TWiR {0}|{0}]] | [[TWiR {1}|{1}]] >>\n",
-                prev, next
-            ));
-        } else {
-            content.push(format!("| [[TWiR {0}|{0}]] >>\n", next));
+        if self.cache_path.exists() {
+            fs::remove_dir_all(self.cache_path.as_path()).await?;
+            removed.push(PathBuf::from(self.cache_path.as_path()));
         }
 
-        let daily_path = self.config.daily_path().join(format!("{}.md", date));
-
-        if update_daily && daily_path.exists() && daily_path.is_file() {
-            content.push(format!("# [[{}]]: This Week in Rust {}\n", date, number));
-        } else {
-            if update_daily {
-                log::warn!("Irrelevant daily path \"{}\"", daily_path.display());
+        // Remove all the log files except the current one.
+        if let Some(log_path) = self.log_file.parent() {
+            let mut entries = fs::read_dir(log_path).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if path.is_file() && path != self.log_file {
+                    fs::remove_file(path.as_path()).await?;
+                    removed.push(path);
+                }
             }
-
-            content.push(format!("# {}: This Week in Rust {}\n", date, number));
         }
-        content.push(md_content);
 
-        let content = content.join("\n");
-        let note_path = path.join(format!("TWiR {}.md", number));
-        {
-            let mut file = File::create(note_path.as_path()).await?;
-            file.write_all(content.as_bytes()).await?;
-            log::trace!(
-                "The This Weel in Rust note \"{}\" has been created",
-                note_path.display()
-            );
+        if empty_dirs {
+            let files_path = self.config.files_path();
+            for entry in self.entries(self.walker(&files_path).contents_first(true)) {
+                let path = entry.map_err(io::Error::from)?.into_path();
+                if path.is_dir()
+                    && path != files_path.as_ref()
+                    && std::fs::read_dir(path.as_path())?.next().is_none()
+                {
+                    fs::remove_dir(path.as_path()).await?;
+                    removed.push(path);
+                }
+            }
         }
 
-        if update_daily && daily_path.exists() && daily_path.is_file() {
-            // Read content of the daily note.
-            let mut buffer = String::new();
-            {
-                let mut file = File::open(daily_path.as_path()).await?;
-                file.read_to_string(&mut buffer).await?;
-            }
+        if !removed.is_empty() {
+            removed.sort();
 
-            let line = format!(
-                "\n\n`rir:Newspaper` [[Twir {0}|This Week in Rust {0}]]\n",
-                number
-            );
-            buffer.push_str(line.as_str());
+            // Create the table.
+            let mut table = Table::new();
+            table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
 
-            // Write updated content of the daily note.
-            {
-                let mut file = File::create(daily_path.as_path()).await?;
-                file.write_all(buffer.as_bytes()).await?;
-                log::trace!(
-                    "The daily note \"{}\" has been updated",
-                    daily_path.display()
-                );
+            table.set_titles(row![b => "Removed Artifacts"]);
+            for path in &removed {
+                table.add_row(row![path.display()]);
             }
+
+            // Print the table to stdout
+            self.print_table(&table)?;
         }
 
-        Ok(())
+        Ok(removed.len())
     }
 
     ///
-    /// Grab This Week in Rust issues.
+    /// Grab the note recording the successful grab of the source and running
+    /// the post-grab hook. Returns the latest grabbed note path and the summary.
     ///
-    async fn grab_twir(&self, issues: &twir::Issues, update_daily: bool) -> Result<(), Error> {
-        let notes = Arc::new(twir::Notes::select().await?);
-
-        let twir_path = Arc::new(PathBuf::from(self.config.twir_path()));
-        tokio::fs::create_dir_all(twir_path.as_path()).await?;
-
-        match issues {
-            // The issues range.
-            twir::Issues::Range(min_number, max_number) => {
-                let errors = stream::iter(*min_number..=*max_number)
-                    .zip(stream::iter(repeat_with(|| {
-                        (notes.clone(), twir_path.clone())
-                    })))
-                    .then(|(number, (notes, twir_path))| async move {
-                        self.grab_twir_note(number, notes, twir_path.as_path(), update_daily)
-                            .await
-                    })
-                    .filter_map(|r| async move { r.err() })
-                    .collect::<Vec<_>>()
-                    .await;
+    async fn grab_note(
+        &self,
+        templates: &template::Templates,
+        note: &Note,
+        since_last: bool,
+        affix: entry::NameAffix,
+        hook_timeout: std::time::Duration,
+    ) -> Result<(Option<PathBuf>, summary::Summary), Error> {
+        let grabber = grabber::new(note, &self.config, affix);
+        let mut state = state::GrabState::load(self.state_file.as_path()).await?;
+        let since = state.last(grabber.source()).filter(|_| since_last);
+
+        let started = Local::now();
+        let context = grabber::GrabContext::new(self, templates, since);
+        let report = grabber.grab(&context).await?;
+
+        // Record the successful grab of the source.
+        if !grabber.is_dry_run() {
+            state.set(grabber.source(), started);
+            state.save().await?;
+        }
 
-                if !errors.is_empty() {
-                    return Err(Error::MultipleExecutorsError(errors));
+        // Post-process the grabbed notes with the configured hook.
+        let mut failed = 0;
+        if let Some(script) = self.config.post_grab_hook() {
+            for path in report.paths() {
+                if !hooks::run(script, path, self.config.root(), hook_timeout).await? {
+                    failed += 1;
                 }
             }
+        }
 
-            // The single issue.
-            twir::Issues::Single(number) => {
-                self.grab_twir_note(*number, notes.clone(), &twir_path, update_daily)
-                    .await?;
-            }
+        let last_path = report.last_path().map(PathBuf::from);
+        let mut summary = report.into_summary();
+        if failed > 0 {
+            summary.count("failed the hook of", failed, "note");
         }
 
-        Ok(())
+        Ok((last_path, summary))
     }
 
     ///
-    /// Show This Week in Rust issues.
+    /// Show This Week in Rust issues as the table or print them to stdout
+    /// as CSV, the missing issues are found by the note names with the affix.
     ///
-    async fn show_twir(&self, last: bool) -> Result<(), Error> {
-        let mut notes = twir::Notes::select().await?;
+    async fn show_twir(
+        &self,
+        last: bool,
+        page: Option<(usize, usize)>,
+        missing: Option<&entry::NameAffix>,
+        format: twir::TableFormat,
+    ) -> Result<usize, Error> {
+        let mut notes = twir::Notes::select(&self.client, self.config.max_body_size()).await?;
         if last {
             notes = notes.first();
         }
+        if let Some((page, per_page)) = page {
+            notes = notes.page(page, per_page);
+        }
 
-        // Create the table.
-        let mut table = Table::new();
-        table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
-
-        table.set_titles(row!["Date", "Title", "URL"]);
-        for note in notes.iter() {
-            table.add_row(row![
-                note.datetime().format("%Y-%m-%d"),
-                note.title(),
-                note.url()
-            ]);
+        let table = notes.table(&self.config, missing);
+        match format {
+            twir::TableFormat::Csv => {
+                table.to_csv(io::stdout())?;
+            }
+            // Show the table through the pager.
+            twir::TableFormat::Table => pager::page(table.to_string().as_str()).await?,
         }
 
-        // Print the table to stdout
-        table.printstd();
+        Ok(table.len())
+    }
 
-        Ok(())
+    ///
+    /// Open This Week in Rust issue in the browser without grabbing it.
+    ///
+    async fn open_twir(&self, number: u32) -> Result<(), Error> {
+        let notes = twir::Notes::select(&self.client, self.config.max_body_size()).await?;
+        let note = notes.find(number)?;
+
+        log::info!("Open the issue \"{}\" at {}", note.title(), note.url());
+        opener::open_url(note.url()).await
     }
 
     ///
-    /// Add the calendar to the monthly note.
+    /// Add the calendar to the monthly, quarterly or annual note.
     ///
-    async fn add_calendar(&self, year: i32, month: u32) -> Result<(), Error> {
+    async fn add_calendar(
+        &self,
+        year: i32,
+        period: calendar::Period,
+        style: calendar::Style,
+        all_links: bool,
+    ) -> Result<(), Error> {
         if year <= 0 {
             return Err(Error::IllegalYearNumber(year));
         }
-        if !(1..=12).contains(&month) {
-            return Err(Error::IllegalMonthNumber(month));
+        match period {
+            calendar::Period::Month(month) if !(1..=12).contains(&month) => {
+                return Err(Error::IllegalMonthNumber(month));
+            }
+            calendar::Period::Quarter(quarter) if !(1..=4).contains(&quarter) => {
+                return Err(Error::IllegalQuarterNumber(quarter));
+            }
+            _ => {}
         }
 
-        let monthly_path = self
+        let note_path = self
             .config
             .daily_path()
-            .join(format!("{}-{:02}.md", year, month));
-        if !monthly_path.is_file() {
-            return Err(Error::IllegalPath(format!("{}", monthly_path.display())));
+            .join(format!("{}.md", period.name(year)));
+        if !note_path.is_file() {
+            return Err(Error::IllegalPath(format!("{}", note_path.display())));
         }
 
-        let mut calendar = vec![
-            "| Пн | Вт | Ср | Чт | Пт | Сб | Вс |".to_string(),
-            "|:--:|:--:|:--:|:--:|:--:|:--:|:--:|".to_string(),
-        ];
-
-        let mut current = NaiveDate::from_ymd(year, month, 1);
-        let mut n = current.weekday().num_days_from_monday() as usize;
-
-        let mut row = "|".to_string();
-        row.push_str("    |".repeat(n).as_str());
-
-        loop {
-            n += 1;
-            row.push_str(
-                format!(" [[{}\\|{}]] |", current.format("%Y-%m-%d"), current.day()).as_str(),
-            );
-            if n > 6 {
-                calendar.push(row);
-                row = "|".to_string();
-                n = 0;
-            }
-
-            let prev = current;
-            current = current.succ();
-            if current.month() != month {
-                n = prev.weekday().num_days_from_monday() as usize;
-                row.push_str("    |".repeat(6 - n).as_str());
-                calendar.push(row);
-                break;
-            }
-        }
+        let linked = |day: NaiveDate| all_links || self.config.daily_note_path(day).is_file();
+        let months = period.months();
+        let calendar = months
+            .iter()
+            .map(|&month| {
+                let calendar = style.render(
+                    year,
+                    month,
+                    self.config.language().weekdays(),
+                    self.config.date_format(),
+                    linked,
+                );
+                if months.len() > 1 {
+                    format!("## {}-{:02}\n\n{}", year, month, calendar)
+                } else {
+                    calendar
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
 
         let mut buffer = String::new();
         {
-            let mut file = File::open(monthly_path.as_path()).await?;
+            let mut file = File::open(note_path.as_path()).await?;
             file.read_to_string(&mut buffer).await?;
         }
 
-        buffer.push_str(format!("\n\n{}\n", calendar.join("\n")).as_str());
+        buffer.push_str(format!("\n\n{}\n", calendar).as_str());
 
-        // Write updated content of the monthly note.
+        // Write updated content of the calendar note.
         {
-            let mut file = File::create(monthly_path.as_path()).await?;
+            self.changes.record(note_path.as_path());
+            let mut file = File::create(note_path.as_path()).await?;
             file.write_all(buffer.as_bytes()).await?;
             log::trace!(
-                "The monthly note \"{}\" has been updated",
-                monthly_path.display()
+                "The calendar note \"{}\" has been updated",
+                note_path.display()
             );
         }
 
         Ok(())
     }
 }
+
+// Get the unified diff of the note content.
+fn unified_diff(name: &str, old: &str, new: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(
+            format!("a/{}", name).as_str(),
+            format!("b/{}", name).as_str(),
+        )
+        .to_string()
+}
+
+// Create the log records format with the optionally colored levels.
+fn log_format(
+    colors: Option<ColoredLevelConfig>,
+) -> impl Fn(fern::FormatCallback, &fmt::Arguments, &log::Record) + Sync + Send + 'static {
+    move |out, message, record| {
+        let level = match colors {
+            Some(colors) => colors.color(record.level()).to_string(),
+            None => record.level().to_string(),
+        };
+        out.finish(format_args!(
+            "{}[{}][{}] {}",
+            chrono::Local::now().format("[%Y-%m-%d][%H:%M:%S]"),
+            record.target(),
+            level,
+            message
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Create the application of the notes set in the temporary directory
+    // with the additional notes set configuration.
+    async fn application(root: &Path, notes_config: &str) -> Application {
+        let notes = root.join("notes");
+        tokio::fs::create_dir_all(notes.as_path()).await.unwrap();
+
+        let config_file = root.join("nta.toml");
+        let content = format!(
+            "[Notes]\nRoot = \"{}\"\n{}\n[\"NASA APoD API\"]\nVersion = \"v1\"\n",
+            notes.display(),
+            notes_config
+        );
+        tokio::fs::write(config_file.as_path(), content)
+            .await
+            .unwrap();
+
+        let config = Config::read(config_file.as_path()).await.unwrap().unwrap();
+        let options = Options::with_data_dir(config_file.as_path(), root.join("data").as_path());
+        Application::new(config, &options)
+    }
+
+    #[tokio::test]
+    async fn add_timestamps_diff_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let application = application(root.as_path(), "").await;
+        let path = application.config.root().join("note.md");
+        let content = "---\ntitle: Note\n---\n# Note\n";
+        tokio::fs::write(path.as_path(), content).await.unwrap();
+
+        // The previewed note is neither written nor recorded for the commit.
+        let added = application
+            .add_timestamps(true, false, true, true)
+            .await
+            .unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(
+            tokio::fs::read_to_string(path.as_path()).await.unwrap(),
+            content
+        );
+        assert!(application.journal.paths().is_empty());
+        assert!(application.changes.paths().is_empty());
+
+        assert_eq!(
+            unified_diff(
+                "note.md",
+                content,
+                "---\ntitle: Note\ncreated: 2022-08-03T10:00:00\n---\n# Note\n"
+            ),
+            "--- a/note.md\n+++ b/note.md\n@@ -1,4 +1,5 @@\n \
+             ---\n title: Note\n+created: 2022-08-03T10:00:00\n ---\n # Note\n"
+        );
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fix_daily_links_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let application = application(root.as_path(), "").await;
+        let daily_path = application.config.daily_path();
+        let apod_path = application.config.apod_path();
+        for path in [daily_path.as_ref(), apod_path.as_ref()] {
+            tokio::fs::create_dir_all(path).await.unwrap();
+        }
+        tokio::fs::write(apod_path.join("2022 - APoD 2022-08-03.md"), "")
+            .await
+            .unwrap();
+
+        // The links to the notes named otherwise are neither fixed nor reported.
+        let daily = daily_path.join("2022-08-03.md");
+        tokio::fs::write(
+            daily.as_path(),
+            "[[2022 -  apod 2022-08-03]] [[2022 - TWiR 454]] [[Apodization]]\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(
+            daily_path.join("2022-08-04.md"),
+            "[[APoD 2022-08-04]] [[TWiR 455]] [[2022 - APoD 2022-08-03 notes]]\n",
+        )
+        .await
+        .unwrap();
+
+        let affix = entry::NameAffix::new(Some("2022 - "), None);
+        assert_eq!(
+            application.fix_daily_links(true, &affix).await.unwrap(),
+            (1, 1)
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(daily.as_path()).await.unwrap(),
+            "[[2022 - APoD 2022-08-03]] [[2022 - TWiR 454]] [[Apodization]]\n"
+        );
+
+        // The notes named without the affix are reported as missing.
+        assert_eq!(
+            application
+                .fix_daily_links(false, &entry::NameAffix::default())
+                .await
+                .unwrap(),
+            (1, 0)
+        );
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn touch_modified_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let application = application(root.as_path(), "Encoding = \"windows-1251\"\n").await;
+        let path = application.config.root().join("note.md");
+        tokio::fs::write(
+            path.as_path(),
+            include_bytes!("application/fixtures/cp1251.md"),
+        )
+        .await
+        .unwrap();
+        application
+            .journal
+            .record_write(path.as_path())
+            .await
+            .unwrap();
+
+        // The note in the fallback encoding is touched and written as UTF-8.
+        assert_eq!(application.touch_modified().await.unwrap(), 1);
+        let content = tokio::fs::read_to_string(path.as_path()).await.unwrap();
+        assert!(content.starts_with("---\nmodified: "));
+        assert!(content.ends_with("# Заметка\n\nСм. ![[image.png]]\n"));
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rename_attached_files_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let application = application(root.as_path(), "").await;
+        let files_path = application.config.files_path();
+        tokio::fs::create_dir_all(files_path.as_ref())
+            .await
+            .unwrap();
+
+        let free = Uuid::parse_str("abcd1234-0000-4000-8000-000000000000").unwrap();
+        let taken = Uuid::parse_str("ef001234-0000-4000-8000-000000000000").unwrap();
+        for (path, content) in [
+            ("first.png", "first"),
+            ("second.png", "second"),
+            ("ef001234-0000-4000-8000-000000000000.png", "taken"),
+        ] {
+            tokio::fs::write(files_path.join(path), content)
+                .await
+                .unwrap();
+        }
+        let note = application.config.root().join("note.md");
+        tokio::fs::write(note.as_path(), "![[first.png]] ![[second.png]]\n")
+            .await
+            .unwrap();
+
+        // The colliding file is neither moved nor overwritten, and the notes
+        // still link to it.
+        let result = application
+            .rename_attached_files_with(|path| {
+                if path.ends_with("first.png") {
+                    free
+                } else {
+                    taken
+                }
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(!files_path.join("first.png").exists());
+        assert_eq!(
+            tokio::fs::read_to_string(files_path.join(format!("{}.png", free)))
+                .await
+                .unwrap(),
+            "first"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(files_path.join("second.png"))
+                .await
+                .unwrap(),
+            "second"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(files_path.join(format!("{}.png", taken)))
+                .await
+                .unwrap(),
+            "taken"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(note.as_path()).await.unwrap(),
+            format!("![[{}.png]] ![[second.png]]\n", free)
+        );
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reshard_files_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let application = application(root.as_path(), "FilesShard = 2\n").await;
+        let files_path = application.config.files_path();
+        tokio::fs::create_dir_all(files_path.join("pdfs"))
+            .await
+            .unwrap();
+
+        let image = "abcd1234-0000-4000-8000-000000000000.png";
+        for path in [
+            image,
+            "pdfs/paper.pdf",
+            "pdfs/ef001234-0000-4000-8000-000000000000.pdf",
+        ] {
+            tokio::fs::write(files_path.join(path), "").await.unwrap();
+        }
+        let note = application.config.root().join("note.md");
+        tokio::fs::write(
+            note.as_path(),
+            format!("![[{0}]] [[Files/{0}]] [[pdfs/paper.pdf]]\n", image),
+        )
+        .await
+        .unwrap();
+
+        // The files of the other subdirectories are not moved.
+        assert_eq!(application.reshard_files().await.unwrap(), 1);
+        assert!(files_path.join("ab").join(image).is_file());
+        assert!(files_path.join("pdfs/paper.pdf").is_file());
+        assert!(files_path
+            .join("pdfs/ef001234-0000-4000-8000-000000000000.pdf")
+            .is_file());
+        assert_eq!(
+            tokio::fs::read_to_string(note.as_path()).await.unwrap(),
+            format!("![[ab/{0}]] [[Files/ab/{0}]] [[pdfs/paper.pdf]]\n", image)
+        );
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+    #[tokio::test]
+    async fn reshard_files_failure_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let application = application(root.as_path(), "FilesShard = 2\n").await;
+        let files_path = application.config.files_path();
+        tokio::fs::create_dir_all(files_path.as_ref())
+            .await
+            .unwrap();
+
+        // The shard directory of the first file cannot be created.
+        let blocked = "abcd1234-0000-4000-8000-000000000000.png";
+        let free = "ef001234-0000-4000-8000-000000000000.png";
+        for path in [blocked, free, "ab"] {
+            tokio::fs::write(files_path.join(path), "").await.unwrap();
+        }
+        let note = application.config.root().join("note.md");
+        let content = format!("![[{}]] ![[{}]]\n", blocked, free);
+        tokio::fs::write(note.as_path(), content.as_str())
+            .await
+            .unwrap();
+
+        // The links to the file which has not been moved are kept.
+        assert!(application.reshard_files().await.is_err());
+        assert!(files_path.join(blocked).is_file());
+        assert!(files_path.join("ef").join(free).is_file());
+        assert_eq!(
+            tokio::fs::read_to_string(note.as_path()).await.unwrap(),
+            format!("![[{}]] ![[ef/{}]]\n", blocked, free)
+        );
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+}
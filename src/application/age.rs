@@ -0,0 +1,86 @@
+use std::{str::FromStr, time::Duration};
+
+use regex::Regex;
+
+use crate::error::Error;
+
+///
+/// The age of a file, e.g. `7d` or `24h`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Age(Duration);
+
+impl Age {
+    ///
+    /// Get the age duration.
+    ///
+    #[inline]
+    pub(crate) fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl FromStr for Age {
+    type Err = Error;
+
+    ///
+    /// Convert string to the Age instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let re = Regex::new(r"^\s*(?P<value>\d+)\s*(?P<unit>[smhdw])\s*$").unwrap();
+        if let Some(caps) = re.captures(s) {
+            let value =
+                u64::from_str(&caps["value"]).map_err(|_| Error::IllegalDuration(s.to_string()))?;
+            let seconds = match &caps["unit"] {
+                "s" => 1,
+                "m" => 60,
+                "h" => 60 * 60,
+                "d" => 24 * 60 * 60,
+                _ => 7 * 24 * 60 * 60,
+            };
+
+            return value
+                .checked_mul(seconds)
+                .map(|secs| Self(Duration::from_secs(secs)))
+                .ok_or_else(|| Error::IllegalDuration(s.to_string()));
+        }
+
+        Err(Error::IllegalDuration(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn age_test() {
+        assert_eq!(Age::from_str("0d").unwrap().duration(), Duration::ZERO);
+        assert_eq!(
+            Age::from_str("30s").unwrap().duration(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            Age::from_str("15m").unwrap().duration(),
+            Duration::from_secs(900)
+        );
+        assert_eq!(
+            Age::from_str("24h").unwrap().duration(),
+            Duration::from_secs(86400)
+        );
+        assert_eq!(
+            Age::from_str(" 7d ").unwrap().duration(),
+            Duration::from_secs(7 * 86400)
+        );
+        assert_eq!(
+            Age::from_str("2w").unwrap().duration(),
+            Duration::from_secs(14 * 86400)
+        );
+
+        assert!(Age::from_str("").is_err());
+        assert!(Age::from_str("7").is_err());
+        assert!(Age::from_str("-1d").is_err());
+        assert!(Age::from_str("1y").is_err());
+        assert!(Age::from_str("99999999999999999999d").is_err());
+    }
+}
@@ -1,9 +1,42 @@
-use chrono::naive::NaiveDate;
+use std::{borrow::Cow, collections::HashMap, path::PathBuf, str::FromStr};
 
+use async_trait::async_trait;
+use chrono::{naive::NaiveDate, Datelike};
+use regex::{Captures, Regex};
+use reqwest::StatusCode;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tokio::{fs::File, io::AsyncWriteExt};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        entry,
+        grabber::{GrabContext, GrabReport, Grabber},
+        graph, http, images,
+        lang::Language,
+        markdown,
+        summary::Summary,
+        template::Templates,
+    },
+    config::{self, Config},
+    error::Error,
+};
 
 const APOD_DATE_FORMAT: &str = "%Y-%m-%d";
 
+pub(crate) const APOD_URL: &str = "https://api.nasa.gov/planetary/apod";
+
+///
+/// The cache file of the downloaded images index.
+///
+const IMAGES_INDEX: &str = "apod-images.json";
+
+///
+/// The web page of the picture by the date in the YYMMDD format.
+///
+const APOD_PAGE_URL: &str = "https://apod.nasa.gov/apod/ap";
+
 ///
 /// NASA Astronomy Picture of the Day API service version.
 ///
@@ -13,6 +46,630 @@ pub enum Version {
     V1_0,
 }
 
+///
+/// The granularity of the date derived tags.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTag {
+    Year,
+    Month,
+}
+
+impl DateTag {
+    ///
+    /// Get the date derived subtags of the tag.
+    ///
+    pub(crate) fn tags(&self, tag: &str, date: NaiveDate) -> Vec<String> {
+        let year = format!("{}/{}", tag, date.year());
+        match self {
+            Self::Year => vec![year],
+            Self::Month => {
+                let month = format!("{}-{:02}", year, date.month());
+                vec![year, month]
+            }
+        }
+    }
+}
+
+impl FromStr for DateTag {
+    type Err = Error;
+
+    ///
+    /// Convert string to the DateTag instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "year" => Ok(Self::Year),
+            "month" => Ok(Self::Month),
+            _ => Err(Error::IllegalDateTag(s.to_string())),
+        }
+    }
+}
+
+///
+/// Get the web page URL of the picture of the date.
+///
+pub(crate) fn page_url(date: NaiveDate) -> String {
+    format!("{}{}.html", APOD_PAGE_URL, date.format("%y%m%d"))
+}
+
+///
+/// Get the collection subdirectory name of the note date, the collection
+/// is formatted as the date, e.g. "%Y" collects the notes by the year.
+///
+pub(crate) fn collection_name(collection: &str, date: NaiveDate) -> Result<String, Error> {
+    let name = config::format_date(date, collection)
+        .ok_or_else(|| Error::IllegalDateFormat(collection.to_string()))?;
+    let name = entry::sanitize_name(name.as_str());
+    let name = name.trim();
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        return Err(Error::IllegalPath(collection.to_string()));
+    }
+
+    Ok(name.to_string())
+}
+
+///
+/// Get the explanation paragraph of the note wrapped to the column width
+/// if it is set.
+///
+fn explanation(language: Language, info: &Info, wrap: Option<usize>) -> String {
+    let explanation = format!("**{}:** {}\n", language.explanation(), info.explanation());
+    match wrap {
+        Some(width) => markdown::wrap(explanation.as_str(), width),
+        None => explanation,
+    }
+}
+
+///
+/// The markup style of the embedded videos.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoEmbed {
+    ///
+    /// The HTML iframe of the video player.
+    ///
+    #[default]
+    Iframe,
+
+    ///
+    /// The Markdown embed of the video link.
+    ///
+    Markdown,
+}
+
+impl VideoEmbed {
+    ///
+    /// Get the markup of the video embedded in the style.
+    ///
+    pub(crate) fn markup(&self, url: &str) -> String {
+        match self {
+            Self::Iframe => [
+                "<iframe width=\"100%\" height=\"450\"",
+                format!("src=\"{}\"", url).as_str(),
+                "title=\"YouTube video player\"",
+                "frameborder=\"0\"",
+                "allow=\"accelerometer; autoplay; clipboard-write;",
+                "encrypted-media; gyroscope; picture-in-picture\"",
+                "allowfullscreen></iframe>",
+            ]
+            .join(" "),
+            Self::Markdown => format!("![]({})", url),
+        }
+    }
+
+    ///
+    /// Rewrite the YouTube iframes of the content in the style. Returns the
+    /// content and the number of the rewritten iframes, the iframes already
+    /// having the style markup are left as is.
+    ///
+    pub(crate) fn normalize<'a>(&self, content: &'a str) -> (Cow<'a, str>, usize) {
+        let re = Regex::new(
+            r#"(?is)<iframe\b[^>]*?\bsrc\s*=\s*["'](?P<src>https?://(?:www\.)?(?:youtube(?:-nocookie)?\.com|youtu\.be)/[^"']*)["'][^>]*>\s*</iframe>"#,
+        )
+        .unwrap();
+        let mut count = 0;
+        let normalized = re.replace_all(content, |caps: &Captures| {
+            let markup = self.markup(&caps["src"]);
+            if markup != caps[0] {
+                count += 1;
+            }
+
+            markup
+        });
+
+        if count == 0 {
+            (Cow::Borrowed(content), count)
+        } else {
+            (Cow::Owned(normalized.into_owned()), count)
+        }
+    }
+}
+
+///
+/// The options of NASA Astronomy Picture of the Day grabbing.
+///
+#[derive(Debug, Default, Clone)]
+pub(crate) struct GrabOptions {
+    ///
+    /// Update daily note in notes set.
+    ///
+    pub(crate) update_daily: bool,
+
+    ///
+    /// Refer to the remote image instead of downloading it.
+    ///
+    pub(crate) no_download: bool,
+
+    ///
+    /// The granularity of the date derived tags if they are required.
+    ///
+    pub(crate) date_tag: Option<DateTag>,
+
+    ///
+    /// Set the downloaded image as the banner of the note.
+    ///
+    pub(crate) banner: bool,
+
+    ///
+    /// Name the note by the local date instead of the response one.
+    ///
+    pub(crate) local_date: bool,
+
+    ///
+    /// Skip the grabbing if the note of today already exists.
+    ///
+    pub(crate) since_config_last: bool,
+
+    ///
+    /// Write only the explanation without any media reference.
+    ///
+    pub(crate) explanation_only: bool,
+
+    ///
+    /// Do not write the picture title into the note aliases.
+    ///
+    pub(crate) no_alias: bool,
+
+    ///
+    /// The inline template of the image caption overriding the configured one.
+    ///
+    pub(crate) caption_template: Option<String>,
+
+    ///
+    /// The number of the explanation words written to the front matter excerpt.
+    ///
+    pub(crate) excerpt: Option<usize>,
+
+    ///
+    /// The file to write the API response to as JSON.
+    ///
+    pub(crate) json_out: Option<PathBuf>,
+
+    ///
+    /// The column width to wrap the explanation to if it is required.
+    ///
+    pub(crate) wrap: Option<usize>,
+
+    ///
+    /// The adornment of the note file name.
+    ///
+    pub(crate) affix: entry::NameAffix,
+
+    ///
+    /// The subdirectory of the note formatted as the note date.
+    ///
+    pub(crate) collection: Option<String>,
+}
+
+impl GrabOptions {
+    ///
+    /// Get the note path of the date in the collection subdirectory of the
+    /// Astronomy Picture of the Day directory adorned by the affix.
+    ///
+    pub(crate) fn note_path(&self, config: &Config, date: NaiveDate) -> Result<PathBuf, Error> {
+        let path = config.apod_note_path(config.format_date(date).as_str());
+        let path = match (self.collection.as_deref(), path.file_name()) {
+            (Some(collection), Some(file_name)) => config
+                .apod_path()
+                .join(collection_name(collection, date)?)
+                .join(file_name),
+            _ => path,
+        };
+
+        Ok(self.affix.apply(path))
+    }
+}
+
+///
+/// NASA Astronomy Picture of the Day grabber.
+///
+#[derive(Debug)]
+pub(crate) struct APoDGrabber {
+    options: GrabOptions,
+}
+
+impl APoDGrabber {
+    ///
+    /// Create NASA Astronomy Picture of the Day grabber with options.
+    ///
+    pub(crate) fn new(options: GrabOptions) -> Self {
+        Self { options }
+    }
+
+    ///
+    /// Grab NASA Astronomy Picture of the Day note. Returns the note path.
+    ///
+    async fn grab_note(&self, context: &GrabContext<'_>) -> Result<PathBuf, Error> {
+        let options = &self.options;
+        let config = context.config();
+        let templates = context.templates();
+        let update_daily = options.update_daily;
+        let language = config.language();
+        let keys = config.keys();
+
+        // Validate the collection before the request.
+        options.note_path(config, config.today())?;
+
+        let nasa_key = config.read_apod_key().await?.ok_or(Error::IllegalNASAKey)?;
+        let url = format!("{}?api_key={}", APOD_URL, nasa_key);
+
+        let response: Info = http::json(
+            context.client().get(url).send().await?,
+            config.max_body_size(),
+        )
+        .await
+        .map_err(|e| match e {
+            Error::Unauthorized { .. } => Error::IllegalNASAKey,
+            e => e,
+        })?;
+
+        // Keep the response to reprocess it later, the unknown fields too.
+        if let Some(ref json_path) = options.json_out {
+            if let Some(parent) = json_path
+                .parent()
+                .filter(|path| !path.as_os_str().is_empty())
+            {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(json_path, serde_json::to_string_pretty(&response)?).await?;
+            context.changes().record(json_path);
+            log::info!(
+                "The APoD response has been written to \"{}\"",
+                json_path.display()
+            );
+        }
+
+        let files_path = config.files_path();
+        tokio::fs::create_dir_all(&files_path).await?;
+        tokio::fs::create_dir_all(config.apod_path()).await?;
+
+        // The caption is written under the image only.
+        let caption = match options
+            .caption_template
+            .as_deref()
+            .or_else(|| config.apod_caption())
+        {
+            Some(template)
+                if !options.explanation_only
+                    && matches!(response.media_type(), MediaType::Image) =>
+            {
+                let mut context = tera::Context::new();
+                context.insert("title", response.title());
+                context.insert("date", &config.format_date(response.date()));
+                context.insert("copyright", &response.copyright());
+                Some(Templates::render_str(template, &context)?)
+            }
+            _ => None,
+        };
+
+        let media_ref: String;
+        let mut banner: Option<String> = None;
+        match response.media_type() {
+            _ if options.explanation_only => {
+                // Omit the media reference entirely.
+                media_ref = String::new();
+            }
+
+            MediaType::Image if options.no_download => {
+                // Refer to the remote image instead of the downloaded one,
+                // the title is the alternative text of the captioned image.
+                let alt = match caption {
+                    Some(_) => response.title().replace(['[', ']'], ""),
+                    None => String::new(),
+                };
+                media_ref = format!("![{}]({})", alt, response.url());
+            }
+
+            MediaType::Image => {
+                let image_url = Url::parse(response.url())?;
+                let image_path = PathBuf::from(
+                    image_url
+                        .path_segments()
+                        .ok_or_else(|| Error::IllegalURL(image_url.clone()))?
+                        .into_iter()
+                        .last()
+                        .ok_or_else(|| Error::IllegalURL(image_url.clone()))?,
+                );
+
+                // Reuse the previously downloaded image file.
+                let mut index =
+                    images::ImageIndex::load(context.cache_path().join(IMAGES_INDEX)).await?;
+                let cached = index
+                    .get(image_url.as_str())
+                    .filter(|entry| files_path.join(entry.file_name()).is_file())
+                    .cloned();
+
+                let new_image_path = match cached {
+                    Some(ref entry) => files_path.join(entry.file_name()),
+                    None => {
+                        let mut new_image_path = PathBuf::from(format!("{}", Uuid::new_v4()));
+                        if let Some(image_extension) = image_path.extension() {
+                            new_image_path = new_image_path.with_extension(image_extension);
+                        }
+                        let file_name = new_image_path.to_string_lossy();
+                        files_path.join(entry::shard_name(file_name.as_ref(), config.files_shard()))
+                    }
+                };
+
+                // The sharded image is referred by the path relative to the files directory.
+                let file_name = graph::node_id(&files_path, new_image_path.as_path());
+
+                // Download the image file if it has been changed.
+                let mut request = context.client().get(image_url.as_str());
+                if let Some(ref entry) = cached {
+                    request = entry.condition(request);
+                }
+                let response = request.send().await?;
+
+                if response.status() == StatusCode::NOT_MODIFIED {
+                    log::trace!(
+                        "The image {} is not modified, reuse the file \"{}\"",
+                        image_url,
+                        new_image_path.display()
+                    );
+                } else {
+                    let entry = images::ImageEntry::new(file_name.as_str(), response.headers());
+                    if let Some(parent) = new_image_path.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+
+                    http::download(response, new_image_path.as_path(), config.max_body_size())
+                        .await?;
+                    context.changes().record(new_image_path.as_path());
+                    log::trace!(
+                        "The image was downloaded from {} into the file \"{}\"",
+                        image_url,
+                        new_image_path.display()
+                    );
+
+                    index.insert(image_url.as_str(), entry);
+                    index.save().await?;
+                }
+
+                // Get the reference to the media file.
+                media_ref = format!("![[{}]]", file_name);
+                if options.banner {
+                    banner = Some(file_name);
+                }
+            }
+
+            MediaType::Video => {
+                media_ref = config.apod_video_embed().markup(response.url());
+            }
+
+            MediaType::Unknown => {
+                return Err(Error::UnknownMediaType);
+            }
+        }
+
+        // The response date follows the US Eastern time zone and may
+        // differ from the local one near the midnight, the configured
+        // time zone always names the note by its own date.
+        let api_date = config.format_date(response.date());
+        let note_date = if options.local_date || config.has_time_zone() {
+            config.today()
+        } else {
+            response.date()
+        };
+        let date = config.format_date(note_date);
+        let daily_path = config.daily_note_path(note_date);
+
+        let daily = daily_path.exists() && daily_path.is_file();
+        if update_daily && !daily {
+            log::warn!("Irrelevant daily path \"{}\"", daily_path.display());
+        }
+        let daily_ref = if update_daily && daily {
+            format!("[[{}]]", date)
+        } else {
+            date.clone()
+        };
+
+        let mut tags = vec!["news/apod".to_string(), "science/astronomy".to_string()];
+        if let Some(date_tag) = options.date_tag {
+            tags.extend(date_tag.tags("science/astronomy", response.date()));
+        }
+        let tags = entry::TemplateEntry::multiple(tags);
+        let aliases = entry::TemplateEntry::multiple(if options.no_alias {
+            Vec::new()
+        } else {
+            vec![response.title().to_string()]
+        });
+        let excerpt = options
+            .excerpt
+            .map(|words| markdown::excerpt(response.explanation(), words));
+
+        let content = if let Some(name) = config.apod_template() {
+            let mut context = tera::Context::new();
+            context.insert("keys", keys);
+            context.insert("title", response.title());
+            context.insert("date", &date);
+            context.insert("api_date", &api_date);
+            context.insert("daily", &daily_ref);
+            context.insert("tags", &tags);
+            context.insert("aliases", &aliases);
+            context.insert("media", &media_ref);
+            context.insert("caption", &caption);
+            context.insert("excerpt", &entry::TemplateEntry::single(excerpt));
+            context.insert("url", response.url());
+            context.insert(
+                "explanation",
+                &match options.wrap {
+                    Some(width) => markdown::wrap(response.explanation(), width),
+                    None => response.explanation().to_string(),
+                },
+            );
+            context.insert(
+                "copyright",
+                &entry::TemplateEntry::single(response.copyright().map(String::from)),
+            );
+            context.insert("extra", response.extra());
+            context.insert("banner", &banner);
+            context.insert("source", &page_url(response.date()));
+
+            templates.render(name, &context).await?
+        } else {
+            let mut content = vec![
+                format!("---\n{}: news", keys.note_type),
+                format!("{}: \"{}\"", keys.name, response.title()),
+                format!("{}: APoD", keys.issue),
+                format!("{}: {}", keys.date, api_date),
+            ];
+            content.extend(config.apod_fields().iter().filter_map(|name| {
+                response
+                    .field(name)
+                    .map(|value| format!("{}: {}", name, value))
+            }));
+            content.push(format!("{}:", keys.tags));
+            content.extend(tags.iter().map(|tag| format!("- {}", tag)));
+            if !options.no_alias {
+                content.push(format!("{}:", keys.aliases));
+                content.extend(aliases.quoted().map(|alias| format!("- {}", alias)));
+            }
+            if let Some(ref banner) = banner {
+                content.push(format!("{}: \"![[{}]]\"", keys.banner, banner));
+            }
+            if let Some(ref excerpt) = excerpt {
+                // The JSON string is the valid double-quoted YAML scalar.
+                content.push(format!(
+                    "{}: {}",
+                    keys.excerpt,
+                    serde_json::to_string(excerpt)?
+                ));
+            }
+            if options.explanation_only {
+                content.push(format!("{}: {}", keys.url, page_url(response.date())));
+            }
+            content.extend(vec![
+                "---\n".to_string(),
+                format!("{}\n", daily_ref),
+                format!("# {}\n", response.title()),
+            ]);
+            if !media_ref.is_empty() {
+                content.push(format!("{}\n", media_ref));
+            }
+            if let Some(ref caption) = caption {
+                content.push(format!("{}\n", caption));
+            }
+            content.push(explanation(language, &response, options.wrap));
+
+            if let Some(copyright) = response.copyright().filter(|_| !options.explanation_only) {
+                content.push(format!(
+                    "*{}:* {}©\n",
+                    language.image_copyright(),
+                    copyright
+                ));
+            }
+
+            content.join("\n")
+        };
+
+        let note_path = options.note_path(config, note_date)?;
+        if let Some(parent) = note_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        {
+            context.changes().record(note_path.as_path());
+            let mut file = File::create(note_path.as_path()).await?;
+            file.write_all(content.as_bytes()).await?;
+            log::trace!(
+                "The Astronomy Picture of the Day note \"{}\" has been created",
+                note_path.display()
+            );
+        }
+
+        if update_daily && daily {
+            // The back-link refers to the note by its actual name.
+            let note_name = note_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| format!("APoD {}", date));
+            let back_link = match config.apod_daily_ref() {
+                Some(template) => {
+                    let mut context = tera::Context::new();
+                    context.insert("date", &date);
+                    context.insert("note", &note_name);
+                    context.insert("title", language.apod());
+                    Templates::render_str(template, &context)?
+                }
+                None => format!("`rir:Star` [[{}|{}]]", note_name, language.apod()),
+            };
+            let line = format!("\n\n{}\n", back_link);
+            context
+                .daily()
+                .append(daily_path.as_path(), line.as_str())
+                .await?;
+            context.changes().record(daily_path.as_path());
+        }
+
+        Ok(note_path)
+    }
+}
+
+#[async_trait(?Send)]
+impl Grabber for APoDGrabber {
+    #[inline]
+    fn source(&self) -> &'static str {
+        "apod"
+    }
+
+    ///
+    /// Grab NASA Astronomy Picture of the Day note, the picture grabbed today
+    /// is skipped if only the newer items are grabbed.
+    ///
+    async fn grab(&self, context: &GrabContext<'_>) -> Result<GrabReport, Error> {
+        let mut summary = Summary::default();
+        if let Some(since) = context.since() {
+            let config = context.config();
+            if config.local_date(&since) == config.today() {
+                log::info!("The Astronomy Picture of the Day has been grabbed today");
+                summary.push("skipped the Astronomy Picture of the Day grabbed today");
+                return Ok(GrabReport::new(Vec::new(), summary));
+            }
+        }
+
+        if self.options.since_config_last {
+            let config = context.config();
+            let path = self.options.note_path(config, config.today())?;
+            if path.is_file() {
+                log::info!(
+                    "The Astronomy Picture of the Day note \"{}\" already exists",
+                    path.display()
+                );
+                summary.push("skipped the Astronomy Picture of the Day of today");
+                return Ok(GrabReport::new(Vec::new(), summary));
+            }
+        }
+
+        let path = self.grab_note(context).await?;
+
+        summary.push("grabbed the Astronomy Picture of the Day");
+
+        Ok(GrabReport::new(vec![path], summary))
+    }
+}
+
 ///
 /// NASA Astronomy Picture of the Day API media type.
 ///
@@ -82,6 +739,13 @@ pub(crate) struct Info {
     /// may be a YouTube video or another arbitrary URL.
     ///
     url: String,
+
+    ///
+    /// The additional fields which are returned depending on the request
+    /// parameters, e.g. `concept_tags` or `thumbnail_url`.
+    ///
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
 }
 
 // The signature of a serialize_with function must follow the pattern:
@@ -162,4 +826,176 @@ impl Info {
     pub(crate) fn url(&self) -> &str {
         self.url.as_str()
     }
+
+    ///
+    /// Get the additional fields of the response.
+    ///
+    #[inline]
+    pub(crate) fn extra(&self) -> &HashMap<String, serde_json::Value> {
+        &self.extra
+    }
+
+    ///
+    /// Get the response field by its name including the additional ones.
+    ///
+    pub(crate) fn field(&self, name: &str) -> Option<serde_json::Value> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(mut fields)) => fields.remove(name),
+            _ => None,
+        }
+        .filter(|value| !value.is_null())
+    }
+}
+
+///
+/// Verify the NASA Astronomy Picture of the Day API key.
+///
+pub(crate) async fn verify_key(client: &reqwest::Client, key: &str) -> Result<(), Error> {
+    let response = client
+        .get(APOD_URL)
+        .query(&[("api_key", key)])
+        .send()
+        .await?;
+
+    match http::check_status(&response) {
+        Err(Error::Unauthorized { .. }) => Err(Error::IllegalNASAKey),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn info_test() {
+        let info: Info = serde_json::from_str(
+            r#"{
+                "date": "2022-08-01",
+                "explanation": "The explanation.",
+                "media_type": "image",
+                "service_version": "v1",
+                "title": "The Title",
+                "url": "https://apod.nasa.gov/apod/image/2208/image.jpg",
+                "concept_tags": ["nebula", "galaxy"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.extra().len(), 1);
+        assert_eq!(info.field("service_version"), Some("v1".into()));
+        assert_eq!(
+            info.field("concept_tags"),
+            Some(serde_json::json!(["nebula", "galaxy"]))
+        );
+        assert_eq!(info.field("copyright"), None);
+
+        // The dumped response is read back with the unknown fields.
+        let dumped: Info = serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+        assert_eq!(dumped.date, info.date);
+        assert_eq!(dumped.extra(), info.extra());
+    }
+
+    #[test]
+    fn explanation_test() {
+        let info: Info = serde_json::from_str(
+            r#"{
+                "date": "2022-08-01",
+                "explanation": "What's happening to this spiral galaxy? Although details remain uncertain, it surely has to do with an ongoing gravitational interaction with the small galaxy.",
+                "media_type": "image",
+                "service_version": "v1",
+                "title": "The Title",
+                "url": "https://apod.nasa.gov/apod/image/2208/image.jpg"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            explanation(Language::English, &info, Some(40)),
+            "**Explanation:** What's happening to\n\
+             this spiral galaxy? Although details\n\
+             remain uncertain, it surely has to do\n\
+             with an ongoing gravitational\n\
+             interaction with the small galaxy.\n"
+        );
+        assert_eq!(
+            explanation(Language::English, &info, None).lines().count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn note_path_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", Uuid::new_v4()));
+        let content = "[Notes]\nRoot = \"/notes\"\nDateFormat = \"%d.%m.%Y\"\n\n\
+                       [\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        tokio::fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        tokio::fs::remove_file(path.as_path()).await.unwrap();
+
+        let date = NaiveDate::from_ymd(2022, 8, 3);
+        let options = GrabOptions::default();
+        assert_eq!(
+            options.note_path(&config, date).unwrap(),
+            Path::new("/notes/Base/Science/Astronomy/APoD/APoD 03.08.2022.md")
+        );
+
+        let options = GrabOptions {
+            collection: Some("%Y".to_string()),
+            ..GrabOptions::default()
+        };
+        assert_eq!(
+            options.note_path(&config, date).unwrap(),
+            Path::new("/notes/Base/Science/Astronomy/APoD/2022/APoD 03.08.2022.md")
+        );
+    }
+
+    #[test]
+    fn collection_name_test() {
+        let date = NaiveDate::from_ymd(2024, 6, 1);
+        assert_eq!(collection_name("%Y", date).unwrap(), "2024");
+        assert_eq!(collection_name("Galaxies", date).unwrap(), "Galaxies");
+        assert_eq!(collection_name("%Y/%m", date).unwrap(), "2024 06");
+        for collection in ["%Q", "%H"] {
+            assert!(matches!(
+                collection_name(collection, date),
+                Err(Error::IllegalDateFormat(_))
+            ));
+        }
+        assert!(matches!(
+            collection_name("..", date),
+            Err(Error::IllegalPath(_))
+        ));
+    }
+
+    #[test]
+    fn video_embed_test() {
+        let url = "https://www.youtube.com/embed/QN2cSGwAD9I?rel=0";
+        let iframe = VideoEmbed::Iframe.markup(url);
+        assert!(matches!(
+            VideoEmbed::Iframe.normalize(iframe.as_str()),
+            (Cow::Borrowed(_), 0)
+        ));
+
+        let content = format!(
+            "Video:\n<iframe width='960' height=\"540\"\n src=\"{}\" allowfullscreen>\n</iframe>\n\
+             <iframe src=\"https://player.vimeo.com/video/1\"></iframe>\n",
+            url
+        );
+        let (normalized, count) = VideoEmbed::Iframe.normalize(content.as_str());
+        assert_eq!(count, 1);
+        assert_eq!(
+            normalized,
+            format!(
+                "Video:\n{}\n<iframe src=\"https://player.vimeo.com/video/1\"></iframe>\n",
+                iframe
+            )
+        );
+
+        let (normalized, count) = VideoEmbed::Markdown.normalize(normalized.as_ref());
+        assert_eq!(count, 1);
+        assert!(normalized.starts_with(format!("Video:\n![]({})\n", url).as_str()));
+    }
 }
@@ -0,0 +1,246 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::error::Error;
+
+///
+/// The layout of the monthly calendar.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    #[default]
+    Table,
+    List,
+    Callout,
+}
+
+impl Style {
+    ///
+    /// Render the calendar of the month linking the days to the daily notes
+    /// named by the date format if the predicate holds.
+    ///
+    pub(crate) fn render<F: Fn(NaiveDate) -> bool>(
+        &self,
+        year: i32,
+        month: u32,
+        weekdays: [&str; 7],
+        date_format: &str,
+        linked: F,
+    ) -> String {
+        let first = NaiveDate::from_ymd(year, month, 1);
+        let link = |day: NaiveDate| linked(day).then(|| day.format(date_format).to_string());
+        match self {
+            Self::Table => table(first, weekdays, &link).join("\n"),
+            Self::List => list(first, weekdays, &link).join("\n"),
+            Self::Callout => {
+                let mut calendar = vec![format!("> [!calendar] {}-{:02}", year, month)];
+                calendar.extend(
+                    table(first, weekdays, &link)
+                        .into_iter()
+                        .map(|line| format!("> {}", line)),
+                );
+                calendar.join("\n")
+            }
+        }
+    }
+}
+
+///
+/// The period covered by the calendar.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Period {
+    Month(u32),
+    Quarter(u32),
+    Year,
+}
+
+impl Period {
+    ///
+    /// Get the months of the period.
+    ///
+    pub(crate) fn months(&self) -> Vec<u32> {
+        match *self {
+            Self::Month(month) => vec![month],
+            Self::Quarter(quarter) => (3 * quarter - 2..=3 * quarter).collect(),
+            Self::Year => (1..=12).collect(),
+        }
+    }
+
+    ///
+    /// Get the name of the note of the period.
+    ///
+    pub(crate) fn name(&self, year: i32) -> String {
+        match *self {
+            Self::Month(month) => format!("{}-{:02}", year, month),
+            Self::Quarter(quarter) => format!("{}-Q{}", year, quarter),
+            Self::Year => year.to_string(),
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = Error;
+
+    ///
+    /// Convert string to the Style instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "list" => Ok(Self::List),
+            "callout" => Ok(Self::Callout),
+            _ => Err(Error::IllegalCalendarStyle(s.to_string())),
+        }
+    }
+}
+
+// Get the days of the month starting from the first one.
+fn days(first: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    first
+        .iter_days()
+        .take_while(move |day| day.month() == first.month())
+}
+
+// Get the day reference linked to the daily note if it is required.
+fn day_ref(day: NaiveDate, link: Option<String>, separator: &str) -> String {
+    match link {
+        Some(link) => format!("[[{}{}{}]]", link, separator, day.day()),
+        None => day.day().to_string(),
+    }
+}
+
+// Render the month as the table of the weeks.
+fn table(
+    first: NaiveDate,
+    weekdays: [&str; 7],
+    link: &dyn Fn(NaiveDate) -> Option<String>,
+) -> Vec<String> {
+    let mut calendar = vec![
+        format!("| {} |", weekdays.join(" | ")),
+        "|:--:|:--:|:--:|:--:|:--:|:--:|:--:|".to_string(),
+    ];
+
+    let mut row = "|".to_string();
+    row.push_str(
+        "    |"
+            .repeat(first.weekday().num_days_from_monday() as usize)
+            .as_str(),
+    );
+
+    let mut last = first;
+    for day in days(first) {
+        row.push_str(format!(" {} |", day_ref(day, link(day), "\\|")).as_str());
+        if day.weekday().num_days_from_monday() == 6 {
+            calendar.push(row);
+            row = "|".to_string();
+        }
+        last = day;
+    }
+
+    let n = last.weekday().num_days_from_monday() as usize;
+    if n < 6 {
+        row.push_str("    |".repeat(6 - n).as_str());
+        calendar.push(row);
+    }
+
+    calendar
+}
+
+// Render the month as the list of the days.
+fn list(
+    first: NaiveDate,
+    weekdays: [&str; 7],
+    link: &dyn Fn(NaiveDate) -> Option<String>,
+) -> Vec<String> {
+    days(first)
+        .map(|day| {
+            format!(
+                "- {} {}",
+                weekdays[day.weekday().num_days_from_monday() as usize],
+                day_ref(day, link(day), "|")
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISO: &str = "%Y-%m-%d";
+
+    #[test]
+    fn render_test() {
+        let weekdays = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+        let calendar = Style::Table.render(2022, 2, weekdays, ISO, |_| true);
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[2],
+            "|    | [[2022-02-01\\|1]] | [[2022-02-02\\|2]] | [[2022-02-03\\|3]] | \
+             [[2022-02-04\\|4]] | [[2022-02-05\\|5]] | [[2022-02-06\\|6]] |"
+        );
+        assert_eq!(
+            lines[6],
+            "| [[2022-02-28\\|28]] |    |    |    |    |    |    |"
+        );
+
+        // The month ending on Sunday has no trailing empty row.
+        let calendar = Style::Table.render(2022, 7, weekdays, ISO, |_| true);
+        assert!(calendar.ends_with("[[2022-07-31\\|31]] |"));
+
+        let calendar = Style::List.render(2022, 2, weekdays, ISO, |_| true);
+        assert_eq!(calendar.lines().count(), 28);
+        assert!(calendar.starts_with("- Tu [[2022-02-01|1]]\n"));
+
+        let calendar = Style::Callout.render(2022, 2, weekdays, ISO, |_| true);
+        assert!(calendar.starts_with("> [!calendar] 2022-02\n> | Mo |"));
+
+        // Only the days with the existing daily notes are linked.
+        let existing = [
+            NaiveDate::from_ymd(2022, 2, 1),
+            NaiveDate::from_ymd(2022, 2, 14),
+        ];
+        let calendar = Style::List.render(2022, 2, weekdays, ISO, |day| existing.contains(&day));
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert_eq!(lines[0], "- Tu [[2022-02-01|1]]");
+        assert_eq!(lines[1], "- We 2");
+        assert_eq!(lines[13], "- Mo [[2022-02-14|14]]");
+        assert_eq!(calendar.matches("[[").count(), 2);
+
+        let calendar = Style::List.render(2022, 2, weekdays, "%Y%m%d", |_| true);
+        assert!(calendar.starts_with("- Tu [[20220201|1]]\n"));
+
+        assert_eq!(Period::Quarter(2).months(), vec![4, 5, 6]);
+        assert_eq!(Period::Quarter(2).name(2024), "2024-Q2");
+        assert_eq!(Period::Year.months().len(), 12);
+    }
+
+    #[test]
+    fn table_cells_test() {
+        let weekdays = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+        for month in 1..=12 {
+            let calendar = Style::Table.render(2024, month, weekdays, ISO, |_| true);
+            for line in calendar.lines() {
+                // Split the row by the pipes which are not escaped.
+                let cells = line.replace("\\|", "").matches('|').count() - 1;
+                assert_eq!(cells, 7, "2024-{:02}: {}", month, line);
+            }
+
+            let days = calendar.matches("[[").count() as u32;
+            assert_eq!(days, days_in_month(2024, month), "2024-{:02}", month);
+        }
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        let next = match month {
+            12 => NaiveDate::from_ymd(year + 1, 1, 1),
+            _ => NaiveDate::from_ymd(year, month + 1, 1),
+        };
+        next.pred().day()
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    fs::{self, File},
+    io::AsyncWriteExt,
+};
+
+use crate::error::Error;
+
+///
+/// The daily notes serializing the concurrent mutations of the same note.
+///
+#[derive(Debug, Default)]
+pub(crate) struct DailyNotes {
+    locks: Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl DailyNotes {
+    ///
+    /// Append the line to the daily note.
+    ///
+    pub(crate) async fn append(&self, path: &Path, line: &str) -> Result<(), Error> {
+        let lock = self.lock(path);
+        let _guard = lock.lock().await;
+
+        // Read content of the daily note.
+        let mut buffer = fs::read_to_string(path).await?;
+        buffer.push_str(line);
+
+        // Write updated content of the daily note.
+        let mut file = File::create(path).await?;
+        file.write_all(buffer.as_bytes()).await?;
+        log::trace!("The daily note \"{}\" has been updated", path.display());
+
+        Ok(())
+    }
+
+    // Get the lock of the daily note.
+    fn lock(&self, path: &Path) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self.locks.lock().unwrap();
+        locks.entry(path.to_path_buf()).or_default().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::future;
+    use uuid::Uuid;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn append_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.as_path()).await.unwrap();
+        let path = root.join("2022-08-01.md");
+        fs::write(path.as_path(), "# 2022-08-01\n").await.unwrap();
+
+        let daily = Arc::new(DailyNotes::default());
+        let tasks = (0..64).map(|number| {
+            let daily = daily.clone();
+            let path = path.clone();
+            tokio::spawn(async move {
+                let line = format!("\n[[TWiR {}]]\n", number);
+                daily.append(path.as_path(), line.as_str()).await
+            })
+        });
+        for result in future::join_all(tasks).await {
+            result.unwrap().unwrap();
+        }
+
+        let content = fs::read_to_string(path.as_path()).await.unwrap();
+        for number in 0..64 {
+            assert!(content.contains(format!("[[TWiR {}]]", number).as_str()));
+        }
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -1,10 +1,87 @@
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     ffi::OsStr,
+    io,
     path::{Path, PathBuf},
 };
 
+use regex::{Captures, Regex};
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+use tokio::fs;
 use uuid::Uuid;
 
+use crate::error::Error;
+
+///
+/// The length of the hyphenated UUID.
+///
+const UUID_LEN: usize = 36;
+
+///
+/// The characters which are not allowed in the note file names.
+///
+const ILLEGAL_CHARS: &[char] = &[
+    '/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+];
+
+///
+/// Replace the characters which are not allowed in the note file names
+/// with the spaces.
+///
+pub(crate) fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if ILLEGAL_CHARS.contains(&c) || c.is_control() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+///
+/// The prefix and the suffix adorning the file names of the grabbed notes.
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct NameAffix {
+    prefix: String,
+    suffix: String,
+}
+
+impl NameAffix {
+    ///
+    /// Create the file name adornment of the sanitized prefix and suffix.
+    ///
+    pub(crate) fn new(prefix: Option<&str>, suffix: Option<&str>) -> Self {
+        Self {
+            prefix: prefix.map(sanitize_name).unwrap_or_default(),
+            suffix: suffix.map(sanitize_name).unwrap_or_default(),
+        }
+    }
+
+    ///
+    /// Adorn the file name of the path keeping the extension.
+    ///
+    pub(crate) fn apply(&self, mut path: PathBuf) -> PathBuf {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            return path;
+        }
+
+        if let Some(stem) = path.file_stem().and_then(OsStr::to_str) {
+            let mut file_name = format!("{}{}{}", self.prefix, stem, self.suffix);
+            if let Some(ext) = path.extension().and_then(OsStr::to_str) {
+                file_name.push('.');
+                file_name.push_str(ext);
+            }
+            path.set_file_name(file_name);
+        }
+
+        path
+    }
+}
+
 ///
 /// The file entry.
 ///
@@ -50,6 +127,19 @@ impl FileEntry {
         None
     }
 
+    ///
+    /// Move the new file into the shard subdirectory of the files directory,
+    /// the new name becomes the path relative to the files directory.
+    ///
+    pub(crate) fn shard<P: AsRef<Path>>(mut self, files_path: P, shard: usize) -> Self {
+        if shard > 0 {
+            self.new_name = shard_name(self.new_name.as_str(), shard);
+            self.new_path = files_path.as_ref().join(self.new_name.as_str());
+        }
+
+        self
+    }
+
     ///
     /// Get the old file path.
     ///
@@ -81,4 +171,384 @@ impl FileEntry {
     pub(crate) fn new_name(&self) -> &str {
         self.new_name.as_str()
     }
+
+    ///
+    /// Create the file entry of the UUID named file renamed to the lowercase
+    /// name, `None` if the name is not UUID or it is already lowercase.
+    ///
+    pub(crate) fn canonical<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let stem = path.as_ref().file_stem().and_then(OsStr::to_str)?;
+        if stem.len() != UUID_LEN || stem == stem.to_lowercase() {
+            return None;
+        }
+
+        let id = Uuid::parse_str(stem).ok()?;
+        Self::new(path, id)
+    }
+
+    ///
+    /// Rename the old file to the new one creating the shard subdirectory,
+    /// the existing new file is never overwritten.
+    ///
+    pub(crate) async fn rename(&self) -> Result<(), Error> {
+        if self.new_path.exists() && self.is_taken().await? {
+            return Err(Error::IoError(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "cannot rename \"{}\", the file \"{}\" already exists",
+                    self.old_path.display(),
+                    self.new_path.display()
+                ),
+            )));
+        }
+
+        if let Some(parent) = self.new_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::rename(self.old_path.as_path(), self.new_path.as_path()).await?;
+
+        Ok(())
+    }
+
+    // Check if the existing new path is another file, the case insensitive
+    // file systems find the old file by the name differing in case only.
+    async fn is_taken(&self) -> Result<bool, Error> {
+        if !self
+            .old_path
+            .as_os_str()
+            .eq_ignore_ascii_case(self.new_path.as_os_str())
+        {
+            return Ok(true);
+        }
+
+        if let Some(parent) = self.new_path.parent() {
+            let mut entries = fs::read_dir(parent).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.file_name().to_str() == Some(self.new_name.as_str()) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+///
+/// Get the path of the attached file relative to the files directory, the
+/// subdirectory is named by the first characters of the file name. The files
+/// having the too short names and the zero shard are kept flat.
+///
+pub(crate) fn shard_name(file_name: &str, shard: usize) -> String {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+
+    match stem.char_indices().nth(shard) {
+        Some((end, _)) if shard > 0 => format!("{}/{}", stem[..end].to_lowercase(), file_name),
+        _ => file_name.to_string(),
+    }
+}
+
+///
+/// Check if the attached file is moved by the resharding, it is named by the
+/// UUID and placed either in the files directory or in its shard subdirectory
+/// named by the first characters of the file name. The path is relative to
+/// the files directory.
+///
+pub(crate) fn is_shardable(path: &Path) -> bool {
+    let components: Vec<&str> = path
+        .components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .collect();
+    let (dir, file_name) = match components[..] {
+        [file_name] => ("", file_name),
+        [dir, file_name] => (dir, file_name),
+        _ => return false,
+    };
+
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+    Uuid::parse_str(stem).is_ok() && stem.to_lowercase().starts_with(&dir.to_lowercase())
+}
+
+///
+/// Replace the targets of the wiki links to the moved files. The files are
+/// matched by the path relative to the files directory, by the path relative
+/// to the notes set root which is kept so or by the bare file name.
+///
+pub(crate) fn reshard_links<'a>(
+    content: &'a str,
+    files_dir: &str,
+    moved: &HashMap<String, String>,
+) -> Cow<'a, str> {
+    let re = Regex::new(r"(?P<open>!?\[\[)(?P<target>[^\[\]\n|#^]+)").unwrap();
+    let by_name: HashMap<&str, &str> = moved
+        .keys()
+        .map(|path| (path.rsplit('/').next().unwrap_or(path), path.as_str()))
+        .collect();
+    let prefix = format!("{}/", files_dir);
+
+    let mut changed = false;
+    let resharded = re.replace_all(content, |caps: &Captures| {
+        let target = caps["target"].trim();
+        let new_target = match target.strip_prefix(prefix.as_str()) {
+            Some(path) => moved
+                .get(path)
+                .map(|new_name| format!("{}{}", prefix, new_name)),
+            None if target.contains('/') => moved.get(target).cloned(),
+            None => by_name.get(target).map(|path| moved[*path].clone()),
+        };
+
+        match new_target {
+            Some(new_target) if new_target != target => {
+                changed = true;
+                format!("{}{}", &caps["open"], new_target)
+            }
+            _ => caps[0].to_string(),
+        }
+    });
+
+    if changed {
+        Cow::Owned(resharded.into_owned())
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
+///
+/// The template entry value.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TemplateEntry {
+    ///
+    /// The single optional value.
+    ///
+    Single(Option<String>),
+
+    ///
+    /// The multiple values.
+    ///
+    Multiple(Vec<String>),
+}
+
+impl TemplateEntry {
+    ///
+    /// Create the single value template entry.
+    ///
+    #[inline]
+    pub(crate) fn single(value: Option<String>) -> Self {
+        Self::Single(value)
+    }
+
+    ///
+    /// Create the multiple values template entry.
+    ///
+    #[inline]
+    pub(crate) fn multiple(values: Vec<String>) -> Self {
+        Self::Multiple(values)
+    }
+
+    ///
+    /// Get the iterator to iterate the entry values.
+    ///
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &str> {
+        let values: Vec<&str> = match self {
+            Self::Single(value) => value.iter().map(String::as_str).collect(),
+            Self::Multiple(values) => values.iter().map(String::as_str).collect(),
+        };
+
+        values.into_iter()
+    }
+
+    ///
+    /// Get the iterator to iterate the entry values written as the double
+    /// quoted front matter scalars, the quotes and the backslashes are escaped.
+    ///
+    pub(crate) fn quoted(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter()
+            .map(|value| serde_json::to_string(value).unwrap_or_default())
+    }
+}
+
+impl Serialize for TemplateEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Single(None) => serializer.serialize_none(),
+            Self::Single(Some(value)) => serializer.serialize_str(value),
+            Self::Multiple(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::Value;
+
+    use super::*;
+
+    #[test]
+    fn shard_test() {
+        let id = Uuid::parse_str("ABCD1234-0000-4000-8000-000000000000").unwrap();
+        let entry = FileEntry::new("/notes/Files/Image.PNG", id)
+            .unwrap()
+            .shard("/notes/Files", 2);
+        assert_eq!(
+            entry.new_name(),
+            "ab/abcd1234-0000-4000-8000-000000000000.PNG"
+        );
+        assert_eq!(
+            entry.new_path(),
+            Path::new("/notes/Files/ab/abcd1234-0000-4000-8000-000000000000.PNG")
+        );
+
+        assert_eq!(shard_name("image.png", 0), "image.png");
+        assert_eq!(shard_name("a.png", 2), "a.png");
+
+        let uuid = "abcd1234-0000-4000-8000-000000000000.png";
+        assert!(is_shardable(Path::new(uuid)));
+        assert!(is_shardable(Path::new("ab").join(uuid).as_path()));
+        assert!(!is_shardable(Path::new("pdfs").join(uuid).as_path()));
+        assert!(!is_shardable(Path::new("ab/cd").join(uuid).as_path()));
+        assert!(!is_shardable(Path::new("image.png")));
+
+        let moved = HashMap::from([
+            ("a1.png".to_string(), "a/a1.png".to_string()),
+            ("b/b2.png".to_string(), "b2.png".to_string()),
+        ]);
+        let content = "![[a1.png]] [[x/a1.png|alias]] ![[b/b2.png#page]] [[a/a1.png]] \
+                       [[Files/a1.png]] [[Files/b/b2.png]] [[c.png]]";
+        assert_eq!(
+            reshard_links(content, "Files", &moved),
+            "![[a/a1.png]] [[x/a1.png|alias]] ![[b2.png#page]] [[a/a1.png]] \
+             [[Files/a/a1.png]] [[Files/b2.png]] [[c.png]]"
+        );
+        assert!(matches!(
+            reshard_links("![[a/a1.png]] [[x/a1.png]] [[c.png]]", "Files", &moved),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn name_affix_test() {
+        let path = PathBuf::from("/notes/APoD/APoD 2024-06-01.md");
+        assert_eq!(NameAffix::default().apply(path.clone()), path);
+        assert_eq!(
+            NameAffix::new(Some("2024 - "), Some(" (draft)")).apply(path),
+            Path::new("/notes/APoD/2024 - APoD 2024-06-01 (draft).md")
+        );
+        assert_eq!(
+            NameAffix::new(Some("../"), Some(":x")).apply(PathBuf::from("/notes/TWiR 1.md")),
+            Path::new("/notes/.. TWiR 1 x.md")
+        );
+    }
+
+    #[tokio::test]
+    async fn rename_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.as_path()).await.unwrap();
+        let id = Uuid::new_v4();
+        let path = root.join("image.png");
+        fs::write(path.as_path(), "image").await.unwrap();
+
+        // The taken name is reported leaving both files intact.
+        let taken = root.join(format!("{}.png", id));
+        fs::write(taken.as_path(), "taken").await.unwrap();
+        let entry = FileEntry::new(path.as_path(), id).unwrap();
+        assert!(matches!(entry.rename().await, Err(Error::IoError(_))));
+        assert_eq!(fs::read_to_string(path.as_path()).await.unwrap(), "image");
+        assert_eq!(fs::read_to_string(taken.as_path()).await.unwrap(), "taken");
+
+        let entry = FileEntry::new(path.as_path(), Uuid::new_v4())
+            .unwrap()
+            .shard(root.as_path(), 2);
+        entry.rename().await.unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(entry.new_path()).await.unwrap(), "image");
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn canonical_test() {
+        assert!(FileEntry::canonical("/notes/Files/image.png").is_none());
+        assert!(
+            FileEntry::canonical("/notes/Files/abcd1234-0000-4000-8000-000000000000.png").is_none()
+        );
+        assert!(
+            FileEntry::canonical("/notes/Files/ABCD12340000400080000000000000000.png").is_none()
+        );
+
+        let entry =
+            FileEntry::canonical("/notes/Files/ab/ABCD1234-0000-4000-8000-00000000000F.PNG")
+                .unwrap();
+        assert_eq!(entry.old_name(), "ABCD1234-0000-4000-8000-00000000000F.PNG");
+        assert_eq!(
+            entry.new_path(),
+            Path::new("/notes/Files/ab/abcd1234-0000-4000-8000-00000000000f.PNG")
+        );
+
+        // The lowercase file is not overwritten on the case sensitive file systems.
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.as_path()).await.unwrap();
+        let id = Uuid::new_v4();
+        let upper = root.join(format!("{}.png", id.to_string().to_uppercase()));
+        fs::write(upper.as_path(), "upper").await.unwrap();
+        let entry = FileEntry::canonical(upper.as_path()).unwrap();
+        fs::write(entry.new_path(), "lower").await.unwrap();
+        if fs::read_to_string(upper.as_path()).await.unwrap() == "upper" {
+            assert!(entry.rename().await.is_err());
+            assert_eq!(fs::read_to_string(entry.new_path()).await.unwrap(), "lower");
+        }
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[test]
+    fn template_entry_test() {
+        let entry = TemplateEntry::single(None);
+        assert_eq!(serde_yaml::to_value(&entry).unwrap(), Value::Null);
+        assert_eq!(entry.iter().count(), 0);
+
+        let entry = TemplateEntry::single(Some("news/apod".to_string()));
+        assert_eq!(
+            serde_yaml::to_value(&entry).unwrap(),
+            Value::String("news/apod".to_string())
+        );
+        assert_eq!(entry.iter().collect::<Vec<_>>(), vec!["news/apod"]);
+
+        let entry = TemplateEntry::multiple(vec!["rust".to_string(), "news/twir".to_string()]);
+        assert_eq!(
+            serde_yaml::to_value(&entry).unwrap(),
+            Value::Sequence(vec![
+                Value::String("rust".to_string()),
+                Value::String("news/twir".to_string()),
+            ])
+        );
+        assert_eq!(entry.iter().collect::<Vec<_>>(), vec!["rust", "news/twir"]);
+
+        let title = "The \"Pillars\" of C:\\Creation";
+        let entry = TemplateEntry::multiple(vec![title.to_string()]);
+        let quoted: Vec<String> = entry.quoted().collect();
+        assert_eq!(quoted, vec![r#""The \"Pillars\" of C:\\Creation""#]);
+        assert_eq!(serde_yaml::from_str::<String>(&quoted[0]).unwrap(), title);
+
+        let entry = TemplateEntry::multiple(Vec::new());
+        assert_eq!(
+            serde_yaml::to_value(&entry).unwrap(),
+            Value::Sequence(Vec::new())
+        );
+    }
 }
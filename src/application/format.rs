@@ -0,0 +1,28 @@
+use std::str::FromStr;
+
+use crate::error::Error;
+
+///
+/// The format of the command output.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    ///
+    /// Convert string to the Format instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::IllegalFormat(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use git2::{ErrorCode, Repository, Signature, Sort, StatusOptions};
+
+use crate::{application::Application, error::Error};
+
+///
+/// The paths changed by the command, only they are committed.
+///
+#[derive(Debug, Default)]
+pub(crate) struct Changes {
+    paths: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl Changes {
+    ///
+    /// Record the created, written or removed path.
+    ///
+    pub(crate) fn record(&self, path: &Path) {
+        self.paths.lock().unwrap().insert(PathBuf::from(path));
+    }
+
+    ///
+    /// Get the recorded paths.
+    ///
+    pub(crate) fn paths(&self) -> Vec<PathBuf> {
+        self.paths.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+///
+/// Commit the changes of the given paths of the notes set to the git
+/// repository containing the notes root, the other changes are left
+/// as is. Returns the list of the committed paths.
+///
+pub(crate) async fn commit(
+    root: &Path,
+    message: &str,
+    paths: Vec<PathBuf>,
+) -> Result<Vec<PathBuf>, Error> {
+    if paths.is_empty() {
+        log::debug!("Nothing has been written, skip commit");
+        return Ok(paths);
+    }
+
+    let root = PathBuf::from(root);
+    let message = message.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        commit_blocking(root.as_path(), message.as_str(), paths.as_slice())
+    })
+    .await?
+}
+
+///
+/// The commit times of the files of the git repository.
+///
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    times: HashMap<PathBuf, (i64, i64)>,
+}
+
+impl History {
+    ///
+    /// Get the time in seconds of the first commit touching the file.
+    ///
+    pub(crate) fn created(&self, path: &Path) -> Option<i64> {
+        self.entry(path).map(|(created, _)| created)
+    }
+
+    ///
+    /// Get the time in seconds of the last commit touching the file.
+    ///
+    pub(crate) fn modified(&self, path: &Path) -> Option<i64> {
+        self.entry(path).map(|(_, modified)| modified)
+    }
+
+    // Get the commit times of the file by its canonical path.
+    fn entry(&self, path: &Path) -> Option<(i64, i64)> {
+        let path = path.canonicalize().ok()?;
+        self.times.get(path.as_path()).copied()
+    }
+}
+
+///
+/// Collect the commit times of the files of the git repository containing
+/// the notes root. The history is empty if there is no repository.
+///
+pub(crate) async fn history(root: &Path) -> Result<History, Error> {
+    let root = PathBuf::from(root);
+
+    tokio::task::spawn_blocking(move || history_blocking(root.as_path())).await?
+}
+
+// Collect the commit times synchronously.
+fn history_blocking(root: &Path) -> Result<History, Error> {
+    let repo = match Repository::discover(root) {
+        Ok(repo) => repo,
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            log::debug!(
+                "The notes root \"{}\" is not a git repository, use the file times",
+                root.display()
+            );
+            return Ok(History::default());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let workdir = match repo.workdir() {
+        Some(workdir) => workdir.canonicalize()?,
+        None => return Ok(History::default()),
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME | Sort::REVERSE)?;
+    match revwalk.push_head() {
+        Ok(()) => {}
+        Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => {
+            return Ok(History::default())
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    // Walk the commits from the oldest one, the merge commits are skipped
+    // as they repeat the changes of the merged branches.
+    let mut history = History::default();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+
+        let parent = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+        let diff = repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), None)?;
+
+        let time = commit.time().seconds();
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path() {
+                history
+                    .times
+                    .entry(workdir.join(path))
+                    .or_insert((time, time))
+                    .1 = time;
+            }
+        }
+    }
+
+    Ok(history)
+}
+
+// Get the canonical path of the file which may be removed already.
+fn canonical(path: &Path) -> Option<PathBuf> {
+    if let Ok(path) = path.canonicalize() {
+        return Some(path);
+    }
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize().ok()?,
+        _ => std::env::current_dir().ok()?,
+    };
+    Some(parent.join(path.file_name()?))
+}
+
+// Commit the changes synchronously.
+fn commit_blocking(root: &Path, message: &str, changed: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let repo = match Repository::discover(root) {
+        Ok(repo) => repo,
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            log::debug!(
+                "The notes root \"{}\" is not a git repository, skip commit",
+                root.display()
+            );
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let workdir = match repo.workdir() {
+        Some(workdir) => PathBuf::from(workdir),
+        None => {
+            log::warn!(
+                "Cannot commit into the bare repository \"{}\"",
+                repo.path().display()
+            );
+            return Ok(Vec::new());
+        }
+    };
+    let workdir = workdir.canonicalize()?;
+    let prefix = root
+        .canonicalize()?
+        .strip_prefix(workdir.as_path())
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    // The changed paths relative to the working directory.
+    let changed: HashSet<PathBuf> = changed
+        .iter()
+        .filter_map(|path| canonical(path))
+        .filter_map(|path| path.strip_prefix(workdir.as_path()).map(PathBuf::from).ok())
+        .collect();
+
+    // Collect the changed paths inside the notes set.
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+
+    let mut paths: Vec<PathBuf> = repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .filter_map(|e| e.path().map(PathBuf::from))
+        .filter(|path| path.starts_with(prefix.as_path()) && changed.contains(path))
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        log::debug!("There are no changes to commit");
+        return Ok(paths);
+    }
+
+    // Stage the changed paths.
+    let mut index = repo.index()?;
+    for path in &paths {
+        if workdir.join(path).exists() {
+            index.add_path(path.as_path())?;
+        } else {
+            index.remove_path(path.as_path())?;
+        }
+    }
+    index.write()?;
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = match repo.signature() {
+        Ok(signature) => signature,
+        Err(_) => Signature::now(Application::NAME, "nta@localhost")?,
+    };
+
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let parents: Vec<_> = parent.iter().collect();
+
+    let message = format!(
+        "{}\n\n{}\n",
+        message.trim(),
+        paths
+            .iter()
+            .map(|path| format!("- {}", path.display()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message.as_str(),
+        &tree,
+        parents.as_slice(),
+    )?;
+    log::info!("The changes have been committed as {}", oid);
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::fs;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn commit_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.as_path()).await.unwrap();
+        let repo = Repository::init(root.as_path()).unwrap();
+
+        let (written, edited) = (root.join("written.md"), root.join("edited.md"));
+        fs::write(written.as_path(), "written").await.unwrap();
+        fs::write(edited.as_path(), "edited").await.unwrap();
+
+        // Nothing is committed if nothing has been written.
+        assert!(commit(root.as_path(), "Update", Vec::new())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The unrelated changes of the user are left as is.
+        let committed = commit(root.as_path(), "Update", vec![written])
+            .await
+            .unwrap();
+        assert_eq!(committed, vec![PathBuf::from("written.md")]);
+        let statuses = repo.statuses(None).unwrap();
+        let dirty: Vec<_> = statuses
+            .iter()
+            .filter_map(|e| e.path().map(String::from))
+            .collect();
+        assert_eq!(dirty, vec!["edited.md".to_string()]);
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+
+use crate::{
+    application::{
+        apod, daily::DailyNotes, entry::NameAffix, git::Changes, raindrop, summary::Summary,
+        template::Templates, twir, Application,
+    },
+    cli::Note,
+    config::Config,
+    error::Error,
+};
+
+///
+/// The context of the notes grabbing.
+///
+pub(crate) struct GrabContext<'a> {
+    application: &'a Application,
+    templates: &'a Templates,
+    since: Option<DateTime<Local>>,
+}
+
+impl<'a> GrabContext<'a> {
+    ///
+    /// Create the grabbing context, only the items newer than the given time
+    /// are grabbed if it is set.
+    ///
+    pub(crate) fn new(
+        application: &'a Application,
+        templates: &'a Templates,
+        since: Option<DateTime<Local>>,
+    ) -> Self {
+        Self {
+            application,
+            templates,
+            since,
+        }
+    }
+
+    ///
+    /// The configuration of the notes set.
+    ///
+    #[inline]
+    pub(crate) fn config(&self) -> &'a Config {
+        &self.application.config
+    }
+
+    ///
+    /// The HTTP client of the grabbing.
+    ///
+    #[inline]
+    pub(crate) fn client(&self) -> &'a reqwest::Client {
+        &self.application.client
+    }
+
+    ///
+    /// The daily notes updated with the back-links.
+    ///
+    #[inline]
+    pub(crate) fn daily(&self) -> &'a DailyNotes {
+        &self.application.daily
+    }
+
+    ///
+    /// The recorder of the paths to commit.
+    ///
+    #[inline]
+    pub(crate) fn changes(&self) -> &'a Changes {
+        &self.application.changes
+    }
+
+    ///
+    /// The cache directory of the application.
+    ///
+    #[inline]
+    pub(crate) fn cache_path(&self) -> &'a Path {
+        self.application.cache_path.as_path()
+    }
+
+    ///
+    /// The note templates.
+    ///
+    #[inline]
+    pub(crate) fn templates(&self) -> &'a Templates {
+        self.templates
+    }
+
+    ///
+    /// The time of the last successful grab of the source if only the newer
+    /// items are grabbed.
+    ///
+    #[inline]
+    pub(crate) fn since(&self) -> Option<DateTime<Local>> {
+        self.since
+    }
+}
+
+///
+/// The report of the notes grabbing.
+///
+#[derive(Debug, Default)]
+pub(crate) struct GrabReport {
+    paths: Vec<PathBuf>,
+    summary: Summary,
+}
+
+impl GrabReport {
+    ///
+    /// Create the report of the grabbed notes.
+    ///
+    pub(crate) fn new(paths: Vec<PathBuf>, summary: Summary) -> Self {
+        Self { paths, summary }
+    }
+
+    ///
+    /// Get the grabbed note paths.
+    ///
+    #[inline]
+    pub(crate) fn paths(&self) -> &[PathBuf] {
+        self.paths.as_slice()
+    }
+
+    ///
+    /// Get the latest grabbed note path.
+    ///
+    #[inline]
+    pub(crate) fn last_path(&self) -> Option<&Path> {
+        self.paths.last().map(PathBuf::as_path)
+    }
+
+    ///
+    /// Take the summary of the grabbing.
+    ///
+    #[inline]
+    pub(crate) fn into_summary(self) -> Summary {
+        self.summary
+    }
+}
+
+///
+/// The source of the grabbed notes.
+///
+#[async_trait(?Send)]
+pub(crate) trait Grabber {
+    ///
+    /// The name of the source recorded in the grab state.
+    ///
+    fn source(&self) -> &'static str;
+
+    ///
+    /// Check if the notes are only shown without grabbing them.
+    ///
+    fn is_dry_run(&self) -> bool {
+        false
+    }
+
+    ///
+    /// Grab the notes to the notes set.
+    ///
+    async fn grab(&self, context: &GrabContext<'_>) -> Result<GrabReport, Error>;
+}
+
+///
+/// Create the grabber of the note.
+///
+pub(crate) fn new(note: &Note, config: &Config, affix: NameAffix) -> Box<dyn Grabber> {
+    match note {
+        // Grab NASA Astronomy Picture of the Day note.
+        Note::APoD {
+            update_daily,
+            no_download,
+            date_tag,
+            banner,
+            local_date,
+            since_config_last,
+            explanation_only,
+            set_alias,
+            no_alias,
+            caption_template,
+            excerpt,
+            json_out,
+            wrap,
+            collection,
+            ..
+        } => Box::new(apod::APoDGrabber::new(apod::GrabOptions {
+            update_daily: *update_daily,
+            no_download: *no_download,
+            date_tag: *date_tag,
+            banner: *banner || config.apod_banner(),
+            // The note of today is checked by the local date.
+            local_date: *local_date || *since_config_last,
+            since_config_last: *since_config_last,
+            explanation_only: *explanation_only,
+            // The last one of the overriding flags wins.
+            no_alias: *no_alias && !*set_alias,
+            caption_template: caption_template.clone(),
+            excerpt: *excerpt,
+            json_out: json_out.clone(),
+            wrap: *wrap,
+            affix,
+            collection: collection.clone(),
+        })),
+
+        // Grab This Week in Rust notes.
+        Note::TWiR {
+            issues,
+            update_daily,
+            toc,
+            toc_depth,
+            keep_html,
+            select,
+            title_from,
+            merge_into,
+            wrap,
+            dry_run,
+            wait_rate_limit,
+        } => Box::new(twir::TWiRGrabber::new(
+            *issues,
+            twir::GrabOptions {
+                update_daily: *update_daily,
+                toc_depth: toc.then_some(*toc_depth),
+                keep_html: *keep_html,
+                selector: select.clone(),
+                title_from: *title_from,
+                merge_into: merge_into.as_ref().map(|path| config.root().join(path)),
+                wrap: *wrap,
+                dry_run: *dry_run,
+                wait_rate_limit: *wait_rate_limit,
+                affix,
+            },
+        )),
+
+        // Grab Raindrop bookmarks notes.
+        Note::Raindrop { tags } => {
+            Box::new(raindrop::RaindropGrabber::new(raindrop::GrabOptions {
+                tags: tags.clone(),
+                affix,
+            }))
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    path::Path,
+    str::FromStr,
+};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::Error;
+
+///
+/// The output format of the link graph.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Json,
+}
+
+impl FromStr for GraphFormat {
+    type Err = Error;
+
+    ///
+    /// Convert string to the GraphFormat instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "dot" => Ok(Self::Dot),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::IllegalGraphFormat(s.to_string())),
+        }
+    }
+}
+
+///
+/// The kind of the graph node.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NodeKind {
+    Note,
+    Attachment,
+}
+
+///
+/// The note or the attachment of the notes set.
+///
+#[derive(Debug, Serialize)]
+struct Node {
+    id: String,
+    title: String,
+    kind: NodeKind,
+}
+
+///
+/// The link from the note to the note or to the attachment.
+///
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+struct Edge {
+    source: String,
+    target: String,
+}
+
+///
+/// The link graph of the notes set.
+///
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct Graph {
+    nodes: Vec<Node>,
+    edges: BTreeSet<Edge>,
+
+    #[serde(skip)]
+    by_path: BTreeMap<String, usize>,
+
+    #[serde(skip)]
+    by_name: BTreeMap<String, usize>,
+
+    #[serde(skip)]
+    extensions: Vec<String>,
+}
+
+impl Graph {
+    ///
+    /// Create the empty graph of the notes with the extensions.
+    ///
+    pub(crate) fn new(extensions: &[&str]) -> Self {
+        Self {
+            extensions: extensions
+                .iter()
+                .map(|extension| extension.trim_start_matches('.').to_string())
+                .collect(),
+            ..Self::default()
+        }
+    }
+    ///
+    /// Add the node identified by the path relative to the notes set root.
+    ///
+    pub(crate) fn add_node(&mut self, id: &str, kind: NodeKind) {
+        let index = self.nodes.len();
+        let name = id.rsplit('/').next().unwrap_or(id);
+        let (path, title) = match kind {
+            NodeKind::Note => (self.strip_extension(id), self.strip_extension(name)),
+            NodeKind::Attachment => (id, name),
+        };
+
+        self.by_path.entry(path.to_lowercase()).or_insert(index);
+        self.by_name.entry(title.to_lowercase()).or_insert(index);
+        self.nodes.push(Node {
+            id: id.to_string(),
+            title: title.to_string(),
+            kind,
+        });
+    }
+
+    ///
+    /// Add the edge from the node to the link target resolved like the wiki
+    /// links are, by the path first and by the name then. Returns `false` if
+    /// the target is not found.
+    ///
+    pub(crate) fn add_link(&mut self, source: &str, target: &str) -> bool {
+        let target = target.trim().trim_start_matches('/').to_lowercase();
+        let index = self
+            .by_path
+            .get(target.as_str())
+            .or_else(|| self.by_path.get(self.strip_extension(target.as_str())))
+            .or_else(|| self.by_name.get(target.as_str()))
+            .or_else(|| self.by_name.get(self.strip_extension(target.as_str())));
+
+        match index {
+            Some(&index) => {
+                self.edges.insert(Edge {
+                    source: source.to_string(),
+                    target: self.nodes[index].id.clone(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Strip the note extension from the path or the name.
+    fn strip_extension<'a>(&self, path: &'a str) -> &'a str {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(OsStr::to_str)
+            .filter(|extension| {
+                self.extensions
+                    .iter()
+                    .any(|e| e.eq_ignore_ascii_case(extension))
+            });
+
+        match extension {
+            Some(extension) => &path[..path.len() - extension.len() - 1],
+            None => path,
+        }
+    }
+
+    ///
+    /// Get the notes having neither the inbound nor the outbound links.
+    ///
+    pub(crate) fn orphans(&self) -> Vec<&str> {
+        let linked: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.source != edge.target)
+            .flat_map(|edge| [edge.source.as_str(), edge.target.as_str()])
+            .collect();
+
+        self.nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Note && !linked.contains(node.id.as_str()))
+            .map(|node| node.id.as_str())
+            .collect()
+    }
+
+    ///
+    /// Render the graph in the Graphviz DOT language.
+    ///
+    pub(crate) fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph notes {".to_string()];
+        for node in self.nodes.iter() {
+            let shape = match node.kind {
+                NodeKind::Note => "ellipse",
+                NodeKind::Attachment => "box",
+            };
+            lines.push(format!(
+                "    {} [label={}, shape={}];",
+                quote(node.id.as_str()),
+                quote(node.title.as_str()),
+                shape
+            ));
+        }
+        for edge in self.edges.iter() {
+            lines.push(format!(
+                "    {} -> {};",
+                quote(edge.source.as_str()),
+                quote(edge.target.as_str())
+            ));
+        }
+        lines.push("}".to_string());
+
+        lines.join("\n")
+    }
+}
+
+///
+/// Get the graph node identifier of the path relative to the notes set root.
+///
+pub(crate) fn node_id(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+///
+/// Extract the targets of the wiki links and the embeds of the note content,
+/// the headings, the blocks and the aliases are dropped.
+///
+pub(crate) fn wiki_links(content: &str) -> Vec<&str> {
+    let re = Regex::new(r"!?\[\[(?P<target>[^\[\]\n|#^]*)[^\[\]\n]*\]\]").unwrap();
+    re.captures_iter(content)
+        .filter_map(|caps| caps.name("target"))
+        .map(|target| target.as_str().trim())
+        .filter(|target| !target.is_empty())
+        .collect()
+}
+
+///
+/// Extract the targets of the markdown links and images of the note content,
+/// the link titles and the fragments are dropped.
+///
+pub(crate) fn markdown_links(content: &str) -> Vec<&str> {
+    let re =
+        Regex::new(r"!?\[[^\]\n]*\]\(\s*(?:<(?P<quoted>[^>\n]*)>|(?P<target>[^)\s#]*))").unwrap();
+    re.captures_iter(content)
+        .filter_map(|caps| caps.name("quoted").or_else(|| caps.name("target")))
+        .map(|target| target.as_str().trim())
+        .filter(|target| !target.is_empty())
+        .collect()
+}
+
+///
+/// Get the file names linked by the wiki and markdown links of the note content.
+///
+pub(crate) fn linked_names(content: &str) -> BTreeSet<&str> {
+    wiki_links(content)
+        .into_iter()
+        .chain(markdown_links(content))
+        .filter_map(|target| target.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// Quote the DOT identifier.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linked_names_test() {
+        let content = "![[apod-x.jpg]], ![image](Files/y.png \"Y\"), [z](<Files/a z.gif>), \
+                       [site](https://example.com/) and x.jpg";
+        assert_eq!(
+            linked_names(content).into_iter().collect::<Vec<_>>(),
+            vec!["a z.gif", "apod-x.jpg", "y.png"]
+        );
+    }
+
+    #[test]
+    fn graph_test() {
+        let content = "See [[Notes/B|the note]], [[b#Heading]] and ![[image.png]], \
+                       not [[Missing]].";
+        assert_eq!(
+            wiki_links(content),
+            vec!["Notes/B", "b", "image.png", "Missing"]
+        );
+
+        let mut graph = Graph::new(&["md", ".markdown"]);
+        graph.add_node("A.md", NodeKind::Note);
+        graph.add_node("Notes/B.md", NodeKind::Note);
+        graph.add_node("Files/image.png", NodeKind::Attachment);
+
+        let resolved: Vec<bool> = wiki_links(content)
+            .into_iter()
+            .map(|target| graph.add_link("A.md", target))
+            .collect();
+        assert_eq!(resolved, vec![true, true, true, false]);
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph notes {\n    \"A.md\" [label=\"A\", shape=ellipse];\n    \
+             \"Notes/B.md\" [label=\"B\", shape=ellipse];\n    \
+             \"Files/image.png\" [label=\"image.png\", shape=box];\n    \
+             \"A.md\" -> \"Files/image.png\";\n    \"A.md\" -> \"Notes/B.md\";\n}"
+        );
+
+        let value = serde_json::to_value(&graph).unwrap();
+        assert_eq!(value["nodes"][2]["kind"], "attachment");
+        assert_eq!(value["edges"][1]["target"], "Notes/B.md");
+
+        graph.add_node("C.md", NodeKind::Note);
+        graph.add_link("C.md", "C");
+        assert_eq!(graph.orphans(), vec!["C.md"]);
+
+        // The configured note extensions are stripped too.
+        graph.add_node("Notes/D.markdown", NodeKind::Note);
+        assert!(graph.add_link("C.md", "D"));
+        assert!(graph.add_link("C.md", "notes/d.markdown"));
+    }
+}
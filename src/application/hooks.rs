@@ -0,0 +1,84 @@
+use std::{path::Path, time::Duration};
+
+use tokio::process::Command;
+
+use crate::error::Error;
+
+///
+/// Run the hook script with the note path. The script is killed after the
+/// timeout, its failure is only logged since the note has been already written.
+///
+pub(crate) async fn run(
+    script: &Path,
+    note: &Path,
+    root: &Path,
+    timeout: Duration,
+) -> Result<bool, Error> {
+    log::debug!(
+        "Run the hook \"{}\" for the note \"{}\"",
+        script.display(),
+        note.display()
+    );
+
+    let mut child = Command::new(script)
+        .arg(note)
+        .current_dir(root)
+        .kill_on_drop(true)
+        .spawn()?;
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => {
+            let status = status?;
+            if !status.success() {
+                log::warn!(
+                    "The hook \"{}\" of the note \"{}\" has been exited with {}",
+                    script.display(),
+                    note.display(),
+                    status
+                );
+            }
+
+            Ok(status.success())
+        }
+        Err(_) => {
+            log::warn!(
+                "The hook \"{}\" of the note \"{}\" has been killed after {} seconds",
+                script.display(),
+                note.display(),
+                timeout.as_secs()
+            );
+            child.kill().await?;
+
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn run_test() {
+        let root = std::env::temp_dir();
+        let note = root.join(format!("nta-{}.md", Uuid::new_v4()));
+        let timeout = Duration::from_secs(5);
+
+        assert!(run(Path::new("true"), &note, &root, timeout).await.unwrap());
+        assert!(!run(Path::new("false"), &note, &root, timeout)
+            .await
+            .unwrap());
+        assert!(!run(
+            Path::new("sleep"),
+            Path::new("5"),
+            &root,
+            Duration::from_millis(100)
+        )
+        .await
+        .unwrap());
+        assert!(run(Path::new("nta-missing-hook"), &note, &root, timeout)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::{
+    fs::{self, File},
+    io::AsyncWriteExt,
+};
+
+use crate::error::Error;
+
+///
+/// The maximum number of the waits for the rate limit to pass.
+///
+const MAX_RATE_LIMIT_WAITS: usize = 3;
+
+///
+/// The longest wait for the rate limit to pass, the longer ones fail the request.
+///
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
+
+///
+/// Create the HTTP client transparently decoding the compressed responses.
+///
+pub(crate) fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .gzip(true)
+        .brotli(true)
+        .build()
+        .unwrap_or_else(|e| {
+            log::warn!("The default HTTP client is used: {}", e);
+            reqwest::Client::new()
+        })
+}
+
+///
+/// Send the request, the rate limited one is sent again after the delay
+/// requested by the server if the waiting is allowed.
+///
+pub(crate) async fn send(
+    mut request: reqwest::RequestBuilder,
+    wait: bool,
+) -> Result<reqwest::Response, Error> {
+    let mut waits = 0;
+    loop {
+        // The request is consumed by sending, so its copy is sent again.
+        let retry = if wait { request.try_clone() } else { None };
+        let response = request.send().await?;
+
+        if let (
+            Some(retry),
+            Err(Error::RateLimited {
+                url,
+                retry_after: Some(delay),
+            }),
+        ) = (retry, check_status(&response))
+        {
+            if waits < MAX_RATE_LIMIT_WAITS && delay <= MAX_RATE_LIMIT_WAIT {
+                log::warn!(
+                    "The requests to {} are rate limited, retry in {} seconds",
+                    url,
+                    delay.as_secs()
+                );
+                tokio::time::sleep(delay).await;
+
+                waits += 1;
+                request = retry;
+                continue;
+            }
+        }
+
+        return Ok(response);
+    }
+}
+
+///
+/// Read the response body aborting once it exceeds the limit.
+///
+pub(crate) async fn bytes(mut response: reqwest::Response, limit: usize) -> Result<Vec<u8>, Error> {
+    check_status(&response)?;
+    check_length(&response, limit)?;
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large(&response, limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+///
+/// Read the response body as the text aborting once it exceeds the limit.
+///
+pub(crate) async fn text(response: reqwest::Response, limit: usize) -> Result<String, Error> {
+    let body = bytes(response, limit).await?;
+    Ok(String::from_utf8_lossy(body.as_slice()).into_owned())
+}
+
+///
+/// Read the response body as the JSON aborting once it exceeds the limit.
+///
+pub(crate) async fn json<T: DeserializeOwned>(
+    response: reqwest::Response,
+    limit: usize,
+) -> Result<T, Error> {
+    let body = bytes(response, limit).await?;
+    Ok(serde_json::from_slice(body.as_slice())?)
+}
+
+///
+/// Stream the response body into the file aborting once it exceeds
+/// the limit. The body is written to the temporary file next to the
+/// target renamed on success, so the existing file is kept on failure.
+///
+pub(crate) async fn download(
+    mut response: reqwest::Response,
+    path: &Path,
+    limit: usize,
+) -> Result<(), Error> {
+    check_status(&response)?;
+    check_length(&response, limit)?;
+
+    let part = part_path(path);
+    match write_body(&mut response, part.as_path(), limit).await {
+        Ok(()) => {
+            fs::rename(part.as_path(), path).await?;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = fs::remove_file(part.as_path()).await;
+            Err(e)
+        }
+    }
+}
+
+///
+/// Get the path of the temporary file the body is downloaded to.
+///
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+///
+/// Write the response body into the file aborting once it exceeds the limit.
+///
+async fn write_body(
+    response: &mut reqwest::Response,
+    path: &Path,
+    limit: usize,
+) -> Result<(), Error> {
+    let mut file = File::create(path).await?;
+    let mut size = 0;
+    while let Some(chunk) = response.chunk().await? {
+        size += chunk.len();
+        if size > limit {
+            return Err(too_large(response, limit));
+        }
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+
+    Ok(())
+}
+
+///
+/// Check the response status, the rejected credentials and the rate
+/// limiting are reported by their own errors.
+///
+pub(crate) fn check_status(response: &reqwest::Response) -> Result<(), Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let url = response.url().to_string();
+    Err(match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Error::Unauthorized { url },
+        StatusCode::TOO_MANY_REQUESTS => Error::RateLimited {
+            url,
+            retry_after: response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs),
+        },
+        _ => Error::HttpStatus {
+            url,
+            status: status.as_u16(),
+        },
+    })
+}
+
+// Check the declared length of the response body.
+fn check_length(response: &reqwest::Response, limit: usize) -> Result<(), Error> {
+    match response.content_length() {
+        Some(length) if length > limit as u64 => Err(too_large(response, limit)),
+        _ => Ok(()),
+    }
+}
+
+// Get the error of the too large response body.
+fn too_large(response: &reqwest::Response, limit: usize) -> Error {
+    Error::BodyTooLarge {
+        url: response.url().to_string(),
+        limit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use uuid::Uuid;
+
+    use super::*;
+
+    // Serve the body to all the requests, the body length is declared on demand.
+    async fn serve(body: Vec<u8>, declared: bool) -> String {
+        serve_status("200 OK", "", body, declared).await
+    }
+
+    // Serve the body with the status and the extra headers to all the requests.
+    async fn serve_status(
+        status: &'static str,
+        headers: &'static str,
+        body: Vec<u8>,
+        declared: bool,
+    ) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let _ = stream.read(&mut request).await.unwrap();
+
+                let mut header = format!("HTTP/1.1 {}\r\nConnection: close\r\n{}", status, headers);
+                if declared {
+                    header.push_str(format!("Content-Length: {}\r\n", body.len()).as_str());
+                }
+                header.push_str("\r\n");
+                // The client may abort reading of the too large body.
+                if stream.write_all(header.as_bytes()).await.is_ok() {
+                    let _ = stream.write_all(body.as_slice()).await;
+                }
+            }
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn bytes_test() {
+        let url = serve(b"hello".to_vec(), true).await;
+
+        let client = client();
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert_eq!(bytes(response, 5).await.unwrap(), b"hello");
+
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert!(matches!(
+            bytes(response, 4).await,
+            Err(Error::BodyTooLarge { limit: 4, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn status_test() {
+        let client = client();
+
+        let url = serve_status("403 Forbidden", "", Vec::new(), true).await;
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert!(matches!(
+            text(response, 1024).await,
+            Err(Error::Unauthorized { .. })
+        ));
+
+        let url = serve_status(
+            "429 Too Many Requests",
+            "Retry-After: 30\r\n",
+            Vec::new(),
+            true,
+        )
+        .await;
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert!(matches!(
+            text(response, 1024).await,
+            Err(Error::RateLimited {
+                retry_after: Some(delay),
+                ..
+            }) if delay == Duration::from_secs(30)
+        ));
+
+        let url = serve_status("503 Service Unavailable", "", Vec::new(), true).await;
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert!(matches!(
+            text(response, 1024).await,
+            Err(Error::HttpStatus { status: 503, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn download_test() {
+        // The multi-megabyte image of the unknown length is streamed.
+        let image: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let url = serve(image.clone(), false).await;
+
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.as_path()).await.unwrap();
+        let path = root.join("image.jpg");
+
+        let client = client();
+        let response = client.get(url.as_str()).send().await.unwrap();
+        download(response, path.as_path(), 8 * 1024 * 1024)
+            .await
+            .unwrap();
+        assert!(fs::read(path.as_path()).await.unwrap() == image);
+
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert!(matches!(
+            download(response, path.as_path(), 1024 * 1024).await,
+            Err(Error::BodyTooLarge { .. })
+        ));
+        // The failed download keeps the existing file and drops the partial one.
+        assert!(fs::read(path.as_path()).await.unwrap() == image);
+        assert!(!part_path(path.as_path()).exists());
+
+        let path = root.join("missing.jpg");
+        let response = client.get(url.as_str()).send().await.unwrap();
+        assert!(download(response, path.as_path(), 1024 * 1024)
+            .await
+            .is_err());
+        assert!(!path.exists());
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use reqwest::{
+    header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    RequestBuilder,
+};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::error::Error;
+
+///
+/// The downloaded image with its HTTP validators.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ImageEntry {
+    file_name: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl ImageEntry {
+    ///
+    /// Create the image entry with the validators from the response headers.
+    ///
+    pub(crate) fn new(file_name: &str, headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+
+        Self {
+            file_name: file_name.to_string(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    ///
+    /// Get the file name of the downloaded image.
+    ///
+    #[inline]
+    pub(crate) fn file_name(&self) -> &str {
+        self.file_name.as_str()
+    }
+
+    ///
+    /// Make the request conditional on the image change.
+    ///
+    pub(crate) fn condition(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(ref etag) = self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref last_modified) = self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        request
+    }
+}
+
+///
+/// The index of the downloaded images by their URLs.
+///
+#[derive(Debug)]
+pub(crate) struct ImageIndex {
+    path: PathBuf,
+    entries: HashMap<String, ImageEntry>,
+}
+
+impl ImageIndex {
+    ///
+    /// Load the index from the file, the missing file is the empty index.
+    ///
+    pub(crate) async fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = PathBuf::from(path.as_ref());
+        let entries = if path.is_file() {
+            serde_json::from_str(fs::read_to_string(path.as_path()).await?.as_str())?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { path, entries })
+    }
+
+    ///
+    /// Get the downloaded image by its URL.
+    ///
+    #[inline]
+    pub(crate) fn get(&self, url: &str) -> Option<&ImageEntry> {
+        self.entries.get(url)
+    }
+
+    ///
+    /// Insert the downloaded image.
+    ///
+    #[inline]
+    pub(crate) fn insert(&mut self, url: &str, entry: ImageEntry) {
+        self.entries.insert(url.to_string(), entry);
+    }
+
+    ///
+    /// Save the index to the file.
+    ///
+    pub(crate) async fn save(&self) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(self.path.as_path(), serde_json::to_string(&self.entries)?).await?;
+
+        Ok(())
+    }
+}
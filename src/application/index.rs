@@ -0,0 +1,155 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::error::Error;
+
+///
+/// The scanned note with the referred file names.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct NoteEntry {
+    modified: SystemTime,
+    links: Vec<String>,
+}
+
+///
+/// The persisted content of the notes index.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Entries {
+    files: HashSet<String>,
+    notes: HashMap<PathBuf, NoteEntry>,
+}
+
+///
+/// The index of the file references of the notes invalidated by the
+/// modification time of the notes.
+///
+#[derive(Debug)]
+pub(crate) struct NoteIndex {
+    path: PathBuf,
+    entries: Entries,
+}
+
+impl NoteIndex {
+    ///
+    /// Create the empty index stored in the file.
+    ///
+    pub(crate) fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: PathBuf::from(path.as_ref()),
+            entries: Entries::default(),
+        }
+    }
+
+    ///
+    /// Load the index from the file, the missing or broken file is the empty index.
+    ///
+    pub(crate) async fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut index = Self::new(path);
+        if index.path.is_file() {
+            let content = fs::read_to_string(index.path.as_path()).await?;
+            match serde_json::from_str(content.as_str()) {
+                Ok(entries) => index.entries = entries,
+                Err(e) => log::warn!(
+                    "The notes index \"{}\" is rebuilt: {}",
+                    index.path.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(index)
+    }
+
+    ///
+    /// Check if the references were scanned for all the file names. The new
+    /// files may be referred by the unchanged notes, so they invalidate the index.
+    ///
+    pub(crate) fn covers<'a, I: IntoIterator<Item = &'a str>>(&self, file_names: I) -> bool {
+        file_names
+            .into_iter()
+            .all(|file_name| self.entries.files.contains(file_name))
+    }
+
+    ///
+    /// Get the referred file names of the unchanged note.
+    ///
+    pub(crate) fn links(&self, path: &Path, modified: SystemTime) -> Option<&[String]> {
+        self.entries
+            .notes
+            .get(path)
+            .filter(|entry| entry.modified == modified)
+            .map(|entry| entry.links.as_slice())
+    }
+
+    ///
+    /// Set the file names the references were scanned for.
+    ///
+    pub(crate) fn set_files<I: IntoIterator<Item = String>>(&mut self, file_names: I) {
+        self.entries.files = file_names.into_iter().collect();
+    }
+
+    ///
+    /// Insert the scanned note.
+    ///
+    pub(crate) fn insert(&mut self, path: PathBuf, modified: SystemTime, links: Vec<String>) {
+        self.entries
+            .notes
+            .insert(path, NoteEntry { modified, links });
+    }
+
+    ///
+    /// Save the index to the file.
+    ///
+    pub(crate) async fn save(&self) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(self.path.as_path(), serde_json::to_string(&self.entries)?).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn index_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let note = root.join("note.md");
+        let modified = SystemTime::now();
+
+        let mut index = NoteIndex::load(root.join("index.json")).await.unwrap();
+        assert!(index.links(note.as_path(), modified).is_none());
+
+        index.set_files(vec!["a.png".to_string(), "b.png".to_string()]);
+        index.insert(note.clone(), modified, vec!["a.png".to_string()]);
+        index.save().await.unwrap();
+
+        let index = NoteIndex::load(root.join("index.json")).await.unwrap();
+        assert!(index.covers(["a.png"]));
+        assert!(!index.covers(["a.png", "c.png"]));
+        assert_eq!(
+            index.links(note.as_path(), modified),
+            Some(&["a.png".to_string()][..])
+        );
+        assert!(index
+            .links(note.as_path(), modified + Duration::from_secs(1))
+            .is_none());
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::error::Error;
+
+///
+/// The file operation of the destructive command.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "lowercase")]
+pub(crate) enum Operation {
+    ///
+    /// The file has been deleted, its copy is kept in the backup.
+    ///
+    Delete { path: PathBuf, backup: PathBuf },
+
+    ///
+    /// The file has been renamed.
+    ///
+    Rename { from: PathBuf, to: PathBuf },
+
+    ///
+    /// The file content has been changed, the previous one is kept in the backup.
+    ///
+    Write { path: PathBuf, backup: PathBuf },
+}
+
+///
+/// The journal of the file operations performed by the last destructive command.
+///
+#[derive(Debug)]
+pub(crate) struct Journal {
+    path: PathBuf,
+    backup_path: PathBuf,
+    operations: Mutex<Vec<Operation>>,
+    replaced: tokio::sync::Mutex<bool>,
+}
+
+impl Journal {
+    ///
+    /// Create the journal stored in the file with the backups in the directory.
+    ///
+    pub(crate) fn new<P: AsRef<Path>, Q: AsRef<Path>>(path: P, backup_path: Q) -> Self {
+        Self {
+            path: PathBuf::from(path.as_ref()),
+            backup_path: PathBuf::from(backup_path.as_ref()),
+            operations: Mutex::new(Vec::new()),
+            replaced: tokio::sync::Mutex::new(false),
+        }
+    }
+
+    ///
+    /// Start the new journal. The previous one with its backups is dropped
+    /// only when the first operation is recorded, so the command changing
+    /// nothing keeps it for the undo.
+    ///
+    pub(crate) async fn begin(&self) {
+        self.operations.lock().unwrap().clear();
+        *self.replaced.lock().await = false;
+    }
+
+    ///
+    /// Record the file deletion, must be called before the file is deleted.
+    ///
+    pub(crate) async fn record_delete(&self, path: &Path) -> Result<(), Error> {
+        self.replace().await?;
+        let backup = self.backup_path.join(Uuid::new_v4().to_string());
+        fs::copy(path, backup.as_path()).await?;
+
+        self.push(Operation::Delete {
+            path: PathBuf::from(path),
+            backup,
+        });
+        Ok(())
+    }
+
+    ///
+    /// Record the file renaming.
+    ///
+    pub(crate) fn record_rename(&self, from: &Path, to: &Path) {
+        self.push(Operation::Rename {
+            from: PathBuf::from(from),
+            to: PathBuf::from(to),
+        });
+    }
+
+    ///
+    /// Record the file content change, must be called before the file is written.
+    /// The file is kept byte for byte, so the decoded notes are restored as is.
+    ///
+    pub(crate) async fn record_write(&self, path: &Path) -> Result<(), Error> {
+        self.replace().await?;
+        let backup = self.backup_path.join(Uuid::new_v4().to_string());
+        fs::copy(path, backup.as_path()).await?;
+
+        self.push(Operation::Write {
+            path: PathBuf::from(path),
+            backup,
+        });
+        Ok(())
+    }
+
+    ///
+    /// Get the paths of the files whose content has been changed, every path once.
+    ///
+    pub(crate) fn written_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for operation in self.operations.lock().unwrap().iter() {
+            if let Operation::Write { path, .. } = operation {
+                if !paths.contains(path) {
+                    paths.push(path.clone());
+                }
+            }
+        }
+
+        paths
+    }
+
+    ///
+    /// Get the paths of the files touched by the recorded operations.
+    ///
+    pub(crate) fn paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for operation in self.operations.lock().unwrap().iter() {
+            match operation {
+                Operation::Delete { path, .. } | Operation::Write { path, .. } => {
+                    paths.push(path.clone())
+                }
+                Operation::Rename { from, to } => paths.extend([from.clone(), to.clone()]),
+            }
+        }
+
+        paths
+    }
+
+    ///
+    /// Save the recorded operations to the journal file.
+    ///
+    pub(crate) async fn save(&self) -> Result<(), Error> {
+        if self.operations.lock().unwrap().is_empty() {
+            return Ok(());
+        }
+
+        self.replace().await?;
+        let content = {
+            let operations = self.operations.lock().unwrap();
+            if operations.is_empty() {
+                return Ok(());
+            }
+
+            serde_json::to_string_pretty(&*operations)?
+        };
+        fs::write(self.path.as_path(), content).await?;
+        log::debug!("The journal \"{}\" has been saved", self.path.display());
+
+        Ok(())
+    }
+
+    ///
+    /// Undo the operations of the saved journal in the reverse order.
+    /// Returns the number of the undone operations.
+    ///
+    pub(crate) async fn undo(&self) -> Result<usize, Error> {
+        if !self.path.exists() {
+            log::warn!("There is nothing to undo");
+            return Ok(0);
+        }
+
+        let content = fs::read_to_string(self.path.as_path()).await?;
+        let operations: Vec<Operation> = serde_json::from_str(&content)?;
+
+        for operation in operations.iter().rev() {
+            match operation {
+                Operation::Delete { path, backup } | Operation::Write { path, backup } => {
+                    fs::copy(backup, path).await?;
+                    log::debug!("The file \"{}\" has been restored", path.display());
+                }
+                Operation::Rename { from, to } => {
+                    fs::rename(to, from).await?;
+                    log::debug!(
+                        "The file \"{}\" has been renamed back to \"{}\"",
+                        to.display(),
+                        from.display()
+                    );
+                }
+            }
+        }
+
+        self.begin().await;
+        self.remove().await?;
+        Ok(operations.len())
+    }
+
+    // Drop the previous journal before the first operation of the new one.
+    async fn replace(&self) -> Result<(), Error> {
+        let mut replaced = self.replaced.lock().await;
+        if !*replaced {
+            self.remove().await?;
+            fs::create_dir_all(self.backup_path.as_path()).await?;
+            *replaced = true;
+        }
+
+        Ok(())
+    }
+
+    // Remove the saved journal with its backups.
+    async fn remove(&self) -> Result<(), Error> {
+        if self.path.exists() {
+            fs::remove_file(self.path.as_path()).await?;
+        }
+        if self.backup_path.exists() {
+            fs::remove_dir_all(self.backup_path.as_path()).await?;
+        }
+
+        Ok(())
+    }
+
+    // Push the operation into the journal.
+    fn push(&self, operation: Operation) {
+        self.operations.lock().unwrap().push(operation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn journal_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let journal = Journal::new(root.join("journal.json"), root.join("backup"));
+        journal.begin().await;
+        fs::create_dir_all(root.as_path()).await.unwrap();
+
+        let deleted = root.join("deleted.png");
+        fs::write(deleted.as_path(), "image").await.unwrap();
+        journal.record_delete(deleted.as_path()).await.unwrap();
+        fs::remove_file(deleted.as_path()).await.unwrap();
+
+        let note = root.join("note.md");
+        fs::write(note.as_path(), "[[old.png]]").await.unwrap();
+        journal.record_write(note.as_path()).await.unwrap();
+        fs::write(note.as_path(), "[[new.png]]").await.unwrap();
+
+        let (old, new) = (root.join("old.png"), root.join("new.png"));
+        fs::write(old.as_path(), "image").await.unwrap();
+        fs::rename(old.as_path(), new.as_path()).await.unwrap();
+        journal.record_rename(old.as_path(), new.as_path());
+
+        journal.save().await.unwrap();
+
+        // The command changing nothing keeps the previous journal.
+        journal.begin().await;
+        journal.save().await.unwrap();
+        assert!(journal.path.is_file());
+
+        assert_eq!(journal.undo().await.unwrap(), 3);
+
+        assert!(deleted.is_file() && old.is_file() && !new.exists());
+        assert_eq!(fs::read_to_string(note).await.unwrap(), "[[old.png]]");
+        assert_eq!(journal.undo().await.unwrap(), 0);
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// The language of the generated notes content.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    ///
+    /// The unset language of the notes generated before the switch was
+    /// added: the Russian calendar and the English grabbed notes.
+    ///
+    #[default]
+    #[serde(skip)]
+    Legacy,
+
+    #[serde(rename = "en")]
+    English,
+
+    #[serde(rename = "ru")]
+    Russian,
+}
+
+impl Language {
+    ///
+    /// Get the short weekday names starting from Monday.
+    ///
+    pub(crate) fn weekdays(&self) -> [&'static str; 7] {
+        match self {
+            Self::English => ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+            Self::Legacy | Self::Russian => ["Пн", "Вт", "Ср", "Чт", "Пт", "Сб", "Вс"],
+        }
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day caption.
+    ///
+    pub(crate) fn apod(&self) -> &'static str {
+        match self {
+            Self::Legacy | Self::English => "Astronomy Picture of the Day",
+            Self::Russian => "Астрономическая картинка дня",
+        }
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day explanation caption.
+    ///
+    pub(crate) fn explanation(&self) -> &'static str {
+        match self {
+            Self::Legacy | Self::English => "Explanation",
+            Self::Russian => "Пояснение",
+        }
+    }
+
+    ///
+    /// Get the image copyright caption.
+    ///
+    pub(crate) fn image_copyright(&self) -> &'static str {
+        match self {
+            Self::Legacy | Self::English => "Image copyright",
+            Self::Russian => "Авторские права на изображение",
+        }
+    }
+
+    ///
+    /// Get the This Week in Rust issue caption.
+    ///
+    pub(crate) fn twir(&self, number: u32) -> String {
+        match self {
+            Self::Legacy | Self::English => format!("This Week in Rust {}", number),
+            Self::Russian => format!("Эта неделя в Rust {}", number),
+        }
+    }
+}
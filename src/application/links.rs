@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+
+use regex::{Captures, Regex};
+use url::Url;
+
+///
+/// The default tracking query keys, the trailing asterisk matches any suffix.
+///
+pub(crate) const TRACKING_KEYS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "yclid", "_hsenc", "_hsmi",
+];
+
+///
+/// Strip the tracking query parameters from all the links of the markdown content.
+///
+pub(crate) fn strip_tracking<'a, S: AsRef<str>>(content: &'a str, keys: &[S]) -> Cow<'a, str> {
+    let re = Regex::new(r#"https?://[^\s()<>\[\]"']+"#).unwrap();
+    re.replace_all(content, |caps: &Captures| {
+        // The trailing punctuation is not the part of the link.
+        let link = caps[0].trim_end_matches(&['.', ',', ';', ':', '!', '?'][..]);
+        let tail = &caps[0][link.len()..];
+
+        match Url::parse(link).ok().and_then(|url| strip_url(url, keys)) {
+            Some(link) => format!("{}{}", link, tail),
+            None => caps[0].to_string(),
+        }
+    })
+}
+
+// Strip the tracking query parameters from the URL, returns `None` if nothing is stripped.
+fn strip_url<S: AsRef<str>>(mut url: Url, keys: &[S]) -> Option<String> {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    let kept: Vec<&(String, String)> = pairs
+        .iter()
+        .filter(|(key, _)| !keys.iter().any(|k| is_match(k.as_ref(), key)))
+        .collect();
+
+    if kept.len() == pairs.len() {
+        return None;
+    }
+
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+
+    Some(url.to_string())
+}
+
+// Check if the query key matches the tracking key pattern.
+fn is_match(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_tracking_test() {
+        let content = "[post](https://blog.rust-lang.org/post?utm_source=twir&id=1#top) and \
+                       https://example.com/?fbclid=abc, https://example.com/?q=rust";
+        assert_eq!(
+            strip_tracking(content, TRACKING_KEYS),
+            "[post](https://blog.rust-lang.org/post?id=1#top) and \
+             https://example.com/, https://example.com/?q=rust"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use clap::Parser;
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::{
+    cli::{GrabTask, Note},
+    error::Error,
+};
+
+///
+/// The manifest of the grab tasks run in sequence.
+///
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    ///
+    /// The grab tasks.
+    ///
+    #[serde(rename = "Grab", default)]
+    tasks: Vec<Task>,
+}
+
+///
+/// The grab task of the manifest.
+///
+#[derive(Debug, Deserialize)]
+struct Task {
+    ///
+    /// The grab command line of the note without the program name and the
+    /// command, e.g. "apod --update-daily" or "twir -i 450..455".
+    ///
+    #[serde(rename = "Note")]
+    note: String,
+}
+
+///
+/// Read the notes to grab of the manifest file.
+///
+pub(crate) async fn read(path: &Path) -> Result<Vec<Note>, Error> {
+    let content = fs::read_to_string(path).await?;
+    parse(path, content.as_str())
+}
+
+///
+/// Parse the notes to grab of the manifest content.
+///
+fn parse(path: &Path, content: &str) -> Result<Vec<Note>, Error> {
+    let illegal = |reason: String| Error::IllegalManifest {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let manifest: Manifest = toml::from_str(content).map_err(|e| illegal(e.to_string()))?;
+    manifest
+        .tasks
+        .iter()
+        .map(|task| {
+            GrabTask::try_parse_from(task.note.split_whitespace())
+                .map(|task| task.note)
+                .map_err(|e| {
+                    let error = e.to_string();
+                    let reason = error.lines().next().unwrap_or_default();
+                    let reason = reason.trim_start_matches("error: ");
+                    illegal(format!("\"{}\" {}", task.note, reason))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let path = Path::new("tasks.toml");
+        let content = "[[Grab]]\nNote = \"apod --update-daily\"\n\n\
+                       [[Grab]]\nNote = \"twir -i 450..452 --toc\"\n";
+        let notes = parse(path, content).unwrap();
+        assert_eq!(notes.len(), 2);
+        assert!(matches!(
+            notes[0],
+            Note::APoD {
+                update_daily: true,
+                ..
+            }
+        ));
+        assert!(matches!(notes[1], Note::TWiR { toc: true, .. }));
+
+        assert!(parse(path, "").unwrap().is_empty());
+        assert!(matches!(
+            parse(path, "[[Grab]]\nNote = \"rss\"\n"),
+            Err(Error::IllegalManifest { .. })
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use regex::{Captures, Regex};
+use textwrap::{Options, WordSeparator, WrapAlgorithm};
+
+///
+/// The placeholder of the spaces inside the links which must not be broken.
+///
+const LINK_SPACE: char = '\u{E000}';
+
+///
+/// Fix the common artifacts of the HTML to markdown conversion: the setext
+/// headings and the closing sequences of the ATX headings, the link texts
+/// starting with the bracket which are treated as the wiki links, the escaped
+/// intraword underscores and the blank lines at the end of the code blocks.
+///
+pub(crate) fn fix_conversion(content: &str) -> String {
+    let link_re =
+        Regex::new(r"(?P<pre>^|[^\\!\[])\[\[(?P<inner>[^\[\]\n]*)\](?P<rest>[^\[\]\n]*)\]\(")
+            .unwrap();
+    let underscore_re = Regex::new(r"(?P<l>[[:alnum:]])\\_(?P<r>[[:alnum:]])").unwrap();
+    let closing_re = Regex::new(r"^(?P<heading>#{1,6} .*?)\s+#+\s*$").unwrap();
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut fenced = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            if fenced {
+                // Drop the blank lines at the end of the code block.
+                while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                    lines.pop();
+                }
+            }
+
+            fenced = !fenced;
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if fenced {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        // Convert the setext heading into the ATX one.
+        if let Some(level) = setext_level(line) {
+            if let Some(title) = lines.last_mut().filter(|l| is_heading_text(l)) {
+                *title = format!("{} {}", "#".repeat(level), title.trim());
+                continue;
+            }
+        }
+
+        let line = closing_re.replace(line, "${heading}");
+        let line = link_re.replace_all(&line, r"${pre}[\[${inner}\]${rest}](");
+        let line = underscore_re.replace_all(&line, "${l}_${r}");
+        lines.push(line.into_owned());
+    }
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+
+    result
+}
+
+///
+/// Hard-wrap the paragraphs to the column width keeping the headings, the lists,
+/// the quotes, the tables, the code blocks and the links unbroken.
+///
+pub(crate) fn wrap(content: &str, width: usize) -> String {
+    let link_re = Regex::new(r"!?\[\[[^\]\n]*\]\]|!?\[[^\]\n]*\]\([^)\n]*\)").unwrap();
+    let options = Options::new(width)
+        .break_words(false)
+        .word_separator(WordSeparator::AsciiSpace)
+        .wrap_algorithm(WrapAlgorithm::FirstFit);
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut fenced = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            fenced = !fenced;
+        }
+
+        if fenced || !is_paragraph(line) || textwrap::core::display_width(line) <= width {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        // Protect the spaces of the links from the breaking.
+        let protected = link_re.replace_all(line, |caps: &Captures| {
+            caps[0].replace(' ', LINK_SPACE.to_string().as_str())
+        });
+        lines.extend(
+            textwrap::wrap(protected.as_ref(), &options)
+                .into_iter()
+                .map(|l| l.replace(LINK_SPACE, " ")),
+        );
+    }
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+
+    result
+}
+
+///
+/// Truncate the text to the number of words at the word boundary, the
+/// truncated text is ellipsized and the whitespaces are collapsed.
+///
+pub(crate) fn excerpt(text: &str, words: usize) -> String {
+    let mut iter = text.split_whitespace();
+    let mut result = iter.by_ref().take(words).collect::<Vec<_>>().join(" ");
+    if iter.next().is_some() {
+        let trimmed = result.trim_end_matches(&[',', ';', ':', '.', '-', '\u{2014}'][..]);
+        result.truncate(trimmed.len());
+        result.push('\u{2026}');
+    }
+
+    result
+}
+
+// Get the heading level of the setext heading underline.
+fn setext_level(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    if line.len() < 3 {
+        return None;
+    }
+
+    if line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+// Check if the line can be the setext heading text.
+fn is_heading_text(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty()
+        && !line.starts_with(['#', '*', '-', '+', '>', '|'])
+        && !line.starts_with(|c: char| c.is_ascii_digit())
+}
+
+// Check if the line is the paragraph text, not the heading, the list item,
+// the quote, the table row, the thematic break or the indented block.
+fn is_paragraph(line: &str) -> bool {
+    let item_re = Regex::new(r"^(?:[*+-]|\d+[.)])(?:\s|$)").unwrap();
+    let break_re = Regex::new(r"^(?:(?:\*\s*){3,}|(?:-\s*){3,}|(?:_\s*){3,}|=+)$").unwrap();
+    !line.trim().is_empty()
+        && !line.starts_with([' ', '\t', '#', '>', '|', '<'])
+        && !item_re.is_match(line)
+        && !break_re.is_match(line.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fix_conversion_test() {
+        let html = include_str!("fixtures/twir.html");
+        let md = include_str!("fixtures/twir.md");
+
+        assert_eq!(fix_conversion(&html2md::parse_html(html)), md.trim_end());
+    }
+
+    #[test]
+    fn wrap_test() {
+        let content = "# The very long heading of the note\n\n\
+                       The galaxy is seen in the [Hubble Space Telescope](https://hubblesite.org) \
+                       image.\n\n```\nlet the_code_line = \"is kept as it is\";\n```\n";
+        assert_eq!(
+            wrap(content, 20),
+            "# The very long heading of the note\n\n\
+             The galaxy is seen\nin the\n[Hubble Space Telescope](https://hubblesite.org)\n\
+             image.\n\n```\nlet the_code_line = \"is kept as it is\";\n```\n"
+        );
+    }
+
+    #[test]
+    fn is_paragraph_test() {
+        assert!(is_paragraph("**Explanation:** The galaxy."));
+        assert!(is_paragraph("*Image Credit:* NASA"));
+        assert!(is_paragraph("2022 was the year."));
+        for line in [
+            "# Heading",
+            "- item",
+            "* item",
+            "1. item",
+            "> quote",
+            "| a |",
+            "***",
+            "",
+        ] {
+            assert!(!is_paragraph(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn excerpt_test() {
+        let text = "The galaxy,  seen\nby Hubble, is near.";
+        assert_eq!(excerpt(text, 2), "The galaxy\u{2026}");
+        assert_eq!(excerpt(text, 3), "The galaxy, seen\u{2026}");
+        assert_eq!(excerpt(text, 6), "The galaxy, seen by Hubble, is\u{2026}");
+        assert_eq!(excerpt(text, 7), "The galaxy, seen by Hubble, is near.");
+        assert_eq!(excerpt(text, 0), "\u{2026}");
+    }
+}
@@ -0,0 +1,539 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+///
+/// The front matter fence of the note.
+///
+const FENCE: &str = "---";
+
+///
+/// The front matter key names of the notes set.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keys {
+    ///
+    /// The key of the note type, e.g. "apod" or "twir".
+    ///
+    #[serde(rename = "Type")]
+    pub(crate) note_type: String,
+
+    ///
+    /// The key of the note name.
+    ///
+    #[serde(rename = "Name")]
+    pub(crate) name: String,
+
+    ///
+    /// The key of the grabbed notes issue, e.g. APoD or the TWiR number.
+    ///
+    #[serde(rename = "Issue")]
+    pub(crate) issue: String,
+
+    ///
+    /// The key of the note date.
+    ///
+    #[serde(rename = "Date")]
+    pub(crate) date: String,
+
+    ///
+    /// The key of the note tags.
+    ///
+    #[serde(rename = "Tags")]
+    pub(crate) tags: String,
+
+    ///
+    /// The key of the note aliases.
+    ///
+    #[serde(rename = "Aliases")]
+    pub(crate) aliases: String,
+
+    ///
+    /// The key of the source URL of the grabbed notes.
+    ///
+    #[serde(rename = "URL")]
+    pub(crate) url: String,
+
+    ///
+    /// The key of the note banner.
+    ///
+    #[serde(rename = "Banner")]
+    pub(crate) banner: String,
+
+    ///
+    /// The key of the APoD explanation excerpt.
+    ///
+    #[serde(rename = "Excerpt")]
+    pub(crate) excerpt: String,
+
+    ///
+    /// The key of the note creation time.
+    ///
+    #[serde(rename = "Created")]
+    pub(crate) created: String,
+
+    ///
+    /// The key of the note modification time.
+    ///
+    #[serde(rename = "Modified")]
+    pub(crate) modified: String,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self {
+            note_type: "type".to_string(),
+            name: "name".to_string(),
+            issue: "issue".to_string(),
+            date: "date".to_string(),
+            tags: "tags".to_string(),
+            aliases: "aliases".to_string(),
+            url: "url".to_string(),
+            banner: "banner".to_string(),
+            excerpt: "excerpt".to_string(),
+            created: "created".to_string(),
+            modified: "modified".to_string(),
+        }
+    }
+}
+
+///
+/// The style of the front matter lists.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListStyle {
+    ///
+    /// The block sequence with the item per line.
+    ///
+    #[default]
+    Block,
+
+    ///
+    /// The inline array in the brackets.
+    ///
+    Inline,
+}
+
+///
+/// The front matter metadata of the note.
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Metadata {
+    lines: Vec<String>,
+}
+
+impl Metadata {
+    ///
+    /// Split the note content into the front matter metadata and the body.
+    ///
+    pub(crate) fn split(content: &str) -> (Self, &str) {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+
+        for (number, line) in content.split_inclusive('\n').enumerate() {
+            offset += line.len();
+            let line = line.trim_end_matches(&['\r', '\n'][..]);
+
+            if number == 0 {
+                if line != FENCE {
+                    break;
+                }
+            } else if line == FENCE {
+                return (Self { lines }, &content[offset..]);
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+
+        (Self::default(), content)
+    }
+
+    ///
+    /// Read the front matter metadata of the note stopping at the closing
+    /// fence, so the body is never read. The metadata is empty like the split
+    /// one if the note has no front matter or the fence is not closed.
+    ///
+    pub(crate) async fn from_reader<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Self> {
+        let mut lines = Vec::new();
+        let mut buffer = Vec::new();
+
+        for number in 0.. {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer).await? == 0 {
+                break;
+            }
+
+            // Only the selection depends on the metadata read, so the bytes
+            // which cannot be decoded are replaced.
+            let line = String::from_utf8_lossy(&buffer);
+            let line = line.trim_end_matches(&['\r', '\n'][..]);
+
+            if number == 0 {
+                if line != FENCE {
+                    break;
+                }
+            } else if line == FENCE {
+                return Ok(Self { lines });
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+
+        Ok(Self::default())
+    }
+
+    ///
+    /// Strip the front matter metadata of the note content leaving the body
+    /// without the leading line breaks, the content without the metadata
+    /// is left as is.
+    ///
+    pub(crate) fn strip(content: &str) -> &str {
+        match Self::split(content) {
+            (metadata, body) if !metadata.is_empty() => body.trim_start_matches(&['\r', '\n'][..]),
+            _ => content,
+        }
+    }
+
+    ///
+    /// Check if the metadata is empty.
+    ///
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    ///
+    /// Get the value of the top level property.
+    ///
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.position(key).map(|index| {
+            let value = self.lines[index][key.len() + 1..].trim();
+            value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value)
+        })
+    }
+
+    ///
+    /// Set the value of the top level property replacing the old one.
+    ///
+    pub(crate) fn set(&mut self, key: &str, value: &str) {
+        let line = format!("{}: {}", key, value);
+        match self.position(key) {
+            Some(index) => {
+                let end = self.value_end(index);
+                self.lines.splice(index..end, [line]);
+            }
+            None => self.lines.push(line),
+        }
+    }
+
+    ///
+    /// Get the items of the top level list property written either as
+    /// the inline array or as the block sequence. The items are kept raw.
+    ///
+    pub(crate) fn list(&self, key: &str) -> Option<Vec<String>> {
+        let index = self.position(key)?;
+        let value = self.lines[index][key.len() + 1..].trim();
+
+        if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return Some(split_inline(inner));
+        }
+        if !value.is_empty() {
+            return Some(vec![value.to_string()]);
+        }
+
+        let items = self.lines[index + 1..self.value_end(index)]
+            .iter()
+            .filter_map(|line| line.trim_start().strip_prefix('-'))
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect();
+        Some(items)
+    }
+
+    ///
+    /// Rewrite the top level list property in the style. Returns `true` if
+    /// the metadata has been changed.
+    ///
+    pub(crate) fn normalize_list(&mut self, key: &str, style: ListStyle) -> bool {
+        let items = match self.list(key) {
+            Some(items) => items,
+            None => return false,
+        };
+
+        let lines = if items.is_empty() {
+            vec![format!("{}: []", key)]
+        } else {
+            match style {
+                ListStyle::Block => {
+                    let mut lines = vec![format!("{}:", key)];
+                    lines.extend(items.iter().map(|item| format!("- {}", item)));
+                    lines
+                }
+                ListStyle::Inline => vec![format!("{}: [{}]", key, items.join(", "))],
+            }
+        };
+
+        let index = self.position(key).unwrap();
+        let end = self.value_end(index);
+        if self.lines[index..end] == lines[..] {
+            return false;
+        }
+
+        self.lines.splice(index..end, lines);
+        true
+    }
+
+    ///
+    /// Get the top level properties which are defined more than once.
+    ///
+    pub(crate) fn duplicate_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+        let mut duplicates: Vec<&str> = Vec::new();
+
+        for key in self.lines.iter().filter_map(|line| top_level_key(line)) {
+            if keys.contains(&key) {
+                if !duplicates.contains(&key) {
+                    duplicates.push(key);
+                }
+            } else {
+                keys.push(key);
+            }
+        }
+
+        duplicates
+    }
+
+    ///
+    /// Remove the duplicate top level properties keeping the last ones
+    /// as the YAML parsers do. Returns the removed keys.
+    ///
+    pub(crate) fn dedup_keys(&mut self) -> Vec<String> {
+        let duplicates: Vec<String> = self
+            .duplicate_keys()
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        for key in duplicates.iter() {
+            while self.occurrences(key) > 1 {
+                let index = self.position(key).unwrap();
+                let end = self.value_end(index);
+                self.lines.drain(index..end);
+            }
+        }
+
+        duplicates
+    }
+
+    ///
+    /// Get the note type.
+    ///
+    #[inline]
+    pub(crate) fn note_type(&self, keys: &Keys) -> Option<&str> {
+        self.get(keys.note_type.as_str())
+    }
+
+    ///
+    /// Get the note banner.
+    ///
+    pub(crate) fn banner(&self, keys: &Keys) -> Option<&str> {
+        self.get(keys.banner.as_str())
+            .filter(|banner| !banner.is_empty())
+    }
+
+    ///
+    /// Set the note banner to the attached file.
+    ///
+    pub(crate) fn set_banner(&mut self, keys: &Keys, file_name: &str) {
+        self.set(
+            keys.banner.as_str(),
+            format!("\"![[{}]]\"", file_name).as_str(),
+        );
+    }
+
+    ///
+    /// Get the note modification time.
+    ///
+    #[inline]
+    pub(crate) fn modified(&self, keys: &Keys) -> Option<&str> {
+        self.get(keys.modified.as_str())
+    }
+
+    ///
+    /// Set the note modification time.
+    ///
+    #[inline]
+    pub(crate) fn set_modified(&mut self, keys: &Keys, value: &str) {
+        self.set(keys.modified.as_str(), value);
+    }
+
+    ///
+    /// Embed the metadata into the note body.
+    ///
+    pub(crate) fn embed(&self, body: &str) -> String {
+        if self.is_empty() {
+            return body.to_string();
+        }
+
+        format!("{}\n{}\n{}\n{}", FENCE, self.lines.join("\n"), FENCE, body)
+    }
+
+    // Find the line of the top level property.
+    fn position(&self, key: &str) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| top_level_key(line) == Some(key))
+    }
+
+    // Count the lines of the top level property.
+    fn occurrences(&self, key: &str) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| top_level_key(line) == Some(key))
+            .count()
+    }
+
+    // Find the end of the property value including the nested lines.
+    fn value_end(&self, index: usize) -> usize {
+        self.lines[index + 1..]
+            .iter()
+            .position(|line| !(line.starts_with(' ') || line.starts_with('-')))
+            .map_or(self.lines.len(), |offset| index + 1 + offset)
+    }
+}
+
+// Split the inline array items keeping the quoted commas.
+fn split_inline(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in inner.chars() {
+        match (quote, c) {
+            (None, ',') => items.push(std::mem::take(&mut item)),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                item.push(c);
+            }
+            (Some(q), _) if c == q => {
+                quote = None;
+                item.push(c);
+            }
+            _ => item.push(c),
+        }
+    }
+    items.push(item);
+
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+// Get the key of the top level property line.
+fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with(|c: char| c.is_whitespace() || c == '-' || c == '#') {
+        return None;
+    }
+
+    let (key, rest) = line.split_once(':')?;
+    if key.is_empty() || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn from_reader_test() {
+        for content in [
+            "---\ntype: apod\ntags: [a, b]\n---\n# Title\n",
+            "---\r\ntype: apod\r\ntags: [a, b]\r\n---\r\n",
+            "# Title\n",
+            "---\ntype: apod\n",
+            "",
+        ] {
+            let metadata = Metadata::from_reader(&mut content.as_bytes())
+                .await
+                .unwrap();
+            assert_eq!(metadata, Metadata::split(content).0);
+        }
+
+        // The body after the closing fence is not read.
+        let mut reader = "---\ntype: apod\n---\nbody\n".as_bytes();
+        let metadata = Metadata::from_reader(&mut reader).await.unwrap();
+        assert_eq!(metadata.get("type"), Some("apod"));
+        assert_eq!(reader, b"body\n");
+    }
+
+    #[test]
+    fn strip_test() {
+        let content = "---\ntype: software\n---\n\n# nta\n\n---\n\ntext\n";
+        assert_eq!(Metadata::strip(content), "# nta\n\n---\n\ntext\n");
+        assert_eq!(Metadata::strip("# nta\n\n---\n"), "# nta\n\n---\n");
+    }
+
+    #[test]
+    fn metadata_test() {
+        let content = "---\ntype: software\ntags:\n- dev/rust\nname: \"nta\"\n---\n\n# nta\n";
+        let keys = Keys::default();
+        let (mut metadata, body) = Metadata::split(content);
+        assert_eq!(body, "\n# nta\n");
+        assert_eq!(metadata.note_type(&keys), Some("software"));
+        assert_eq!(metadata.get("name"), Some("nta"));
+        assert_eq!(metadata.banner(&keys), None);
+        assert_eq!(metadata.embed(body), content);
+
+        metadata.set_banner(&keys, "rust.png");
+        assert_eq!(metadata.banner(&keys), Some("![[rust.png]]"));
+
+        let keys = Keys {
+            note_type: "category".to_string(),
+            ..Keys::default()
+        };
+        assert_eq!(metadata.note_type(&keys), None);
+
+        metadata.set("tags", "[]");
+        assert_eq!(
+            metadata.embed(body),
+            "---\ntype: software\ntags: []\nname: \"nta\"\nbanner: \"![[rust.png]]\"\n---\n\n# nta\n"
+        );
+
+        let content = "---\ntags:\n- a\ndate: 2022-08-01\ntags:\n- b\ndate: 2022-08-02\n---\n";
+        let (mut metadata, _) = Metadata::split(content);
+        assert_eq!(metadata.duplicate_keys(), vec!["tags", "date"]);
+        assert_eq!(metadata.dedup_keys(), vec!["tags", "date"]);
+        assert_eq!(
+            metadata.embed(""),
+            "---\ntags:\n- b\ndate: 2022-08-02\n---\n"
+        );
+
+        let inline = "---\ntags: [news/apod, \"science, astronomy\"]\ndate: 2022-08-01\n---\n";
+        let block = "---\ntags:\n- news/apod\n- \"science, astronomy\"\ndate: 2022-08-01\n---\n";
+
+        let (mut metadata, _) = Metadata::split(inline);
+        assert!(metadata.normalize_list("tags", ListStyle::Block));
+        assert_eq!(metadata.embed(""), block);
+        assert!(!metadata.normalize_list("tags", ListStyle::Block));
+
+        let (mut metadata, _) = Metadata::split(block);
+        assert!(metadata.normalize_list("tags", ListStyle::Inline));
+        assert_eq!(metadata.embed(""), inline);
+        assert!(!metadata.normalize_list("aliases", ListStyle::Inline));
+
+        let (metadata, body) = Metadata::split("# Title\n---\n");
+        assert!(metadata.is_empty());
+        assert_eq!(body, "# Title\n---\n");
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::Path,
+};
+
+use tokio::process::Command;
+
+use crate::error::Error;
+
+///
+/// Open the note with the editor or the platform opener. The note is not
+/// opened if the standard output is not an interactive terminal.
+///
+pub(crate) async fn open(path: &Path) -> Result<(), Error> {
+    if !io::stdout().is_terminal() {
+        log::debug!(
+            "Skip opening of the note \"{}\" outside of the terminal",
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let mut command = match env::var("EDITOR") {
+        Ok(editor) if !editor.trim().is_empty() => editor_command(editor.as_str())?,
+        _ => platform(),
+    };
+
+    let status = command.arg(path).status().await?;
+    if !status.success() {
+        log::warn!(
+            "The opener of the note \"{}\" has been exited with {}",
+            path.display(),
+            status
+        );
+    }
+
+    Ok(())
+}
+
+///
+/// Open the URL in the default browser with the platform opener.
+///
+pub(crate) async fn open_url(url: &str) -> Result<(), Error> {
+    let status = platform().arg(url).status().await?;
+    if !status.success() {
+        log::warn!(
+            "The opener of the URL \"{}\" has been exited with {}",
+            url,
+            status
+        );
+    }
+
+    Ok(())
+}
+
+///
+/// Get the command of the editor, the editor may be given with the arguments,
+/// e.g. "code -w".
+///
+fn editor_command(editor: &str) -> Result<Command, Error> {
+    let args = shlex::split(editor).ok_or_else(|| Error::IllegalEditor(editor.to_string()))?;
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| Error::IllegalEditor(editor.to_string()))?;
+
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+///
+/// Get the command of the platform opener.
+///
+fn platform() -> Command {
+    if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editor_command_test() {
+        let command = editor_command("code -w").unwrap();
+        let command = command.as_std();
+        assert_eq!(command.get_program(), "code");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!["-w"]);
+
+        let command = editor_command("\"/opt/My Editor/edit\" --wait").unwrap();
+        assert_eq!(command.as_std().get_program(), "/opt/My Editor/edit");
+
+        assert!(matches!(
+            editor_command("vim \"-c"),
+            Err(Error::IllegalEditor(_))
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    process::Stdio,
+};
+
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::error::Error;
+
+///
+/// The default pager command.
+///
+const DEFAULT_PAGER: &str = "less -FRX";
+
+///
+/// Show the content through the pager if the standard output is an
+/// interactive terminal, otherwise print the content as is.
+///
+pub(crate) async fn page(content: &str) -> Result<(), Error> {
+    if !io::stdout().is_terminal() {
+        print!("{}", content);
+        return Ok(());
+    }
+
+    let pager = env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut args = pager.split_whitespace();
+
+    let mut child = match args.next().map(|program| {
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+    }) {
+        Some(Ok(child)) => child,
+        _ => {
+            log::debug!("Cannot start the pager \"{}\"", pager);
+            print!("{}", content);
+            return Ok(());
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may be closed before the whole content is written.
+        if let Err(e) = stdin.write_all(content.as_bytes()).await {
+            if e.kind() != io::ErrorKind::BrokenPipe {
+                return Err(e.into());
+            }
+        }
+    }
+    child.wait().await?;
+
+    Ok(())
+}
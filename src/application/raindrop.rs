@@ -0,0 +1,298 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use serde::Deserialize;
+use tokio::{
+    fs::{self, File},
+    io::AsyncWriteExt,
+};
+
+use crate::{
+    application::{
+        entry,
+        grabber::{GrabContext, GrabReport, Grabber},
+        http, links,
+        summary::Summary,
+    },
+    error::Error,
+};
+
+const RAINDROPS_URL: &str = "https://api.raindrop.io/rest/v1/raindrops/0";
+
+///
+/// The number of the bookmarks requested per page, the maximum allowed by the API.
+///
+const PER_PAGE: usize = 50;
+
+///
+/// Raindrop bookmark.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Item {
+    title: String,
+    link: String,
+    #[serde(default)]
+    excerpt: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    created: DateTime<FixedOffset>,
+}
+
+impl Item {
+    ///
+    /// Bookmark title.
+    ///
+    #[inline]
+    pub(crate) fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    ///
+    /// Bookmark link.
+    ///
+    #[inline]
+    pub(crate) fn link(&self) -> &str {
+        self.link.as_str()
+    }
+
+    ///
+    /// Bookmark description.
+    ///
+    #[inline]
+    pub(crate) fn excerpt(&self) -> &str {
+        self.excerpt.as_str()
+    }
+
+    ///
+    /// Bookmark tags.
+    ///
+    #[inline]
+    pub(crate) fn tags(&self) -> &[String] {
+        self.tags.as_slice()
+    }
+
+    ///
+    /// Bookmark creation date and time.
+    ///
+    #[inline]
+    pub(crate) fn created(&self) -> DateTime<FixedOffset> {
+        self.created
+    }
+
+    ///
+    /// Check if the bookmark has any of the tags, every bookmark matches
+    /// the empty tags. The tags are compared case insensitively.
+    ///
+    pub(crate) fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.is_empty()
+            || tags
+                .iter()
+                .any(|tag| self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    ///
+    /// Get the note file name of the bookmark.
+    ///
+    pub(crate) fn note_name(&self, prefix: Option<&str>) -> String {
+        let title = entry::sanitize_name(self.title.as_str());
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        format!("{}{}.md", prefix.unwrap_or_default(), title)
+    }
+}
+
+///
+/// The page of the Raindrop bookmarks.
+///
+#[derive(Debug, Deserialize)]
+struct Page {
+    items: Vec<Item>,
+}
+
+///
+/// Select all Raindrop bookmarks.
+///
+pub(crate) async fn select(
+    client: &reqwest::Client,
+    token: &str,
+    limit: usize,
+) -> Result<Vec<Item>, Error> {
+    let mut items: Vec<Item> = Vec::new();
+    for page in 0.. {
+        log::trace!("Retriving the Raindrop bookmarks page {}", page);
+        let response = client
+            .get(RAINDROPS_URL)
+            .bearer_auth(token)
+            .query(&[("page", page), ("perpage", PER_PAGE)])
+            .send()
+            .await?;
+        let page: Page = http::json(response, limit).await?;
+
+        let last = page.items.len() < PER_PAGE;
+        items.extend(page.items);
+        if last {
+            break;
+        }
+    }
+
+    Ok(items)
+}
+
+///
+/// The options of Raindrop bookmarks grabbing.
+///
+#[derive(Debug, Default, Clone)]
+pub(crate) struct GrabOptions {
+    ///
+    /// Grab only the bookmarks having any of the tags, all the bookmarks
+    /// are grabbed if there are no tags.
+    ///
+    pub(crate) tags: Vec<String>,
+
+    ///
+    /// The adornment of the note file names.
+    ///
+    pub(crate) affix: entry::NameAffix,
+}
+
+///
+/// Raindrop bookmarks grabber.
+///
+#[derive(Debug)]
+pub(crate) struct RaindropGrabber {
+    options: GrabOptions,
+}
+
+impl RaindropGrabber {
+    ///
+    /// Create Raindrop bookmarks grabber with options.
+    ///
+    pub(crate) fn new(options: GrabOptions) -> Self {
+        Self { options }
+    }
+
+    ///
+    /// Grab Raindrop bookmarks, the note per bookmark. The bookmarks having
+    /// the notes are skipped. Returns the paths of the created notes.
+    ///
+    async fn grab_notes(&self, context: &GrabContext<'_>) -> Result<Vec<PathBuf>, Error> {
+        let options = &self.options;
+        let since = context.since();
+        let config = context.config();
+        let keys = config.keys();
+        let token = config
+            .read_raindrop_token()
+            .await?
+            .ok_or_else(|| Error::MissingToken("Raindrop".to_string()))?;
+
+        let items = select(context.client(), token.as_str(), config.max_body_size()).await?;
+        let raindrop_path = config.raindrop_path();
+        if !raindrop_path.exists() {
+            fs::create_dir_all(&raindrop_path).await?;
+        }
+
+        let mut paths = Vec::new();
+        for item in items
+            .iter()
+            .filter(|item| item.has_any_tag(&options.tags))
+            .filter(|item| since.is_none_or(|since| item.created() > since))
+        {
+            let note_path = options
+                .affix
+                .apply(raindrop_path.join(item.note_name(config.raindrop_prefix())));
+            if note_path.exists() {
+                log::debug!(
+                    "The Raindrop note \"{}\" already exists",
+                    note_path.display()
+                );
+                continue;
+            }
+
+            let mut content = vec![
+                format!("---\n{}: bookmark", keys.note_type),
+                format!("{}: \"{}\"", keys.name, item.title().replace('"', "\\\"")),
+                format!("{}: {}", keys.url, item.link()),
+                format!(
+                    "{}: {}",
+                    keys.date,
+                    config.format_date(item.created().naive_local().date())
+                ),
+                format!("{}:", keys.tags),
+            ];
+            content.extend(item.tags().iter().map(|tag| {
+                format!(
+                    "- {}{}",
+                    config.raindrop_tag_prefix().unwrap_or_default(),
+                    tag.replace(' ', "-")
+                )
+            }));
+            content.push("---\n".to_string());
+            content.push(format!("# {}\n", item.title()));
+            if !item.excerpt().trim().is_empty() {
+                content.push(format!("{}\n", item.excerpt().trim()));
+            }
+            content.push(format!("<{}>\n", item.link()));
+            let content = content.join("\n");
+            let content = links::strip_tracking(content.as_str(), &config.tracking_keys());
+
+            context.changes().record(note_path.as_path());
+            let mut file = File::create(note_path.as_path()).await?;
+            file.write_all(content.as_bytes()).await?;
+            log::trace!(
+                "The Raindrop note \"{}\" has been created",
+                note_path.display()
+            );
+
+            paths.push(note_path);
+        }
+
+        Ok(paths)
+    }
+}
+
+#[async_trait(?Send)]
+impl Grabber for RaindropGrabber {
+    #[inline]
+    fn source(&self) -> &'static str {
+        "raindrop"
+    }
+
+    ///
+    /// Grab Raindrop bookmarks notes, only the bookmarks created after
+    /// the last grab if only the newer items are grabbed.
+    ///
+    async fn grab(&self, context: &GrabContext<'_>) -> Result<GrabReport, Error> {
+        let paths = self.grab_notes(context).await?;
+
+        let mut summary = Summary::default();
+        summary.count("grabbed", paths.len(), "Raindrop bookmark");
+
+        Ok(GrabReport::new(paths, summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_test() {
+        let item: Item = serde_json::from_str(
+            r#"{
+                "title": "Rust: the <book>",
+                "link": "https://doc.rust-lang.org/book/",
+                "tags": ["Rust", "reading"],
+                "created": "2022-08-03T10:00:00.000Z"
+            }"#,
+        )
+        .unwrap();
+
+        assert!(item.has_any_tag(&[]));
+        assert!(item.has_any_tag(&["rust".to_string(), "news".to_string()]));
+        assert!(!item.has_any_tag(&["news".to_string()]));
+
+        assert_eq!(item.note_name(None), "Rust the book.md");
+        assert_eq!(item.note_name(Some("RD ")), "RD Rust the book.md");
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use tokio::fs;
+
+use crate::error::Error;
+
+///
+/// The times of the last successful grabs by the source name.
+///
+#[derive(Debug)]
+pub(crate) struct GrabState {
+    path: PathBuf,
+    sources: BTreeMap<String, DateTime<Local>>,
+}
+
+impl GrabState {
+    ///
+    /// Load the state from the file, the missing or broken file is the empty state.
+    ///
+    pub(crate) async fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut state = Self {
+            path: PathBuf::from(path.as_ref()),
+            sources: BTreeMap::new(),
+        };
+
+        if state.path.is_file() {
+            let content = fs::read_to_string(state.path.as_path()).await?;
+            match serde_json::from_str(content.as_str()) {
+                Ok(sources) => state.sources = sources,
+                Err(e) => log::warn!(
+                    "The grab state \"{}\" is dropped: {}",
+                    state.path.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(state)
+    }
+
+    ///
+    /// Get the time of the last successful grab of the source.
+    ///
+    pub(crate) fn last(&self, source: &str) -> Option<DateTime<Local>> {
+        self.sources.get(source).copied()
+    }
+
+    ///
+    /// Set the time of the last successful grab of the source.
+    ///
+    pub(crate) fn set(&mut self, source: &str, time: DateTime<Local>) {
+        self.sources.insert(source.to_string(), time);
+    }
+
+    ///
+    /// Save the state to the file.
+    ///
+    pub(crate) async fn save(&self) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(
+            self.path.as_path(),
+            serde_json::to_string_pretty(&self.sources)?,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
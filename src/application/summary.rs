@@ -0,0 +1,78 @@
+use std::fmt;
+
+///
+/// The summary of the command results.
+///
+#[derive(Debug, Default)]
+pub(crate) struct Summary {
+    items: Vec<String>,
+}
+
+impl Summary {
+    ///
+    /// Add the counted item to the summary, e.g. "deleted 3 unused files".
+    ///
+    pub(crate) fn count(&mut self, action: &str, number: usize, noun: &str) {
+        let suffix = if number == 1 { "" } else { "s" };
+        self.items
+            .push(format!("{} {} {}{}", action, number, noun, suffix));
+    }
+
+    ///
+    /// Add the arbitrary item to the summary.
+    ///
+    pub(crate) fn push<S: Into<String>>(&mut self, item: S) {
+        self.items.push(item.into());
+    }
+
+    ///
+    /// Append the items of the other summary.
+    ///
+    pub(crate) fn append(&mut self, other: Summary) {
+        self.items.extend(other.items);
+    }
+
+    ///
+    /// Check if the summary is empty.
+    ///
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.items.join(", ");
+        let mut chars = line.chars();
+        if let Some(first) = chars.next() {
+            write!(f, "{}{}", first.to_uppercase(), chars.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_test() {
+        let mut summary = Summary::default();
+        assert!(summary.is_empty());
+        assert_eq!(summary.to_string(), "");
+
+        summary.count("repaired", 12, "file");
+        summary.count("deleted", 1, "unused file");
+        summary.count("renamed", 0, "file");
+        assert_eq!(
+            summary.to_string(),
+            "Repaired 12 files, deleted 1 unused file, renamed 0 files"
+        );
+
+        let mut summary = Summary::default();
+        summary.push("added the calendar");
+        assert_eq!(summary.to_string(), "Added the calendar");
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use tera::{Context, Tera};
+use tokio::{fs::File, io::AsyncReadExt};
+
+use crate::error::Error;
+
+///
+/// The notes templates.
+///
+#[derive(Debug)]
+pub(crate) struct Templates {
+    path: PathBuf,
+}
+
+impl Templates {
+    ///
+    /// Create the notes templates located in the directory.
+    ///
+    pub(crate) fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: PathBuf::from(path.as_ref()),
+        }
+    }
+
+    ///
+    /// Render the named template with the context.
+    ///
+    pub(crate) async fn render(&self, name: &str, context: &Context) -> Result<String, Error> {
+        let template_path = self.path.join(name);
+        if !template_path.is_file() {
+            return Err(Error::IllegalPath(format!("{}", template_path.display())));
+        }
+
+        let mut buffer = String::new();
+        {
+            let mut file = File::open(template_path.as_path()).await?;
+            file.read_to_string(&mut buffer).await?;
+        }
+
+        let mut tera = Tera::default();
+        tera.add_raw_template(name, buffer.as_str())?;
+        log::trace!("Rendering the template \"{}\"", template_path.display());
+
+        Ok(tera.render(name, context)?)
+    }
+
+    ///
+    /// Render the inline template with the context.
+    ///
+    pub(crate) fn render_str(template: &str, context: &Context) -> Result<String, Error> {
+        Ok(Tera::one_off(template, context, false)?)
+    }
+}
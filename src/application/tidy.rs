@@ -0,0 +1,380 @@
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+};
+
+use regex::{Captures, Regex};
+
+use crate::application::meta::Metadata;
+
+///
+/// The minimal number of the consecutive blank lines to be collapsed.
+///
+const MIN_BLANK_LINES: usize = 3;
+
+///
+/// Collapse the runs of the consecutive blank lines into the single blank
+/// line keeping the fenced code blocks verbatim.
+///
+pub(crate) fn collapse_blank_lines(content: &str) -> Cow<'_, str> {
+    let mut result = String::with_capacity(content.len());
+    let mut blank: Vec<&str> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut collapsed = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+
+        if fence.is_none() && trimmed.is_empty() {
+            blank.push(line);
+            continue;
+        }
+
+        if blank.len() >= MIN_BLANK_LINES {
+            result.push_str(blank[0]);
+            collapsed = true;
+        } else {
+            blank.iter().for_each(|line| result.push_str(line));
+        }
+        blank.clear();
+
+        // Track the fenced code block context.
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            _ => {}
+        }
+
+        result.push_str(line);
+    }
+
+    if blank.len() >= MIN_BLANK_LINES {
+        result.push_str(blank[0]);
+        collapsed = true;
+    } else {
+        blank.iter().for_each(|line| result.push_str(line));
+    }
+
+    if collapsed {
+        Cow::Owned(result)
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
+///
+/// End the non-empty content with exactly one line break collapsing
+/// the trailing ones.
+///
+pub(crate) fn final_newline(content: &str) -> Cow<'_, str> {
+    let trimmed = content.trim_end_matches(&['\r', '\n'][..]);
+    if trimmed.is_empty() {
+        return Cow::Borrowed(content);
+    }
+
+    let newline = if content[trimmed.len()..].starts_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    if content.len() == trimmed.len() + newline.len() {
+        Cow::Borrowed(content)
+    } else {
+        Cow::Owned(format!("{}{}", trimmed, newline))
+    }
+}
+
+///
+/// Turn the wiki links to the attachments into the embeds. Returns the content
+/// and the targets of the turned links, the embeds are left as is. The front
+/// matter, the fenced code blocks and the code spans are kept verbatim.
+///
+pub(crate) fn embed_attachments<F>(content: &str, is_attachment: F) -> (Cow<'_, str>, Vec<String>)
+where
+    F: Fn(&str) -> bool,
+{
+    let re = Regex::new(r"(?P<embed>!?)\[\[(?P<target>[^\[\]\n|#^]*)[^\[\]\n]*\]\]").unwrap();
+    let mut targets = Vec::new();
+
+    let (_, body) = Metadata::split(content);
+    let mut embedded = String::with_capacity(content.len());
+    embedded.push_str(&content[..content.len() - body.len()]);
+
+    let mut fence: Option<&str> = None;
+    for line in body.split_inclusive('\n') {
+        // Track the fenced code block context, the fences are code too.
+        let fenced = fence.is_some();
+        let trimmed = line.trim();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            _ => {}
+        }
+        if fenced || fence.is_some() {
+            embedded.push_str(line);
+            continue;
+        }
+
+        for (text, code) in split_code_spans(line) {
+            if code {
+                embedded.push_str(text);
+                continue;
+            }
+
+            let text = re.replace_all(text, |caps: &Captures| {
+                let link = &caps[0];
+                let target = caps["target"].trim();
+                if !caps["embed"].is_empty() || !is_attachment(target) {
+                    return link.to_string();
+                }
+
+                targets.push(target.to_string());
+                format!("!{}", link)
+            });
+            embedded.push_str(text.as_ref());
+        }
+    }
+
+    if targets.is_empty() {
+        (Cow::Borrowed(content), targets)
+    } else {
+        (Cow::Owned(embedded), targets)
+    }
+}
+
+// Split the line into the text and the code span parts, the code spans
+// are flagged by `true`. The unmatched backticks are the text.
+fn split_code_spans(line: &str) -> Vec<(&str, bool)> {
+    let bytes = line.as_bytes();
+    let run = |from: usize| bytes[from..].iter().take_while(|&&b| b == b'`').count();
+
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+
+        // Find the closing backticks run of the same length.
+        let length = run(i);
+        let mut j = i + length;
+        let mut end = None;
+        while j < bytes.len() {
+            if bytes[j] != b'`' {
+                j += 1;
+                continue;
+            }
+
+            let closing = run(j);
+            if closing == length {
+                end = Some(j + closing);
+                break;
+            }
+            j += closing;
+        }
+
+        match end {
+            Some(end) => {
+                if start < i {
+                    parts.push((&line[start..i], false));
+                }
+                parts.push((&line[i..end], true));
+                start = end;
+                i = end;
+            }
+            None => i += length,
+        }
+    }
+    if start < line.len() {
+        parts.push((&line[start..], false));
+    }
+
+    parts
+}
+
+///
+/// Replace the targets of the wiki links by the resolving function keeping
+/// the embeds, the headings and the aliases. Returns the content and the
+/// replaced and the replacing targets.
+///
+pub(crate) fn relink<F>(content: &str, resolve: F) -> (Cow<'_, str>, Vec<(String, String)>)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let re =
+        Regex::new(r"(?P<open>!?\[\[)(?P<target>[^\[\]\n|#^]*)(?P<rest>[^\[\]\n]*\]\])").unwrap();
+    let mut replaced = Vec::new();
+    let relinked = re.replace_all(content, |caps: &Captures| {
+        let target = caps["target"].trim();
+        match resolve(target) {
+            Some(new_target) if new_target != target => {
+                let link = format!("{}{}{}", &caps["open"], new_target, &caps["rest"]);
+                replaced.push((target.to_string(), new_target));
+                link
+            }
+            _ => caps[0].to_string(),
+        }
+    });
+
+    if replaced.is_empty() {
+        (Cow::Borrowed(content), replaced)
+    } else {
+        (Cow::Owned(relinked.into_owned()), replaced)
+    }
+}
+
+///
+/// Get the lines of the note body compared to find the duplicates: the front
+/// matter, the trailing whitespaces and the blank lines are ignored.
+///
+fn body_lines(content: &str) -> Vec<&str> {
+    let (_, body) = Metadata::split(content);
+    body.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+///
+/// Get the hash of the note body to find the duplicate candidates, `None`
+/// if the body is empty.
+///
+pub(crate) fn body_hash(content: &str) -> Option<u64> {
+    let lines = body_lines(content);
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    lines.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+///
+/// Group the notes with the same body, the notes of the same hash are
+/// compared by their bodies, so the hash collisions are not reported.
+/// Only the groups of two notes and more are returned.
+///
+pub(crate) fn duplicates(notes: &[(PathBuf, String)]) -> Vec<Vec<PathBuf>> {
+    let mut groups: Vec<(Vec<&str>, Vec<PathBuf>)> = Vec::new();
+    for (path, content) in notes {
+        let lines = body_lines(content);
+        match groups.iter_mut().find(|(other, _)| *other == lines) {
+            Some((_, paths)) => paths.push(path.clone()),
+            None => groups.push((lines, vec![path.clone()])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, paths)| paths)
+        .filter(|paths| paths.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapse_blank_lines_test() {
+        let content = "# Title\n\n\n\ntext\n\n\n```\n\n\n\ncode\n```\n\n\n\n";
+        assert_eq!(
+            collapse_blank_lines(content),
+            "# Title\n\ntext\n\n\n```\n\n\n\ncode\n```\n\n"
+        );
+
+        assert!(matches!(
+            collapse_blank_lines("# Title\n\ntext\n"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn final_newline_test() {
+        assert_eq!(final_newline("# Title\n\ntext"), "# Title\n\ntext\n");
+        assert_eq!(final_newline("# Title\n\ntext\n\n\n"), "# Title\n\ntext\n");
+        assert_eq!(final_newline("text\r\n\r\n"), "text\r\n");
+
+        assert!(matches!(final_newline("text\n"), Cow::Borrowed(_)));
+        assert!(matches!(final_newline(""), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn embed_attachments_test() {
+        let is_attachment = |target: &str| {
+            ["image.png", "files/diagram.svg"].contains(&target.to_lowercase().as_str())
+        };
+
+        let (content, targets) =
+            embed_attachments(include_str!("fixtures/embeds.md"), is_attachment);
+        assert_eq!(content, include_str!("fixtures/embeds-fixed.md"));
+        assert_eq!(targets, vec!["image.png", "Files/diagram.svg"]);
+
+        let (content, targets) =
+            embed_attachments(include_str!("fixtures/embeds-fixed.md"), is_attachment);
+        assert!(matches!(content, Cow::Borrowed(_)));
+        assert!(targets.is_empty());
+
+        // The front matter and the code are kept verbatim.
+        let content = "---\nbanner: \"[[image.png]]\"\n---\nSee `[[image.png]]` and \
+                       ``a ` [[image.png]]`` or [[image.png]].\n\
+                       ```markdown\n[[image.png]]\n```\n";
+        let (embedded, targets) = embed_attachments(content, is_attachment);
+        assert_eq!(
+            embedded,
+            content.replace("or [[image.png]]", "or ![[image.png]]")
+        );
+        assert_eq!(targets, vec!["image.png"]);
+    }
+
+    #[test]
+    fn relink_test() {
+        let content = "`rir:Star` [[APoD 2022-08-01|APoD]] and [[Twir 454#News]] [[TWiR 455]]\n";
+        let (relinked, replaced) = relink(content, |target| match target {
+            "Twir 454" => Some("TWiR 454".to_string()),
+            "TWiR 455" => Some("TWiR 455".to_string()),
+            _ => None,
+        });
+        assert_eq!(
+            relinked,
+            "`rir:Star` [[APoD 2022-08-01|APoD]] and [[TWiR 454#News]] [[TWiR 455]]\n"
+        );
+        assert_eq!(
+            replaced,
+            vec![("Twir 454".to_string(), "TWiR 454".to_string())]
+        );
+
+        assert!(matches!(relink(content, |_| None).0, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn duplicates_test() {
+        let note = |name: &str, content: &str| (PathBuf::from(name), content.to_string());
+        let notes = [
+            note("a.md", "---\ntags: []\n---\n# Note\n\ntext\n"),
+            note("b.md", "# Note  \n\n\ntext"),
+            note("c.md", "# Note\n\nother text\n"),
+            note("d.md", "# Other\n"),
+        ];
+
+        assert_eq!(
+            body_hash(notes[0].1.as_str()),
+            body_hash(notes[1].1.as_str())
+        );
+        assert_eq!(body_hash("---\ntags: []\n---\n\n"), None);
+
+        // The notes of the same bucket differing by the body are not grouped.
+        assert_eq!(
+            duplicates(&notes),
+            vec![vec![PathBuf::from("a.md"), PathBuf::from("b.md")]]
+        );
+        assert!(duplicates(&notes[2..]).is_empty());
+    }
+}
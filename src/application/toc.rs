@@ -0,0 +1,85 @@
+///
+/// The characters which are not allowed in the heading references.
+///
+const ILLEGAL_CHARS: &[char] = &['#', '|', '^', ':', '%', '[', ']'];
+
+///
+/// Prepend the table of contents with the headings not deeper than the
+/// given level to the markdown content.
+///
+pub(crate) fn prepend(content: &str, max_level: usize) -> String {
+    let mut fenced = false;
+    let headings: Vec<(usize, &str)> = content
+        .lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                fenced = !fenced;
+                return false;
+            }
+
+            !fenced
+        })
+        .filter_map(heading)
+        .filter(|(level, _)| *level <= max_level)
+        .collect();
+
+    let min_level = match headings.iter().map(|(level, _)| *level).min() {
+        Some(min_level) => min_level,
+        None => return content.to_string(),
+    };
+
+    let mut toc: Vec<String> = headings
+        .into_iter()
+        .map(|(level, title)| {
+            let anchor: String = title
+                .chars()
+                .filter(|c| !ILLEGAL_CHARS.contains(c))
+                .collect();
+            format!(
+                "{}- [[#{}|{}]]",
+                "  ".repeat(level - min_level),
+                anchor.trim(),
+                title
+            )
+        })
+        .collect();
+    toc.push(String::new());
+    toc.push(content.to_string());
+
+    toc.join("\n")
+}
+
+// Parse the heading line into the level and the title.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    let title = line[level..].strip_prefix(' ')?.trim();
+    if title.is_empty() {
+        None
+    } else {
+        Some((level, title))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toc_test() {
+        let content =
+            "## News\n\ntext\n\n```\n# comment\n```\n### Rust: 1.64\n#### Deep\n#hashtag\n";
+        assert_eq!(
+            prepend(content, 3),
+            format!(
+                "- [[#News|News]]\n  - [[#Rust 1.64|Rust: 1.64]]\n\n{}",
+                content
+            )
+        );
+
+        assert_eq!(prepend("no headings\n", 3), "no headings\n");
+    }
+}
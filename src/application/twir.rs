@@ -1,12 +1,41 @@
-use std::str::FromStr;
+use std::{
+    collections::HashSet,
+    iter::repeat_with,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
+use async_trait::async_trait;
 use chrono::{DateTime, FixedOffset};
+use futures::stream::{self, StreamExt};
+use prettytable::{row, Table};
 use regex::Regex;
+use tokio::{
+    fs::{self, File},
+    io::AsyncWriteExt,
+};
 
-use crate::error::Error;
+use crate::{
+    application::{
+        entry,
+        grabber::{GrabContext, GrabReport, Grabber},
+        http, links, markdown, pager,
+        summary::Summary,
+        template::Templates,
+        toc,
+    },
+    config::Config,
+    error::Error,
+};
 
 const ARCHIVE_URL: &str = "https://this-week-in-rust.org/blog/archives/index.html";
 
+///
+/// The default CSS selector of the article.
+///
+const ARTICLE_SELECTOR: &str = "article.post-content";
+
 ///
 /// This Week in Rust issue.
 ///
@@ -34,6 +63,14 @@ impl Note {
         self.title.as_str()
     }
 
+    ///
+    /// Issue number taken from the end of the title.
+    ///
+    pub(crate) fn number(&self) -> Option<u32> {
+        let prefix = self.title.trim_end_matches(|c: char| c.is_ascii_digit());
+        self.title[prefix.len()..].parse().ok()
+    }
+
     ///
     /// Issue url.
     ///
@@ -42,6 +79,523 @@ impl Note {
     }
 }
 
+///
+/// The source of the note heading title.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TitleSource {
+    H1,
+    #[default]
+    FrontMatter,
+}
+
+impl FromStr for TitleSource {
+    type Err = Error;
+
+    ///
+    /// Convert string to the TitleSource instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "h1" => Ok(Self::H1),
+            "front-matter" => Ok(Self::FrontMatter),
+            _ => Err(Error::IllegalTitleSource(s.to_string())),
+        }
+    }
+}
+
+///
+/// The output format of the shown issues.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    #[default]
+    Table,
+    Csv,
+}
+
+impl FromStr for TableFormat {
+    type Err = Error;
+
+    ///
+    /// Convert string to the TableFormat instance.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::IllegalTableFormat(s.to_string())),
+        }
+    }
+}
+
+///
+/// The options of This Week in Rust issues grabbing.
+///
+#[derive(Debug, Default, Clone)]
+pub(crate) struct GrabOptions {
+    ///
+    /// Update daily note in notes set.
+    ///
+    pub(crate) update_daily: bool,
+
+    ///
+    /// The deepest heading level of the table of contents if it is required.
+    ///
+    pub(crate) toc_depth: Option<usize>,
+
+    ///
+    /// Save the raw article HTML next to the note.
+    ///
+    pub(crate) keep_html: bool,
+
+    ///
+    /// The CSS selector of the article overriding the default one.
+    ///
+    pub(crate) selector: Option<String>,
+
+    ///
+    /// The source of the note heading title.
+    ///
+    pub(crate) title_from: TitleSource,
+
+    ///
+    /// Append the issues to the single note instead of the note per issue.
+    ///
+    pub(crate) merge_into: Option<PathBuf>,
+
+    ///
+    /// The column width to wrap the paragraphs to if it is required.
+    ///
+    pub(crate) wrap: Option<usize>,
+
+    ///
+    /// Show the issues which would be grabbed without grabbing them.
+    ///
+    pub(crate) dry_run: bool,
+
+    ///
+    /// Wait for the rate limit to pass as the server requests instead of failing.
+    ///
+    pub(crate) wait_rate_limit: bool,
+
+    ///
+    /// The adornment of the note file names.
+    ///
+    pub(crate) affix: entry::NameAffix,
+}
+
+impl GrabOptions {
+    ///
+    /// Get the note path of the issue adorned by the affix.
+    ///
+    pub(crate) fn note_path(&self, config: &Config, number: u32) -> PathBuf {
+        self.affix.apply(config.twir_note_path(number))
+    }
+
+    ///
+    /// Get the note name of the issue to link it, the merged issues are
+    /// linked to the note they are appended to.
+    ///
+    pub(crate) fn note_name(&self, config: &Config, number: u32) -> String {
+        let path = match self.merge_into {
+            Some(ref merge_path) => merge_path.clone(),
+            None => self.note_path(config, number),
+        };
+
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("TWiR {}", number))
+    }
+}
+
+///
+/// This Week in Rust grabber.
+///
+#[derive(Debug)]
+pub(crate) struct TWiRGrabber {
+    issues: Issues,
+    options: GrabOptions,
+}
+
+impl TWiRGrabber {
+    ///
+    /// Create This Week in Rust grabber of the issues with options.
+    ///
+    pub(crate) fn new(issues: Issues, options: GrabOptions) -> Self {
+        Self { issues, options }
+    }
+
+    ///
+    /// Grab This Week in Rust issues.
+    ///
+    async fn grab_notes(&self, context: &GrabContext<'_>) -> Result<Vec<PathBuf>, Error> {
+        let options = &self.options;
+        let config = context.config();
+
+        // Validate the article selector before grabbing.
+        article_selector(options)?;
+
+        let notes = Arc::new(Notes::select(context.client(), config.max_body_size()).await?);
+        tokio::fs::create_dir_all(config.twir_path()).await?;
+
+        let (grabbed, errors) = match &self.issues {
+            // The issues range.
+            Issues::Range(min_number, max_number) => {
+                let results =
+                    stream::iter(*min_number..=*max_number)
+                        .zip(stream::iter(repeat_with(|| notes.clone())))
+                        .map(|(number, notes)| async move {
+                            self.grab_note(context, number, notes).await
+                        })
+                        .buffered(config.network_jobs())
+                        .collect::<Vec<_>>()
+                        .await;
+
+                let mut grabbed: Vec<(PathBuf, String)> = Vec::new();
+                let mut errors: Vec<Error> = Vec::new();
+                for r in results.into_iter() {
+                    match r {
+                        Ok(note) => grabbed.push(note),
+                        Err(e) => errors.push(e),
+                    }
+                }
+
+                (grabbed, errors)
+            }
+
+            // The single issue.
+            Issues::Single(number) => {
+                let note = self.grab_note(context, *number, notes.clone()).await?;
+
+                (vec![note], Vec::new())
+            }
+        };
+
+        // Append the grabbed issues ordered by the number to the single note.
+        let paths = match options.merge_into {
+            Some(ref merge_path) if !grabbed.is_empty() => {
+                let sections: Vec<String> = grabbed.into_iter().map(|(_, s)| s).collect();
+                merge_notes(context, merge_path.as_path(), sections).await?;
+                vec![merge_path.clone()]
+            }
+            _ => grabbed.into_iter().map(|(path, _)| path).collect(),
+        };
+
+        if !errors.is_empty() {
+            return Err(Error::multiple(errors));
+        }
+
+        Ok(paths)
+    }
+
+    ///
+    /// Grab This Week in Rust single note. Returns the note path and content.
+    ///
+    async fn grab_note(
+        &self,
+        context: &GrabContext<'_>,
+        number: u32,
+        notes: Arc<Notes>,
+    ) -> Result<(PathBuf, String), Error> {
+        let options = &self.options;
+        let config = context.config();
+        let templates = context.templates();
+        let update_daily = options.update_daily;
+        let language = config.language();
+        let keys = config.keys();
+        let note = notes.find(number)?;
+        let html_content = http::text(
+            http::send(context.client().get(note.url()), options.wait_rate_limit).await?,
+            config.max_body_size(),
+        )
+        .await?;
+        if html_content.trim().is_empty() {
+            return Err(Error::IllegalHTMLContent);
+        }
+        let document = scraper::Html::parse_document(&html_content);
+
+        let (selector, article_selector) = article_selector(options)?;
+        let article = match document.select(&article_selector).next() {
+            Some(article) => article,
+            None => {
+                return Err(Error::HtmlExtraction {
+                    url: note.url().to_string(),
+                    reason: format!("the selector \"{}\" does not match any element", selector),
+                })
+            }
+        };
+        let mut md_content =
+            markdown::fix_conversion(html2md::parse_html(article.inner_html().as_str()).as_str());
+        md_content =
+            links::strip_tracking(md_content.as_str(), &config.tracking_keys()).into_owned();
+        if let Some(width) = options.wrap {
+            md_content = markdown::wrap(md_content.as_str(), width);
+        }
+        if let Some(toc_depth) = options.toc_depth {
+            md_content = toc::prepend(md_content.as_str(), toc_depth);
+        }
+
+        let heading = match options.title_from {
+            TitleSource::H1 => {
+                // The page header is not the part of the article.
+                let h1_selector = scraper::Selector::parse("h1").unwrap();
+                let h1 = article
+                    .select(&h1_selector)
+                    .next()
+                    .map(|h1| h1.text().collect::<String>().trim().to_string())
+                    .filter(|h1| !h1.is_empty());
+                if h1.is_none() {
+                    log::warn!("The article \"{}\" has no first heading", note.url());
+                }
+                h1
+            }
+            TitleSource::FrontMatter => None,
+        };
+
+        let note_date = note.datetime().naive_local().date();
+        let date = config.format_date(note_date);
+
+        let tags =
+            entry::TemplateEntry::multiple(vec!["rust".to_string(), "news/twir".to_string()]);
+        let aliases = entry::TemplateEntry::multiple(vec![
+            note.title().to_string(),
+            format!("TWiR {} {}", date, language.twir(number)),
+        ]);
+
+        let prev = if number > 1 { Some(number - 1) } else { None };
+        let next = number + 1;
+
+        let daily_path = config.daily_note_path(note_date);
+
+        let daily = daily_path.exists() && daily_path.is_file();
+        if update_daily && !daily {
+            log::warn!("Irrelevant daily path \"{}\"", daily_path.display());
+        }
+        let daily_ref = if update_daily && daily {
+            format!("[[{}]]", date)
+        } else {
+            date.clone()
+        };
+
+        let content = if options.merge_into.is_some() {
+            // The merged issues are separated by their headings.
+            let heading = heading.unwrap_or_else(|| format!("{}: {}", date, language.twir(number)));
+            format!("## {}\n\n{}", heading, md_content)
+        } else if let Some(name) = config.twir_template() {
+            let mut context = tera::Context::new();
+            context.insert("number", &number);
+            context.insert("title", note.title());
+            context.insert("heading", &heading);
+            context.insert("date", &date);
+            context.insert("daily", &daily_ref);
+            context.insert("tags", &tags);
+            context.insert("aliases", &aliases);
+            context.insert("url", note.url());
+            context.insert("prev", &prev);
+            context.insert("next", &next);
+            context.insert("content", &md_content);
+
+            templates.render(name, &context).await?
+        } else {
+            let mut content = vec![
+                format!("---\n{}: news\n{}: {}", keys.note_type, keys.issue, number),
+                format!("{}: {}", keys.date, date),
+                format!("{}:", keys.tags),
+            ];
+            content.extend(tags.iter().map(|tag| format!("- {}", tag)));
+            content.push(format!("{}:", keys.aliases));
+            content.extend(aliases.quoted().map(|alias| format!("- {}", alias)));
+            content.push(format!("{}: {}\n---\n", keys.url, note.url()));
+
+            // The neighbour issues are linked by their actual note names.
+            let next_link = format!("[[{}|{}]]", options.note_name(config, next), next);
+            if let Some(prev) = prev {
+                content.push(format!(
+                    "<< [[{}|{}]] | {} >>\n",
+                    options.note_name(config, prev),
+                    prev,
+                    next_link
+                ));
+            } else {
+                content.push(format!("| {} >>\n", next_link));
+            }
+
+            // The heading keeps the link to the daily note.
+            let heading = heading.unwrap_or_else(|| language.twir(number));
+            content.push(format!("# {}: {}\n", daily_ref, heading));
+            content.push(md_content);
+
+            content.join("\n")
+        };
+
+        let note_path = options.note_path(config, number);
+        if options.keep_html {
+            let html_path = note_path.with_extension("html");
+            fs::write(html_path.as_path(), article.html()).await?;
+            context.changes().record(html_path.as_path());
+            log::trace!(
+                "The This Week in Rust article \"{}\" has been saved",
+                html_path.display()
+            );
+        }
+
+        if options.merge_into.is_none() {
+            context.changes().record(note_path.as_path());
+            let mut file = File::create(note_path.as_path()).await?;
+            file.write_all(content.as_bytes()).await?;
+            log::trace!(
+                "The This Weel in Rust note \"{}\" has been created",
+                note_path.display()
+            );
+        }
+
+        if update_daily && daily {
+            // The back-link refers to the note by its actual name.
+            let note_name = options.note_name(config, number);
+            let back_link = match config.twir_daily_ref() {
+                Some(template) => {
+                    let mut context = tera::Context::new();
+                    context.insert("number", &number);
+                    context.insert("date", &date);
+                    context.insert("note", &note_name);
+                    context.insert("title", &language.twir(number));
+                    Templates::render_str(template, &context)?
+                }
+                None => format!(
+                    "`rir:Newspaper` [[{}|{}]]",
+                    note_name,
+                    language.twir(number)
+                ),
+            };
+            let line = format!("\n\n{}\n", back_link);
+            context
+                .daily()
+                .append(daily_path.as_path(), line.as_str())
+                .await?;
+            context.changes().record(daily_path.as_path());
+        }
+
+        Ok((note_path, content))
+    }
+}
+
+#[async_trait(?Send)]
+impl Grabber for TWiRGrabber {
+    #[inline]
+    fn source(&self) -> &'static str {
+        "twir"
+    }
+
+    #[inline]
+    fn is_dry_run(&self) -> bool {
+        self.options.dry_run
+    }
+
+    ///
+    /// Grab This Week in Rust notes or only show them on the dry run.
+    ///
+    async fn grab(&self, context: &GrabContext<'_>) -> Result<GrabReport, Error> {
+        if self.options.dry_run {
+            let config = context.config();
+            let notes = Notes::select(context.client(), config.max_body_size())
+                .await?
+                .select_issues(&self.issues);
+
+            // Show the issues which would be grabbed through the pager.
+            let table = notes.table(config, Some(&self.options.affix));
+            pager::page(table.to_string().as_str()).await?;
+            let found = table.len();
+
+            let mut summary = Summary::default();
+            summary.count("found", found, "TWiR issue");
+
+            return Ok(GrabReport::new(Vec::new(), summary));
+        }
+
+        let paths = self.grab_notes(context).await?;
+
+        let mut summary = Summary::default();
+        summary.count("grabbed", paths.len(), "TWiR issue");
+
+        Ok(GrabReport::new(paths, summary))
+    }
+}
+
+///
+/// Get This Week in Rust article selector giving the priority to the
+/// overriding one.
+///
+fn article_selector(options: &GrabOptions) -> Result<(&str, scraper::Selector), Error> {
+    let selector = options.selector.as_deref().unwrap_or(ARTICLE_SELECTOR);
+    let article_selector = scraper::Selector::parse(selector)
+        .map_err(|_| Error::IllegalSelector(selector.to_string()))?;
+
+    Ok((selector, article_selector))
+}
+
+///
+/// Get the sections which headings are not present in the note content.
+///
+fn new_sections(content: &str, sections: Vec<String>) -> Vec<String> {
+    let mut headings: HashSet<String> = content
+        .lines()
+        .filter(|line| line.starts_with("## "))
+        .map(|line| line.trim_end().to_string())
+        .collect();
+
+    sections
+        .into_iter()
+        .filter(|section| {
+            let heading = section.lines().next().unwrap_or_default().trim_end();
+            if headings.insert(heading.to_string()) {
+                true
+            } else {
+                log::info!("The section \"{}\" is already merged", heading);
+                false
+            }
+        })
+        .collect()
+}
+
+///
+/// Append the sections to the note creating it if required. The sections
+/// already present in the note are skipped.
+///
+async fn merge_notes(
+    context: &GrabContext<'_>,
+    path: &Path,
+    sections: Vec<String>,
+) -> Result<(), Error> {
+    let exists = path.is_file();
+    let sections = if exists {
+        new_sections(fs::read_to_string(path).await?.as_str(), sections)
+    } else {
+        new_sections("", sections)
+    };
+    if sections.is_empty() {
+        return Ok(());
+    }
+
+    let mut content = sections.join("\n\n");
+    if exists {
+        content.insert_str(0, "\n\n");
+    } else if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(content.as_bytes()).await?;
+    context.changes().record(path);
+    log::trace!("The note \"{}\" has been updated", path.display());
+
+    Ok(())
+}
+
 ///
 /// The collection of This Week in Rust issues.
 ///
@@ -53,12 +607,24 @@ impl Notes {
     ///
     /// Select all This Week in Rust issues.
     ///
-    pub(crate) async fn select() -> Result<Notes, Error> {
+    #[inline]
+    pub(crate) async fn select(client: &reqwest::Client, limit: usize) -> Result<Notes, Error> {
+        Self::select_from(client, ARCHIVE_URL, limit).await
+    }
+
+    ///
+    /// Select all This Week in Rust issues from the archive.
+    ///
+    async fn select_from(
+        client: &reqwest::Client,
+        url: &str,
+        limit: usize,
+    ) -> Result<Notes, Error> {
         log::trace!(
             "Retriving the \"This Week in Rust\" issues list from the \"{}\"",
-            ARCHIVE_URL
+            url
         );
-        let html_content = reqwest::get(ARCHIVE_URL).await?.text().await?;
+        let html_content = http::text(client.get(url).send().await?, limit).await?;
         log::trace!("Parsing the \"This Week in Rust\" issues list");
         let document = scraper::Html::parse_document(&html_content);
 
@@ -103,6 +669,35 @@ impl Notes {
         Self { notes }
     }
 
+    ///
+    /// Get the page of issues collection, the pages are numbered from 1.
+    ///
+    pub(crate) fn page(self, page: usize, per_page: usize) -> Self {
+        let mut notes: Vec<_> = self
+            .notes
+            .into_iter()
+            .skip(page.saturating_sub(1) * per_page)
+            .take(per_page)
+            .collect();
+        notes.shrink_to_fit();
+
+        Self { notes }
+    }
+
+    ///
+    /// Get the issues collection containing only the requested issues.
+    ///
+    pub(crate) fn select_issues(self, issues: &Issues) -> Self {
+        let mut notes: Vec<_> = self
+            .notes
+            .into_iter()
+            .filter(|note| note.number().is_some_and(|number| issues.contains(number)))
+            .collect();
+        notes.shrink_to_fit();
+
+        Self { notes }
+    }
+
     ///
     /// Get the iterator to iterate issues collection.
     ///
@@ -112,17 +707,45 @@ impl Notes {
     }
 
     ///
-    /// Find the issue by it's number.
+    /// Create the table of the issues with their grabbing status if required,
+    /// the grabbed issues are found by the note names adorned by the affix.
     ///
-    pub(crate) fn find(&self, number: u32) -> Result<&Note, Error> {
-        let issue = number.to_string();
-        for item in self.notes.iter() {
-            if item.title.ends_with(issue.as_str()) {
-                return Ok(item);
+    pub(crate) fn table(&self, config: &Config, missing: Option<&entry::NameAffix>) -> Table {
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        if missing.is_some() {
+            table.set_titles(row![b => "Date", "Title", "URL", "Status"]);
+        } else {
+            table.set_titles(row![b => "Date", "Title", "URL"]);
+        }
+        for note in self.iter() {
+            let mut row = row![note.datetime().format("%Y-%m-%d"), note.title(), note.url()];
+            if let Some(affix) = missing {
+                // Check if the issue has been already grabbed.
+                let present = note
+                    .number()
+                    .is_some_and(|number| affix.apply(config.twir_note_path(number)).is_file());
+                row.add_cell(prettytable::Cell::new(if present {
+                    "present"
+                } else {
+                    "missing"
+                }));
             }
+            table.add_row(row);
         }
 
-        Err(Error::IllegalIssue(issue))
+        table
+    }
+
+    ///
+    /// Find the issue by it's number.
+    ///
+    pub(crate) fn find(&self, number: u32) -> Result<&Note, Error> {
+        self.notes
+            .iter()
+            .find(|note| note.number() == Some(number))
+            .ok_or_else(|| Error::IllegalIssue(number.to_string()))
     }
 }
 
@@ -142,6 +765,18 @@ pub enum Issues {
     Single(u32),
 }
 
+impl Issues {
+    ///
+    /// Check if the issue number is requested.
+    ///
+    pub(crate) fn contains(&self, number: u32) -> bool {
+        match *self {
+            Self::Range(min, max) => (min..=max).contains(&number),
+            Self::Single(value) => value == number,
+        }
+    }
+}
+
 impl FromStr for Issues {
     type Err = Error;
 
@@ -173,6 +808,47 @@ impl FromStr for Issues {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn note_name_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", uuid::Uuid::new_v4()));
+        let content = "[Notes]\nRoot = \"/notes\"\nTWiRPrefix = \"Rust \"\n\n\
+                       [\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        tokio::fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        tokio::fs::remove_file(path.as_path()).await.unwrap();
+
+        let options = GrabOptions {
+            affix: entry::NameAffix::new(Some("2022 - "), None),
+            ..GrabOptions::default()
+        };
+        assert_eq!(
+            options.note_path(&config, 463).file_name().unwrap(),
+            "2022 - Rust 463.md"
+        );
+        assert_eq!(options.note_name(&config, 463), "2022 - Rust 463");
+
+        let options = GrabOptions {
+            merge_into: Some(PathBuf::from("/notes/Rust News.md")),
+            ..options
+        };
+        assert_eq!(options.note_name(&config, 463), "Rust News");
+    }
+
+    #[test]
+    fn new_sections_test() {
+        let content = "## 2022-10-05: This Week in Rust 463\n\ntext\n";
+        let sections = vec![
+            "## 2022-10-05: This Week in Rust 463\n\ntext".to_string(),
+            "## 2022-10-12: This Week in Rust 464\n\nmore".to_string(),
+            "## 2022-10-12: This Week in Rust 464\n\nmore".to_string(),
+        ];
+
+        assert_eq!(
+            new_sections(content, sections),
+            vec!["## 2022-10-12: This Week in Rust 464\n\nmore".to_string()]
+        );
+    }
+
     #[test]
     fn issue_test() {
         for i in 1..=100 {
@@ -181,6 +857,8 @@ mod tests {
         }
 
         assert!(Issues::from_str("0").is_err());
+        assert!(Issues::from_str("3..5").unwrap().contains(5));
+        assert!(!Issues::from_str("3..5").unwrap().contains(6));
         assert!(Issues::from_str("-1").is_err());
 
         for (i1, i2) in (1..=100).zip((1..=100).map(|x| x * x)) {
@@ -193,4 +871,137 @@ mod tests {
             assert_eq!(value, Issues::Range(i1, i2));
         }
     }
+
+    #[tokio::test]
+    async fn table_csv_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", uuid::Uuid::new_v4()));
+        let content = "[Notes]\nRoot = \"/notes\"\n\n[\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        fs::remove_file(path.as_path()).await.unwrap();
+
+        let notes = Notes {
+            notes: vec![Note {
+                datetime: DateTime::parse_from_rfc3339("2022-08-03T00:00:00+00:00").unwrap(),
+                title: "This Week in Rust 454: \"Rust, Cargo\" and You".to_string(),
+                url: "https://this-week-in-rust.org/blog/2022/08/03/this-week-in-rust-454/"
+                    .to_string(),
+            }],
+        };
+
+        let csv = notes.table(&config, None).to_csv(Vec::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(csv.into_inner().unwrap()).unwrap(),
+            "Date,Title,URL\n2022-08-03,\"This Week in Rust 454: \"\"Rust, Cargo\"\" and You\",\
+             https://this-week-in-rust.org/blog/2022/08/03/this-week-in-rust-454/\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn table_missing_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", uuid::Uuid::new_v4()));
+        let path = root.join("nta.toml");
+        fs::create_dir_all(root.join("TWiR")).await.unwrap();
+        let content = format!(
+            "[Notes]\nRoot = {:?}\nTWiR = {:?}\n\n[\"NASA APoD API\"]\nVersion = \"v1\"\n",
+            root,
+            root.join("TWiR")
+        );
+        fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+
+        let note = |number: u32| Note {
+            datetime: DateTime::parse_from_rfc3339("2022-08-03T00:00:00+00:00").unwrap(),
+            title: format!("This Week in Rust {}", number),
+            url: String::new(),
+        };
+        let notes = Notes {
+            notes: vec![note(454), note(455)],
+        };
+        let status = |affix: &entry::NameAffix| {
+            let csv = notes
+                .table(&config, Some(affix))
+                .to_csv(Vec::new())
+                .unwrap();
+            String::from_utf8(csv.into_inner().unwrap())
+                .unwrap()
+                .lines()
+                .skip(1)
+                .map(|line| line.rsplit(',').next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // The affixed issue is present only for the same affix.
+        fs::write(root.join("TWiR/2022 - TWiR 454.md"), "")
+            .await
+            .unwrap();
+        let affix = entry::NameAffix::new(Some("2022 - "), None);
+        assert_eq!(status(&affix), ["present", "missing"]);
+        assert_eq!(status(&entry::NameAffix::default()), ["missing", "missing"]);
+
+        fs::remove_dir_all(root.as_path()).await.unwrap();
+    }
+
+    #[test]
+    fn number_test() {
+        let note = |title: &str| Note {
+            datetime: DateTime::parse_from_rfc3339("2022-08-03T00:00:00+00:00").unwrap(),
+            title: title.to_string(),
+            url: String::new(),
+        };
+
+        assert_eq!(note("This Week in Rust 454").number(), Some(454));
+        assert_eq!(note("This Week in Rust").number(), None);
+
+        let notes = Notes {
+            notes: vec![note("This Week in Rust 454"), note("This Week in Rust 54")],
+        };
+        assert_eq!(notes.find(54).unwrap().title(), "This Week in Rust 54");
+        assert!(notes.find(4).is_err());
+    }
+
+    #[tokio::test]
+    async fn select_gzip_test() {
+        use std::io::Write;
+
+        use flate2::{write::GzEncoder, Compression};
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(include_str!("fixtures/twir-archive.html").as_bytes())
+            .unwrap();
+        let body = encoder.finish().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let size = stream.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..size]).to_lowercase();
+
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\
+                 Content-Encoding: gzip\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).await.unwrap();
+            stream.write_all(body.as_slice()).await.unwrap();
+
+            request
+        });
+
+        let notes = Notes::select_from(&http::client(), url.as_str(), 1024 * 1024)
+            .await
+            .unwrap();
+        assert!(server.await.unwrap().contains("accept-encoding: gzip"));
+
+        let notes: Vec<_> = notes.iter().map(|note| note.number()).collect();
+        assert_eq!(notes, vec![Some(454), Some(453)]);
+    }
 }
@@ -0,0 +1,241 @@
+use std::{ffi::OsStr, io, path::Path};
+
+use encoding_rs::Encoding;
+use tokio::{fs, io::BufReader};
+use walkdir::{DirEntry, FilterEntry, IntoIter, WalkDir};
+
+use crate::application::meta::Metadata;
+
+///
+/// The default extensions of the notes.
+///
+pub(crate) const NOTE_EXTENSIONS: &[&str] = &["md"];
+
+///
+/// Create the directory walker limited by the maximum depth. The symbolic
+/// links are followed only on demand because they may form cycles.
+///
+pub(crate) fn dir<P: AsRef<Path>>(
+    path: P,
+    max_depth: Option<usize>,
+    follow_links: bool,
+) -> WalkDir {
+    let walker = WalkDir::new(path).follow_links(follow_links);
+    match max_depth {
+        Some(max_depth) => walker.max_depth(max_depth),
+        None => walker,
+    }
+}
+
+///
+/// The entries of the directory walk.
+///
+pub(crate) type Entries = FilterEntry<IntoIter, fn(&DirEntry) -> bool>;
+
+///
+/// Get the entries of the walker, the hidden files and directories are
+/// skipped with their contents unless they are included. The walk root
+/// is never skipped.
+///
+pub(crate) fn entries(walker: WalkDir, include_hidden: bool) -> Entries {
+    let filter: fn(&DirEntry) -> bool = if include_hidden {
+        |_| true
+    } else {
+        |entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+    };
+
+    walker.into_iter().filter_entry(filter)
+}
+
+///
+/// Check if the file is the note by its extension.
+///
+pub(crate) fn is_note<P: AsRef<Path>>(path: P, extensions: &[&str]) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(extension))
+        })
+}
+
+///
+/// Read the note decoding it with the fallback encoding if it is not valid
+/// UTF-8. Returns `None` and reports the note if it cannot be decoded.
+///
+pub(crate) async fn read_note<P: AsRef<Path>>(
+    path: P,
+    fallback: Option<&'static Encoding>,
+) -> io::Result<Option<String>> {
+    let path = path.as_ref();
+    let bytes = match String::from_utf8(fs::read(path).await?) {
+        Ok(content) => return Ok(Some(content)),
+        Err(e) => e.into_bytes(),
+    };
+
+    if let Some(encoding) = fallback {
+        let (content, malformed) = encoding.decode_without_bom_handling(&bytes);
+        if !malformed {
+            log::debug!(
+                "The note \"{}\" has been decoded from {}",
+                path.display(),
+                encoding.name()
+            );
+            return Ok(Some(content.into_owned()));
+        }
+    }
+
+    log::warn!(
+        "The note \"{}\" is not valid UTF-8 and has been skipped",
+        path.display()
+    );
+    Ok(None)
+}
+
+///
+/// Read the note to scan it only, the bytes which cannot be decoded are
+/// replaced, so the note is never skipped.
+///
+pub(crate) async fn scan_note<P: AsRef<Path>>(
+    path: P,
+    fallback: Option<&'static Encoding>,
+) -> io::Result<String> {
+    let bytes = match String::from_utf8(fs::read(path.as_ref()).await?) {
+        Ok(content) => return Ok(content),
+        Err(e) => e.into_bytes(),
+    };
+
+    Ok(match fallback {
+        Some(encoding) => encoding.decode_without_bom_handling(&bytes).0.into_owned(),
+        None => String::from_utf8_lossy(&bytes).into_owned(),
+    })
+}
+
+///
+/// Read the front matter metadata of the note only, the body of the note
+/// is not read.
+///
+pub(crate) async fn read_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    let mut reader = BufReader::new(fs::File::open(path.as_ref()).await?);
+    Metadata::from_reader(&mut reader).await
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(nested.as_path()).unwrap();
+        std::fs::write(nested.join("note.md"), "").unwrap();
+        std::os::unix::fs::symlink(root.as_path(), nested.join("loop")).unwrap();
+
+        let count = |max_depth, follow_links| {
+            dir(root.as_path(), max_depth, follow_links)
+                .into_iter()
+                .filter_map(Result::ok)
+                .count()
+        };
+        assert_eq!(count(None, false), 5);
+        assert_eq!(count(Some(1), false), 2);
+        // The symbolic link loop is detected and reported as an error.
+        assert_eq!(count(None, true), 4);
+
+        std::fs::remove_dir_all(root.as_path()).unwrap();
+    }
+
+    #[test]
+    fn entries_test() {
+        let root = std::env::temp_dir().join(format!(".nta-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join(".obsidian").join("plugins")).unwrap();
+        std::fs::write(root.join(".obsidian/plugins/README.md"), "").unwrap();
+        std::fs::write(root.join(".hidden.md"), "").unwrap();
+        std::fs::write(root.join("note.md"), "").unwrap();
+
+        let count = |include_hidden| {
+            entries(dir(root.as_path(), None, false), include_hidden)
+                .filter_map(Result::ok)
+                .count()
+        };
+        assert_eq!(count(false), 2);
+        assert_eq!(count(true), 6);
+
+        std::fs::remove_dir_all(root.as_path()).unwrap();
+    }
+
+    #[test]
+    fn is_note_test() {
+        assert!(is_note("Notes/note.md", NOTE_EXTENSIONS));
+        assert!(!is_note("Notes/note.markdown", NOTE_EXTENSIONS));
+        assert!(!is_note("Notes/md", NOTE_EXTENSIONS));
+
+        let extensions = ["md", ".markdown"];
+        assert!(is_note("Notes/note.markdown", &extensions));
+        assert!(is_note("Notes/NOTE.MD", &extensions));
+        assert!(!is_note("Files/image.png", &extensions));
+    }
+
+    #[tokio::test]
+    async fn markdown_extension_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("Files")).await.unwrap();
+        let path = root.join("note.markdown");
+        fs::write(path.as_path(), include_str!("fixtures/note.markdown"))
+            .await
+            .unwrap();
+        fs::write(root.join("note.md"), "# Note\n").await.unwrap();
+        fs::write(root.join("Files/image.png"), "image")
+            .await
+            .unwrap();
+
+        let notes = |extensions: &[&str]| {
+            let mut notes: Vec<_> = entries(dir(root.as_path(), None, false), false)
+                .filter_map(Result::ok)
+                .filter(|e| e.file_type().is_file() && is_note(e.path(), extensions))
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect();
+            notes.sort();
+            notes
+        };
+        assert_eq!(notes(NOTE_EXTENSIONS), vec!["note.md"]);
+        assert_eq!(notes(&["md", "markdown"]), vec!["note.markdown", "note.md"]);
+        assert!(read_note(path.as_path(), None)
+            .await
+            .unwrap()
+            .is_some_and(|content| content.contains("![[image.png]]")));
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_note_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.md", Uuid::new_v4()));
+        fs::write(path.as_path(), include_bytes!("fixtures/cp1251.md"))
+            .await
+            .unwrap();
+
+        assert!(read_note(path.as_path(), None).await.unwrap().is_none());
+        assert!(scan_note(path.as_path(), None)
+            .await
+            .unwrap()
+            .ends_with(" ![[image.png]]\n"));
+
+        let fallback = Encoding::for_label(b"windows-1251");
+        assert_eq!(
+            read_note(path.as_path(), fallback)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("# Заметка\n\nСм. ![[image.png]]\n")
+        );
+
+        fs::remove_file(path.as_path()).await.unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::{DateTime, Local};
+use chrono_tz::Tz;
+
+use crate::{application::age::Age, config::WatchTask, error::Error};
+
+///
+/// The schedule of the watched task.
+///
+#[derive(Debug)]
+pub(crate) enum Schedule {
+    Every(Duration),
+    Cron(Box<cron::Schedule>, Option<Tz>),
+}
+
+impl Schedule {
+    ///
+    /// Get the first run time of the schedule after the given time, the cron
+    /// schedule follows the time zone if it is set or the local one.
+    ///
+    pub(crate) fn after(&self, time: &DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Self::Every(duration) => chrono::Duration::from_std(*duration)
+                .ok()
+                .and_then(|duration| time.checked_add_signed(duration)),
+            Self::Cron(schedule, Some(time_zone)) => schedule
+                .after(&time.with_timezone(time_zone))
+                .next()
+                .map(|next| next.with_timezone(&Local)),
+            Self::Cron(schedule, None) => schedule.after(time).next(),
+        }
+    }
+}
+
+///
+/// The task run by the watch command.
+///
+#[derive(Debug)]
+pub(crate) struct Task {
+    command: String,
+    args: Vec<String>,
+    schedule: Schedule,
+    next: Option<DateTime<Local>>,
+}
+
+impl Task {
+    ///
+    /// Create the task of the configured one validating its command and
+    /// its schedule in the time zone.
+    ///
+    pub(crate) fn new(task: &WatchTask, time_zone: Option<Tz>) -> Result<Self, Error> {
+        let illegal = |reason: &str| Error::IllegalSchedule {
+            task: task.command.clone(),
+            reason: reason.to_string(),
+        };
+
+        if task.command.trim().is_empty() {
+            return Err(illegal("the command is empty"));
+        }
+        let args = shlex::split(task.command.as_str())
+            .ok_or_else(|| illegal("the command quotes are not closed"))?;
+
+        let schedule = match (task.every.as_deref(), task.cron.as_deref()) {
+            (Some(every), None) => {
+                let duration = Age::from_str(every)?.duration();
+                if duration.is_zero() {
+                    return Err(Error::IllegalDuration(every.to_string()));
+                }
+
+                Schedule::Every(duration)
+            }
+            (None, Some(cron)) => {
+                let schedule = cron::Schedule::from_str(cron)
+                    .map_err(|e| illegal(format!("{} in \"{}\"", e, cron).as_str()))?;
+                Schedule::Cron(Box::new(schedule), time_zone)
+            }
+            _ => return Err(illegal("set either Every or Cron")),
+        };
+
+        Ok(Self {
+            command: task.command.clone(),
+            args,
+            schedule,
+            next: None,
+        })
+    }
+
+    ///
+    /// The command line of the task.
+    ///
+    #[inline]
+    pub(crate) fn command(&self) -> &str {
+        self.command.as_str()
+    }
+
+    ///
+    /// The command line arguments of the task split like the shell does.
+    ///
+    #[inline]
+    pub(crate) fn args(&self) -> impl Iterator<Item = &str> {
+        self.args.iter().map(String::as_str)
+    }
+
+    ///
+    /// The next run time of the task, `None` if the task will not run anymore.
+    ///
+    #[inline]
+    pub(crate) fn next(&self) -> Option<DateTime<Local>> {
+        self.next
+    }
+
+    ///
+    /// Check if the task is due at the given time.
+    ///
+    #[inline]
+    pub(crate) fn is_due(&self, time: &DateTime<Local>) -> bool {
+        self.next.is_some_and(|next| next <= *time)
+    }
+
+    ///
+    /// Run the task at the given time.
+    ///
+    #[inline]
+    pub(crate) fn run_at(&mut self, time: DateTime<Local>) {
+        self.next = Some(time);
+    }
+
+    ///
+    /// Schedule the next run of the task after the given time.
+    ///
+    #[inline]
+    pub(crate) fn reschedule(&mut self, time: &DateTime<Local>) {
+        self.next = self.schedule.after(time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn task_test() {
+        let time = Local.ymd(2022, 8, 3).and_hms(10, 30, 0);
+        let watch_task = |every: Option<&str>, cron: Option<&str>| WatchTask {
+            command: "grab apod".to_string(),
+            every: every.map(str::to_string),
+            cron: cron.map(str::to_string),
+        };
+
+        let mut task = Task::new(&watch_task(Some("6h"), None), None).unwrap();
+        assert_eq!(task.args().collect::<Vec<_>>(), vec!["grab", "apod"]);
+        assert!(!task.is_due(&time));
+        task.reschedule(&time);
+        assert_eq!(task.next(), Some(Local.ymd(2022, 8, 3).and_hms(16, 30, 0)));
+
+        let mut task = Task::new(&watch_task(None, Some("0 0 9 * * *")), None).unwrap();
+        task.reschedule(&time);
+        assert_eq!(task.next(), Some(Local.ymd(2022, 8, 4).and_hms(9, 0, 0)));
+        assert!(task.is_due(&Local.ymd(2022, 8, 4).and_hms(9, 0, 0)));
+
+        // The cron schedule follows the configured time zone.
+        let time_zone: Tz = "Asia/Tokyo".parse().unwrap();
+        let mut task = Task::new(&watch_task(None, Some("0 0 9 * * *")), Some(time_zone)).unwrap();
+        task.reschedule(&time);
+        assert_eq!(
+            task.next(),
+            Some(
+                time_zone
+                    .ymd(2022, 8, 4)
+                    .and_hms(9, 0, 0)
+                    .with_timezone(&Local)
+            )
+        );
+
+        // The quoted arguments are kept whole.
+        let mut quoted = watch_task(Some("1d"), None);
+        quoted.command = "grab twir --merge-into \"Rust News.md\"".to_string();
+        let task = Task::new(&quoted, None).unwrap();
+        assert_eq!(
+            task.args().collect::<Vec<_>>(),
+            vec!["grab", "twir", "--merge-into", "Rust News.md"]
+        );
+        quoted.command = "grab twir --merge-into \"Rust News.md".to_string();
+        assert!(matches!(
+            Task::new(&quoted, None),
+            Err(Error::IllegalSchedule { .. })
+        ));
+
+        for (every, cron) in [
+            (None, None),
+            (Some("1d"), Some("0 0 9 * * *")),
+            (None, Some("9am")),
+        ] {
+            assert!(matches!(
+                Task::new(&watch_task(every, cron), None),
+                Err(Error::IllegalSchedule { .. })
+            ));
+        }
+        assert!(matches!(
+            Task::new(&watch_task(Some("0h"), None), None),
+            Err(Error::IllegalDuration(_))
+        ));
+    }
+}
@@ -1,7 +1,20 @@
-use chrono::Datelike;
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
-use crate::application::{twir, Application};
+use chrono::{Datelike, NaiveDate};
+use clap::{CommandFactory, ErrorKind, Parser, Subcommand};
+
+use crate::application::{age, apod, calendar, format, graph, twir, Application};
+
+///
+/// The description of the process exit codes.
+///
+const EXIT_CODES: &str = "EXIT CODES:
+    0    The command has succeeded
+    1    Internal error
+    2    Configuration or command line error
+    3    Network error
+    4    File system error
+    5    Some items of the batch have failed";
 
 ///
 /// The application arguments.
@@ -12,6 +25,8 @@ use crate::application::{twir, Application};
     version,
     about = Application::DESCRIPTION,
     long_about = None,
+    after_help = EXIT_CODES,
+    arg_required_else_help = true,
 )]
 #[clap(propagate_version = true)]
 pub struct Arguments {
@@ -21,11 +36,134 @@ pub struct Arguments {
     #[clap(short = 'v', long = "verbose", parse(from_occurrences))]
     pub(crate) verbosity: i32,
 
+    ///
+    /// The configuration file path.
+    ///
+    #[clap(short = 'c', long = "config", takes_value = true, global = true)]
+    pub(crate) config: Option<PathBuf>,
+
+    ///
+    /// Do not print the command summary.
+    ///
+    #[clap(short = 'q', long = "quiet", parse(from_flag), global = true)]
+    pub(crate) quiet: bool,
+
+    ///
+    /// The maximum depth of the notes set walks. The symbolic links are
+    /// not followed unless the `FollowLinks` option is configured.
+    ///
+    #[clap(long = "max-depth", takes_value = true, global = true)]
+    pub(crate) max_depth: Option<usize>,
+
+    ///
+    /// The number of the concurrent network requests.
+    ///
+    #[clap(long = "net-jobs", takes_value = true, global = true)]
+    pub(crate) net_jobs: Option<usize>,
+
+    ///
+    /// The number of the concurrently processed files.
+    ///
+    #[clap(long = "io-jobs", takes_value = true, global = true)]
+    pub(crate) io_jobs: Option<usize>,
+
+    ///
+    /// The maximum size of the downloaded response bodies in bytes.
+    ///
+    #[clap(long = "max-body-size", takes_value = true, global = true)]
+    pub(crate) max_body_size: Option<usize>,
+
+    ///
+    /// Scan all the notes ignoring the index of the unchanged ones.
+    ///
+    #[clap(long = "no-index", parse(from_flag), global = true)]
+    pub(crate) no_index: bool,
+
+    ///
+    /// Walk the hidden files and directories too, e.g. ".obsidian".
+    ///
+    #[clap(long = "include-hidden", parse(from_flag), global = true)]
+    pub(crate) include_hidden: bool,
+
+    ///
+    /// Disable the colored output, it is disabled by the NO_COLOR environment
+    /// variable too or if the standard output is not a terminal.
+    ///
+    #[clap(long = "no-color", parse(from_flag), global = true)]
+    pub(crate) no_color: bool,
+
+    ///
+    /// The qualifier of the configuration and the data directories identity,
+    /// "ru" by default or the NTA_QUALIFIER environment variable.
+    ///
+    #[clap(long = "qualifier", takes_value = true, global = true)]
+    pub(crate) qualifier: Option<String>,
+
+    ///
+    /// The author of the configuration and the data directories identity,
+    /// "new-divos" by default or the NTA_AUTHOR environment variable.
+    ///
+    #[clap(long = "author", takes_value = true, global = true)]
+    pub(crate) author: Option<String>,
+
+    ///
+    /// The application name of the configuration and the data directories
+    /// identity, "nta" by default or the NTA_APPLICATION environment variable.
+    ///
+    #[clap(long = "application", takes_value = true, global = true)]
+    pub(crate) application: Option<String>,
+
+    ///
+    /// The output format of the errors (text or json), given before the command.
+    ///
+    #[clap(
+        long = "format",
+        default_value = "text",
+        takes_value = true,
+        parse(try_from_str)
+    )]
+    pub(crate) format: format::Format,
+
+    ///
+    /// Commit the changes of the notes set with the given message.
+    ///
+    #[clap(long = "commit", takes_value = true, global = true)]
+    pub(crate) commit: Option<String>,
+
+    ///
+    /// Print the configuration file path and exit.
+    ///
+    #[clap(long = "print-config-path", parse(from_flag))]
+    pub(crate) print_config_path: bool,
+
+    ///
+    /// Print the log file path and exit.
+    ///
+    #[clap(long = "print-log-path", parse(from_flag))]
+    pub(crate) print_log_path: bool,
+
     ///
     /// The application command.
     ///
     #[clap(subcommand)]
-    pub(crate) command: Command,
+    pub(crate) command: Option<Command>,
+}
+
+impl Arguments {
+    ///
+    /// Parse the process arguments, the command is required unless
+    /// the paths are printed.
+    ///
+    pub fn parse_args() -> Self {
+        let args = Self::parse();
+        if args.command.is_none() && !args.print_config_path && !args.print_log_path {
+            Self::command()
+                .error(ErrorKind::MissingSubcommand, "the command is required")
+                .exit();
+        }
+
+        args
+    }
 }
 
 ///
@@ -50,19 +188,185 @@ pub enum Command {
         #[clap(long = "remove-unused-files", parse(from_flag))]
         remove_unused_files: bool,
 
+        ///
+        /// Remove only unused files older than the given age (e.g. 7d, 24h).
+        ///
+        #[clap(long = "older-than", takes_value = true, parse(try_from_str))]
+        older_than: Option<age::Age>,
+
         ///
         /// Rename attached files.
         ///
         #[clap(long = "rename-files", parse(from_flag))]
         rename_files: bool,
+
+        ///
+        /// Move the attached files into the subdirectories configured by
+        /// Notes.FilesShard (back to the flat directory if zero) and rewrite the links.
+        ///
+        #[clap(long = "reshard", parse(from_flag))]
+        reshard: bool,
+
+        ///
+        /// Lowercase the attached file names which are the uppercase UUIDs and rewrite the links.
+        ///
+        #[clap(long = "canonical-uuid", parse(from_flag))]
+        canonical_uuid: bool,
+
+        ///
+        /// Set the default banners of the notes by the note type.
+        ///
+        #[clap(long = "apply-banners", parse(from_flag))]
+        apply_banners: bool,
+
+        ///
+        /// Collapse the runs of three or more blank lines into the single one.
+        ///
+        #[clap(long = "tidy", parse(from_flag))]
+        tidy: bool,
+
+        ///
+        /// End every note with exactly one line break.
+        ///
+        #[clap(long = "final-newline", parse(from_flag))]
+        final_newline: bool,
+
+        ///
+        /// Rewrite the front matter tags in the configured style (block by default).
+        ///
+        #[clap(long = "normalize-tags-block", parse(from_flag))]
+        normalize_tags_block: bool,
+
+        ///
+        /// Turn the wiki links to the attached files into the embeds.
+        ///
+        #[clap(long = "fix-embeds-without-exclamation", parse(from_flag))]
+        fix_embeds: bool,
+
+        ///
+        /// Rewrite the YouTube iframes of the APoD notes in the configured video style.
+        ///
+        #[clap(long = "fix-apod-iframes", parse(from_flag))]
+        fix_apod_iframes: bool,
+
+        ///
+        /// Set the modification time of the notes changed by this repair.
+        ///
+        #[clap(long = "touch-modified", parse(from_flag))]
+        touch_modified: bool,
+
+        ///
+        /// Report the notes with the duplicate front matter keys.
+        ///
+        #[clap(long = "dedup-keys", parse(from_flag))]
+        dedup_keys: bool,
+
+        ///
+        /// Fix the reported problems keeping the last values of the duplicate keys.
+        ///
+        #[clap(long = "fix", parse(from_flag), requires = "dedup-keys")]
+        fix: bool,
+
+        ///
+        /// Report the back-links of the daily notes to the missing APoD and TWiR notes.
+        ///
+        #[clap(long = "fix-daily-links", parse(from_flag))]
+        fix_daily_links: bool,
+
+        ///
+        /// Rewrite the back-links differing from the note names in case or spacing only.
+        ///
+        #[clap(long = "apply", parse(from_flag), requires = "fix-daily-links")]
+        apply: bool,
+
+        ///
+        /// The prefix of the grabbed note file names the back-links refer to.
+        ///
+        #[clap(long = "prefix", takes_value = true, requires = "fix-daily-links")]
+        prefix: Option<String>,
+
+        ///
+        /// The suffix of the grabbed note file names the back-links refer to.
+        ///
+        #[clap(long = "suffix", takes_value = true, requires = "fix-daily-links")]
+        suffix: Option<String>,
+
+        ///
+        /// Report the groups of the notes with the same content.
+        ///
+        #[clap(long = "find-duplicates", parse(from_flag))]
+        find_duplicates: bool,
+
+        ///
+        /// Report the Astronomy Picture of the Day notes older than the kept ones
+        /// with their images.
+        ///
+        #[clap(long = "prune-apod", parse(from_flag))]
+        prune_apod: bool,
+
+        ///
+        /// The number of the most recent Astronomy Picture of the Day notes kept by pruning.
+        ///
+        #[clap(long = "keep", default_value_t = 30, takes_value = true)]
+        keep: usize,
+
+        ///
+        /// Confirm the removal of the pruned notes.
+        ///
+        #[clap(short = 'y', long = "yes", parse(from_flag), requires = "prune-apod")]
+        yes: bool,
     },
 
     ///
     /// Grab notes to the notes set.
     ///
     Grab {
+        ///
+        /// Override the templates directory of the notes set.
+        ///
+        #[clap(long = "template-dir", takes_value = true)]
+        template_dir: Option<PathBuf>,
+
+        ///
+        /// Open the grabbed note with the editor.
+        ///
+        #[clap(short = 'o', long = "open", parse(from_flag))]
+        open: bool,
+
+        ///
+        /// Grab only the items newer than the last successful grab of the source.
+        ///
+        #[clap(long = "since-last", parse(from_flag))]
+        since_last: bool,
+
+        ///
+        /// Prepend the text to the file names of the grabbed notes.
+        ///
+        #[clap(long = "prefix", takes_value = true)]
+        prefix: Option<String>,
+
+        ///
+        /// Append the text to the file names of the grabbed notes.
+        ///
+        #[clap(long = "suffix", takes_value = true)]
+        suffix: Option<String>,
+
+        ///
+        /// Grab the notes listed by the manifest file in sequence before the given note.
+        ///
+        #[clap(long = "manifest", takes_value = true)]
+        manifest: Option<PathBuf>,
+
         #[clap(subcommand)]
-        note: Note,
+        note: Option<Note>,
+    },
+
+    ///
+    /// Open the note with the editor.
+    ///
+    Open {
+        #[clap(subcommand)]
+        target: Target,
     },
 
     ///
@@ -80,6 +384,174 @@ pub enum Command {
         #[clap(subcommand)]
         annex: Annex,
     },
+
+    ///
+    /// Initialize the application configuration and the notes set.
+    ///
+    Init {
+        ///
+        /// The root directory of the notes set.
+        ///
+        #[clap(short = 'r', long = "root", takes_value = true)]
+        root: Option<PathBuf>,
+
+        ///
+        /// The NASA Astronomy Picture of the Day API key.
+        ///
+        #[clap(short = 'k', long = "nasa-key", takes_value = true)]
+        nasa_key: Option<String>,
+
+        ///
+        /// Overwrite the existing configuration file.
+        ///
+        #[clap(short = 'f', long = "force", parse(from_flag))]
+        force: bool,
+    },
+
+    ///
+    /// Undo the last repair of the notes set.
+    ///
+    Undo,
+
+    ///
+    /// Print the version information.
+    ///
+    Version {
+        ///
+        /// Print the commit hash, the build date and the compiler version too.
+        ///
+        #[clap(long = "build-info", parse(from_flag))]
+        build_info: bool,
+    },
+
+    ///
+    /// Print the link graph of the notes set in the DOT (the default) or the JSON format.
+    ///
+    Graph {
+        ///
+        /// Include the linked attachments into the graph.
+        ///
+        #[clap(long = "attachments", parse(from_flag))]
+        attachments: bool,
+
+        ///
+        /// The output format of the graph (dot or json).
+        ///
+        #[clap(
+            long = "format",
+            default_value = "dot",
+            takes_value = true,
+            parse(try_from_str)
+        )]
+        format: graph::GraphFormat,
+    },
+
+    ///
+    /// Copy the notes of the notes set into the directory for publishing.
+    ///
+    Export {
+        ///
+        /// Remove the front matter of the exported notes leaving just the body.
+        ///
+        #[clap(long = "strip-frontmatter", parse(from_flag))]
+        strip_frontmatter: bool,
+
+        ///
+        /// The directory the notes are exported to, outside of the notes set.
+        ///
+        destination: PathBuf,
+    },
+
+    ///
+    /// Remove the caches, the rotated logs and the generated artifacts.
+    ///
+    Clean {
+        ///
+        /// Remove the empty subdirectories of the notes set files directory.
+        ///
+        #[clap(long = "empty-dirs", parse(from_flag))]
+        empty_dirs: bool,
+
+        ///
+        /// Confirm the removal of the notes set items.
+        ///
+        #[clap(short = 'y', long = "yes", parse(from_flag))]
+        yes: bool,
+    },
+
+    ///
+    /// Upgrade the configuration file to the supported schema version.
+    ///
+    Migrate,
+
+    ///
+    /// Run the configured tasks on their schedules until interrupted.
+    ///
+    Watch {
+        ///
+        /// Run all the tasks once on start before following the schedules.
+        ///
+        #[clap(long = "run-now", parse(from_flag))]
+        run_now: bool,
+    },
+
+    ///
+    /// Print the effective paths of the features after the defaults are applied.
+    ///
+    #[clap(name = "check-config")]
+    CheckConfig,
+
+    ///
+    /// Change the application configuration.
+    ///
+    Config {
+        #[clap(subcommand)]
+        setting: Setting,
+    },
+}
+
+///
+/// The application config command object.
+///
+#[derive(Debug, Subcommand)]
+#[non_exhaustive]
+pub enum Setting {
+    ///
+    /// Set the configuration property, e.g. "Notes.Root".
+    ///
+    #[clap(name = "set")]
+    Set {
+        ///
+        /// The property name.
+        ///
+        property: String,
+
+        ///
+        /// The property value.
+        ///
+        value: String,
+    },
+
+    ///
+    /// Unset the configuration property, e.g. "Git.AutoCommit".
+    ///
+    #[clap(name = "unset")]
+    Unset {
+        ///
+        /// The property name.
+        ///
+        property: String,
+    },
+}
+
+///
+/// The grab task of the manifest parsed as the grab command line.
+///
+#[derive(Debug, Parser)]
+#[clap(no_binary_name = true)]
+pub(crate) struct GrabTask {
+    #[clap(subcommand)]
+    pub(crate) note: Note,
 }
 
 ///
@@ -98,6 +570,96 @@ pub enum Note {
         ///
         #[clap(short = 'd', long = "update-daily", parse(from_flag))]
         update_daily: bool,
+
+        ///
+        /// Refer to the remote image instead of downloading it.
+        ///
+        #[clap(long = "no-download", parse(from_flag))]
+        no_download: bool,
+
+        ///
+        /// Add the date derived tags with the given granularity (year or month).
+        ///
+        #[clap(long = "date-tag", takes_value = true, parse(try_from_str))]
+        date_tag: Option<apod::DateTag>,
+
+        ///
+        /// Set the downloaded image as the banner of the note.
+        ///
+        #[clap(long = "banner", parse(from_flag))]
+        banner: bool,
+
+        ///
+        /// Name the note by the local date. The picture of the day is switched
+        /// in the US Eastern time zone, so the response date may differ from
+        /// the local one; it is still kept in the note properties.
+        ///
+        #[clap(long = "local-date", parse(from_flag))]
+        local_date: bool,
+
+        ///
+        /// Skip the grabbing without any request if the note of today already
+        /// exists, e.g. for the daily cron task. The note is named by the local
+        /// date, or by the date of the configured time zone.
+        ///
+        #[clap(long = "since-config-last", parse(from_flag))]
+        since_config_last: bool,
+
+        ///
+        /// Write only the title, the date, the explanation and the source URL
+        /// without any media reference, nothing is downloaded.
+        ///
+        #[clap(
+            long = "explanation-only",
+            parse(from_flag),
+            conflicts_with_all = &["no-download", "banner"]
+        )]
+        explanation_only: bool,
+
+        ///
+        /// Write the picture title into the note aliases, it is the default.
+        ///
+        #[clap(long = "set-alias", parse(from_flag), overrides_with = "no-alias")]
+        set_alias: bool,
+
+        ///
+        /// Do not write the picture title into the note aliases.
+        ///
+        #[clap(long = "no-alias", parse(from_flag), overrides_with = "set-alias")]
+        no_alias: bool,
+
+        ///
+        /// The inline template of the caption line under the image, e.g.
+        /// "*{{ title }}*". The title, the date and the copyright are available.
+        ///
+        #[clap(long = "caption-template", takes_value = true)]
+        caption_template: Option<String>,
+
+        ///
+        /// Write the explanation truncated to the number of words to the
+        /// front matter excerpt.
+        ///
+        #[clap(long = "excerpt", takes_value = true)]
+        excerpt: Option<usize>,
+
+        ///
+        /// Also write the API response as JSON to the file.
+        ///
+        #[clap(long = "json-out", takes_value = true)]
+        json_out: Option<PathBuf>,
+
+        ///
+        /// Wrap the explanation to the column width.
+        ///
+        #[clap(long = "wrap", takes_value = true)]
+        wrap: Option<usize>,
+
+        ///
+        /// Put the note into the subdirectory of the APoD directory, the name
+        /// is formatted as the note date, e.g. "%Y" collects the notes by the year.
+        ///
+        #[clap(long = "collection", takes_value = true)]
+        collection: Option<String>,
     },
 
     #[clap(name = "twir")]
@@ -116,6 +678,115 @@ pub enum Note {
         ///
         #[clap(short = 'd', long = "update-daily", parse(from_flag))]
         update_daily: bool,
+
+        ///
+        /// Prepend the table of contents to the note.
+        ///
+        #[clap(long = "toc", parse(from_flag))]
+        toc: bool,
+
+        ///
+        /// The deepest heading level included into the table of contents.
+        ///
+        #[clap(long = "toc-depth", default_value_t = 2, takes_value = true)]
+        toc_depth: usize,
+
+        ///
+        /// Save the raw article HTML next to the note.
+        ///
+        #[clap(long = "keep-html", parse(from_flag))]
+        keep_html: bool,
+
+        ///
+        /// Append the issues to the given note instead of the note per issue.
+        ///
+        #[clap(long = "merge-into", takes_value = true, conflicts_with = "update-daily")]
+        merge_into: Option<PathBuf>,
+
+        ///
+        /// The CSS selector of the article overriding the default one.
+        ///
+        #[clap(long = "select", takes_value = true)]
+        select: Option<String>,
+
+        ///
+        /// Take the note heading title from the article first heading (h1)
+        /// or build it from the note properties (front-matter).
+        ///
+        #[clap(
+            long = "title-from",
+            default_value = "front-matter",
+            takes_value = true,
+            parse(try_from_str)
+        )]
+        title_from: twir::TitleSource,
+
+        ///
+        /// Wrap the paragraphs to the column width.
+        ///
+        #[clap(long = "wrap", takes_value = true)]
+        wrap: Option<usize>,
+
+        ///
+        /// Show the issues which would be grabbed without writing anything.
+        ///
+        #[clap(long = "dry-run", parse(from_flag))]
+        dry_run: bool,
+
+        ///
+        /// Wait for the rate limit to pass as the server requests (Retry-After)
+        /// instead of failing the rate limited issues.
+        ///
+        #[clap(long = "wait-rate-limit", parse(from_flag))]
+        wait_rate_limit: bool,
+    },
+
+    ///
+    /// Grab Raindrop bookmarks to the notes set.
+    ///
+    #[clap(name = "raindrop")]
+    Raindrop {
+        ///
+        /// Grab only the bookmarks tagged with the tag. The bookmark is grabbed
+        /// if it has any of the given tags (OR semantics).
+        ///
+        #[clap(
+            short = 't',
+            long = "tag",
+            takes_value = true,
+            multiple_occurrences = true
+        )]
+        tags: Vec<String>,
+    },
+}
+
+///
+/// The application open command object.
+///
+#[derive(Debug, Subcommand)]
+#[non_exhaustive]
+pub enum Target {
+    ///
+    /// Open NASA Astronomy Picture of the Day note.
+    ///
+    #[clap(name = "apod")]
+    APoD {
+        ///
+        /// The note date.
+        ///
+        #[clap(default_value_t = chrono::offset::Local::today().naive_local())]
+        date: NaiveDate,
+    },
+
+    ///
+    /// Open This Week in Rust note.
+    ///
+    #[clap(name = "twir")]
+    TWiR {
+        ///
+        /// The issue number.
+        ///
+        issue: u32,
     },
 }
 
@@ -135,6 +806,82 @@ pub enum Info {
         ///
         #[clap(short = 'l', long = "last", required = false, takes_value = false)]
         last: bool,
+
+        ///
+        /// Show only the given page of issues, the pages are numbered from 1.
+        ///
+        #[clap(long = "page", takes_value = true, conflicts_with = "last")]
+        page: Option<usize>,
+
+        ///
+        /// The number of issues per page.
+        ///
+        #[clap(long = "per-page", default_value_t = 20, takes_value = true)]
+        per_page: usize,
+
+        ///
+        /// Mark the issues as present or missing in the notes set.
+        ///
+        #[clap(long = "missing", parse(from_flag))]
+        missing: bool,
+
+        ///
+        /// The prefix of the grabbed note file names to find the missing issues.
+        ///
+        #[clap(long = "prefix", takes_value = true, requires = "missing")]
+        prefix: Option<String>,
+
+        ///
+        /// The suffix of the grabbed note file names to find the missing issues.
+        ///
+        #[clap(long = "suffix", takes_value = true, requires = "missing")]
+        suffix: Option<String>,
+
+        ///
+        /// The output format of the issues (table or csv).
+        ///
+        #[clap(
+            long = "format",
+            default_value = "table",
+            takes_value = true,
+            parse(try_from_str)
+        )]
+        format: twir::TableFormat,
+
+        ///
+        /// Open the issue with the given number in the browser instead of showing the issues.
+        ///
+        #[clap(
+            long = "open",
+            takes_value = true,
+            conflicts_with_all = &["last", "page", "missing"]
+        )]
+        open: Option<u32>,
+    },
+
+    ///
+    /// Show the unused files without removing them.
+    ///
+    #[clap(name = "unused")]
+    Unused {
+        ///
+        /// Show only unused files older than the given age (e.g. 7d, 24h).
+        ///
+        #[clap(long = "older-than", takes_value = true, parse(try_from_str))]
+        older_than: Option<age::Age>,
+    },
+
+    ///
+    /// Show the notes having neither the inbound nor the outbound links.
+    ///
+    #[clap(name = "orphans")]
+    Orphans {
+        ///
+        /// Do not show the notes matching the glob pattern relative to the notes
+        /// set root (e.g. "Daily/**"), the option can be repeated.
+        ///
+        #[clap(short = 'e', long = "exclude", takes_value = true, multiple_occurrences = true)]
+        exclude: Vec<String>,
     },
 }
 
@@ -168,5 +915,80 @@ pub enum Annex {
             long = "month"
         )]
         month: u32,
+
+        ///
+        /// Add the calendars of the quarter months to the quarterly note.
+        ///
+        #[clap(short = 'Q', long = "quarter", takes_value = true, conflicts_with = "month")]
+        quarter: Option<u32>,
+
+        ///
+        /// Add the calendars of the year months to the annual note.
+        ///
+        #[clap(
+            long = "whole-year",
+            parse(from_flag),
+            conflicts_with_all = &["month", "quarter"]
+        )]
+        whole_year: bool,
+
+        ///
+        /// The calendar layout (table, list or callout).
+        ///
+        #[clap(
+            long = "style",
+            default_value = "table",
+            takes_value = true,
+            parse(try_from_str)
+        )]
+        style: calendar::Style,
+
+        ///
+        /// Link every day of the month even if the daily note does not exist.
+        ///
+        #[clap(long = "all-links", parse(from_flag))]
+        all_links: bool,
+    },
+
+    ///
+    /// Add the creation time to the front matter of the notes missing it.
+    ///
+    #[clap(name = "created")]
+    Created {
+        ///
+        /// Replace the creation time of the notes having it.
+        ///
+        #[clap(short = 'f', long = "force", parse(from_flag))]
+        force: bool,
+
+        ///
+        /// Use the file times even if the notes set is a git repository.
+        ///
+        #[clap(long = "no-git", parse(from_flag))]
+        no_git: bool,
+
+        ///
+        /// Print the unified diff of every affected note instead of writing it.
+        ///
+        #[clap(long = "diff", parse(from_flag))]
+        diff: bool,
+    },
+
+    ///
+    /// Add the modification time to the front matter of the notes.
+    ///
+    #[clap(name = "modified")]
+    Modified {
+        ///
+        /// Use the file times even if the notes set is a git repository.
+        ///
+        #[clap(long = "no-git", parse(from_flag))]
+        no_git: bool,
+
+        ///
+        /// Print the unified diff of every affected note instead of writing it.
+        ///
+        #[clap(long = "diff", parse(from_flag))]
+        diff: bool,
     },
 }
@@ -1,24 +1,269 @@
 use std::{
     borrow::Cow,
-    io::Write,
+    collections::{BTreeMap, BTreeSet},
+    env,
+    ffi::OsString,
+    io::{self, IsTerminal, Write},
     path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+    time::Duration,
 };
 
+use chrono::{
+    format::{self, StrftimeItems},
+    DateTime, Local, NaiveDate, TimeZone, Utc,
+};
+use chrono_tz::Tz;
 use directories::ProjectDirs;
+use encoding_rs::Encoding;
 use serde::{Deserialize, Serialize};
 use tokio::{
     fs::{self, File},
     io::{AsyncReadExt, AsyncWriteExt},
 };
+use toml_edit::{Document, Item, Table, TableLike, Value};
 
-use crate::application::{apod, Application};
+use crate::application::{
+    age::Age,
+    apod, entry,
+    lang::Language,
+    links,
+    meta::{Keys, ListStyle},
+    walk, Application,
+};
+use crate::cli::Arguments;
 use crate::error::Error;
 
+///
+/// The configuration properties which can be changed by the `config` command.
+///
+const PROPERTIES: &[&str] = &[
+    "Notes.Root",
+    "Notes.Files",
+    "Notes.FilesShard",
+    "Notes.Daily",
+    "Notes.Banners",
+    "Notes.APoD",
+    "Notes.TWiR",
+    "Notes.TWiRPrefix",
+    "Notes.Language",
+    "Notes.MaxDepth",
+    "Notes.FollowLinks",
+    "Notes.Extensions",
+    "Notes.DateFormat",
+    "Notes.TagsStyle",
+    "Notes.TimeZone",
+    "Notes.Encoding",
+    "NASA APoD API.Key",
+    "NASA APoD API.KeyFile",
+    "NASA APoD API.Version",
+    "NASA APoD API.Fields",
+    "NASA APoD API.Banner",
+    "NASA APoD API.VideoEmbed",
+    "Templates.Path",
+    "Templates.APoD.Content",
+    "Templates.APoD.DailyRef",
+    "Templates.APoD.Caption",
+    "Templates.TWiR.Content",
+    "Templates.TWiR.DailyRef",
+    "Git.AutoCommit",
+    "Links.TrackingKeys",
+    "Concurrency.Network",
+    "Concurrency.IO",
+    "Network.MaxBodySize",
+    "Hooks.PostGrab",
+    "Hooks.Timeout",
+    "Raindrop.Path",
+    "Raindrop.Prefix",
+    "Raindrop.Token",
+    "Raindrop.TokenFile",
+    "Raindrop.TagPrefix",
+    "Omnivore.Path",
+    "Omnivore.Prefix",
+    "Omnivore.Token",
+    "Omnivore.TokenFile",
+    "Omnivore.TagPrefix",
+    "Keys.Type",
+    "Keys.Name",
+    "Keys.Issue",
+    "Keys.Date",
+    "Keys.Tags",
+    "Keys.Aliases",
+    "Keys.URL",
+    "Keys.Banner",
+    "Keys.Excerpt",
+    "Keys.Created",
+    "Keys.Modified",
+];
+
+///
+/// The default number of the concurrent network requests.
+///
+const DEFAULT_NETWORK_JOBS: usize = 4;
+
+///
+/// The default number of the concurrently processed files.
+///
+const DEFAULT_IO_JOBS: usize = 16;
+
+///
+/// The default date format of the note names, the headings and the properties.
+///
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+///
+/// The default banners directory name of the notes set.
+///
+const DEFAULT_BANNERS_DIR: &str = "Banners";
+
+///
+/// The default time the hook is killed after.
+///
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+///
+/// The default file name prefix of This Week in Rust notes.
+///
+const DEFAULT_TWIR_PREFIX: &str = "TWiR ";
+
+///
+/// The default maximum size of the downloaded response bodies, 64 MiB.
+///
+const DEFAULT_MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+///
+/// The environment variable of the Raindrop API token.
+///
+const RAINDROP_TOKEN_VAR: &str = "NTA_RAINDROP_TOKEN";
+
+///
+/// The environment variable of the Omnivore API token.
+///
+const OMNIVORE_TOKEN_VAR: &str = "NTA_OMNIVORE_TOKEN";
+
+///
+/// The environment variable of the configuration directory overriding the platform one.
+///
+const CONFIG_DIR_VAR: &str = "NTA_CONFIG_DIR";
+
+///
+/// The environment variable of the data directory overriding the platform one.
+///
+const DATA_DIR_VAR: &str = "NTA_DATA_DIR";
+
+///
+/// The environment variable of the qualifier of the directories identity.
+///
+const QUALIFIER_VAR: &str = "NTA_QUALIFIER";
+
+///
+/// The environment variable of the author of the directories identity.
+///
+const AUTHOR_VAR: &str = "NTA_AUTHOR";
+
+///
+/// The environment variable of the application name of the directories identity.
+///
+const APPLICATION_VAR: &str = "NTA_APPLICATION";
+
+///
+/// The configuration tables whose properties can be changed by the `config` command.
+///
+const PROPERTY_TABLES: &[&str] = &["Banners"];
+
+///
+/// Get the directory from the environment variable looked up by the function
+/// if it is set and not empty, otherwise the default one.
+///
+fn dir_from_env<E>(env: &E, var: &str, default: &Path) -> PathBuf
+where
+    E: Fn(&str) -> Option<OsString>,
+{
+    match env(var) {
+        Some(value) if !value.is_empty() => PathBuf::from(value),
+        _ => PathBuf::from(default),
+    }
+}
+
+///
+/// Get the value of the command line option, the environment variable looked
+/// up by the function if it is set and not empty, or the default one.
+///
+fn value_from_env<E>(env: &E, value: Option<&str>, var: &str, default: &str) -> String
+where
+    E: Fn(&str) -> Option<OsString>,
+{
+    match value {
+        Some(value) => value.to_string(),
+        None => match env(var).and_then(|value| value.into_string().ok()) {
+            Some(value) if !value.is_empty() => value,
+            _ => default.to_string(),
+        },
+    }
+}
+
+///
+/// The identity of the platform configuration and data directories, the
+/// forks and the side-by-side installations can use their own ones.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    qualifier: String,
+    author: String,
+    application: String,
+}
+
+impl Identity {
+    ///
+    /// Get the identity of the command line arguments or the environment.
+    ///
+    fn new<E>(args: &Arguments, env: &E) -> Self
+    where
+        E: Fn(&str) -> Option<OsString>,
+    {
+        Self {
+            qualifier: value_from_env(
+                env,
+                args.qualifier.as_deref(),
+                QUALIFIER_VAR,
+                Application::QUALIFIER,
+            ),
+            author: value_from_env(env, args.author.as_deref(), AUTHOR_VAR, Application::AUTHOR),
+            application: value_from_env(
+                env,
+                args.application.as_deref(),
+                APPLICATION_VAR,
+                Application::NAME,
+            ),
+        }
+    }
+
+    ///
+    /// Get the command line arguments passing the identity to the child process.
+    ///
+    pub(crate) fn args(&self) -> [&str; 6] {
+        [
+            "--qualifier",
+            self.qualifier.as_str(),
+            "--author",
+            self.author.as_str(),
+            "--application",
+            self.application.as_str(),
+        ]
+    }
+}
+
 ///
 /// The application options.
 ///
 #[derive(Debug)]
 pub struct Options {
+    ///
+    /// The identity of the configuration and data directories.
+    ///
+    identity: Identity,
+
     ///
     /// The configuration file path.
     ///
@@ -28,37 +273,143 @@ pub struct Options {
     /// The log file path.
     ///
     log_file: PathBuf,
+
+    ///
+    /// The cache directory path.
+    ///
+    cache_path: PathBuf,
+
+    ///
+    /// The journal file path.
+    ///
+    journal_file: PathBuf,
+
+    ///
+    /// The backup directory path of the journal.
+    ///
+    backup_path: PathBuf,
+
+    ///
+    /// The notes index file path.
+    ///
+    index_file: PathBuf,
+
+    ///
+    /// The grab state file path.
+    ///
+    state_file: PathBuf,
+
+    ///
+    /// Scan all the notes ignoring the notes index.
+    ///
+    no_index: bool,
+
+    ///
+    /// The maximum depth of the notes set walks overriding the configured one.
+    ///
+    max_depth: Option<usize>,
+
+    ///
+    /// Walk the hidden files and directories of the notes set too.
+    ///
+    include_hidden: bool,
+
+    ///
+    /// The number of the concurrent network requests overriding the configured one.
+    ///
+    network_jobs: Option<usize>,
+
+    ///
+    /// The number of the concurrently processed files overriding the configured one.
+    ///
+    io_jobs: Option<usize>,
+
+    ///
+    /// The maximum size of the downloaded response bodies overriding the configured one.
+    ///
+    max_body_size: Option<usize>,
+
+    ///
+    /// Color the output.
+    ///
+    color: bool,
 }
 
 impl Options {
     ///
     /// The new instance of the application options.
     ///
-    pub async fn new() -> Result<Self, Error> {
+    pub async fn new(args: &Arguments) -> Result<Self, Error> {
+        Self::with_env(args, |var| env::var_os(var)).await
+    }
+
+    ///
+    /// The new instance of the application options looking the environment
+    /// variables up by the function.
+    ///
+    async fn with_env<E>(args: &Arguments, env: E) -> Result<Self, Error>
+    where
+        E: Fn(&str) -> Option<OsString>,
+    {
+        let identity = Identity::new(args, &env);
         let project_dirs = ProjectDirs::from(
-            Application::QUALIFIER,
-            Application::AUTHOR,
-            Application::NAME,
+            identity.qualifier.as_str(),
+            identity.author.as_str(),
+            identity.application.as_str(),
         )
         .ok_or(Error::AppInitError)?;
+        let config_dir = dir_from_env(&env, CONFIG_DIR_VAR, project_dirs.config_dir());
+        let data_dir = dir_from_env(&env, DATA_DIR_VAR, project_dirs.data_local_dir());
 
-        if !project_dirs.config_dir().exists() {
-            fs::create_dir_all(project_dirs.config_dir()).await?;
+        if !config_dir.exists() {
+            fs::create_dir_all(config_dir.as_path()).await?;
         }
-        let config_file = project_dirs.config_dir().join("nta.toml");
+        let config_file = match args.config {
+            Some(ref config_file) => config_file.clone(),
+            None => config_dir.join("nta.toml"),
+        };
 
-        let log_path = project_dirs.data_local_dir().join("log");
+        let log_path = data_dir.join("log");
         if !log_path.exists() {
             fs::create_dir_all(log_path.as_path()).await?;
         }
         let log_file = log_path.join("nta.log");
 
+        let cache_path = PathBuf::from(project_dirs.cache_dir());
+        let journal_file = data_dir.join("journal.json");
+        let backup_path = data_dir.join("backup");
+        let index_file = data_dir.join("index.json");
+        let state_file = data_dir.join("state.json");
+
         Ok(Self {
+            identity,
             config_file,
             log_file,
+            cache_path,
+            journal_file,
+            backup_path,
+            index_file,
+            state_file,
+            no_index: args.no_index,
+            max_depth: args.max_depth,
+            include_hidden: args.include_hidden,
+            network_jobs: args.net_jobs,
+            io_jobs: args.io_jobs,
+            max_body_size: args.max_body_size,
+            color: !args.no_color
+                && env("NO_COLOR").is_none_or(|value| value.is_empty())
+                && io::stdout().is_terminal(),
         })
     }
 
+    ///
+    /// Get the identity of the configuration and data directories.
+    ///
+    #[inline]
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
     ///
     /// Get the configuration file path.
     ///
@@ -74,6 +425,102 @@ impl Options {
     pub fn log_file(&self) -> &Path {
         self.log_file.as_path()
     }
+
+    ///
+    /// Get the cache directory path.
+    ///
+    #[inline]
+    pub fn cache_path(&self) -> &Path {
+        self.cache_path.as_path()
+    }
+
+    ///
+    /// Get the journal file path.
+    ///
+    #[inline]
+    pub fn journal_file(&self) -> &Path {
+        self.journal_file.as_path()
+    }
+
+    ///
+    /// Get the backup directory path of the journal.
+    ///
+    #[inline]
+    pub fn backup_path(&self) -> &Path {
+        self.backup_path.as_path()
+    }
+
+    ///
+    /// Get the notes index file path.
+    ///
+    #[inline]
+    pub fn index_file(&self) -> &Path {
+        self.index_file.as_path()
+    }
+
+    ///
+    /// Get the grab state file path.
+    ///
+    #[inline]
+    pub fn state_file(&self) -> &Path {
+        self.state_file.as_path()
+    }
+
+    ///
+    /// Check if the notes index should be ignored.
+    ///
+    #[inline]
+    pub fn no_index(&self) -> bool {
+        self.no_index
+    }
+
+    ///
+    /// Get the maximum depth of the notes set walks.
+    ///
+    #[inline]
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    ///
+    /// Check if the hidden files and directories are walked too.
+    ///
+    #[inline]
+    pub fn include_hidden(&self) -> bool {
+        self.include_hidden
+    }
+
+    ///
+    /// Get the number of the concurrent network requests.
+    ///
+    #[inline]
+    pub fn network_jobs(&self) -> Option<usize> {
+        self.network_jobs
+    }
+
+    ///
+    /// Get the number of the concurrently processed files.
+    ///
+    #[inline]
+    pub fn io_jobs(&self) -> Option<usize> {
+        self.io_jobs
+    }
+
+    ///
+    /// Get the maximum size of the downloaded response bodies.
+    ///
+    #[inline]
+    pub fn max_body_size(&self) -> Option<usize> {
+        self.max_body_size
+    }
+
+    ///
+    /// Check if the output should be colored.
+    ///
+    #[inline]
+    pub fn color(&self) -> bool {
+        self.color
+    }
 }
 
 ///
@@ -93,12 +540,25 @@ pub(crate) struct NotesConfig {
     #[serde(rename = "Files")]
     files_path: Option<PathBuf>,
 
+    ///
+    /// The number of the leading file name characters naming the subdirectory
+    /// of the attached file, the files directory is flat by default.
+    ///
+    #[serde(rename = "FilesShard", default)]
+    files_shard: usize,
+
     ///
     /// The daily directory of the notes set.
     ///
     #[serde(rename = "Daily")]
     daily_path: Option<PathBuf>,
 
+    ///
+    /// The default banners directory of the notes set.
+    ///
+    #[serde(rename = "Banners")]
+    banners_path: Option<PathBuf>,
+
     ///
     /// The Astronomy Picture of the Day directory of the notes set.
     ///
@@ -110,6 +570,60 @@ pub(crate) struct NotesConfig {
     ///
     #[serde(rename = "TWiR")]
     twir_path: Option<PathBuf>,
+
+    ///
+    /// The file name prefix of This Week in Rust notes, "TWiR " by default.
+    ///
+    #[serde(rename = "TWiRPrefix")]
+    twir_prefix: Option<String>,
+
+    ///
+    /// The language of the generated notes content.
+    ///
+    #[serde(rename = "Language")]
+    language: Option<Language>,
+
+    ///
+    /// The maximum depth of the notes set walks.
+    ///
+    #[serde(rename = "MaxDepth")]
+    max_depth: Option<usize>,
+
+    ///
+    /// Follow the symbolic links during the notes set walks.
+    ///
+    #[serde(rename = "FollowLinks", default)]
+    follow_links: bool,
+
+    ///
+    /// The recognized extensions of the notes.
+    ///
+    #[serde(rename = "Extensions")]
+    extensions: Option<Vec<String>>,
+
+    ///
+    /// The date format of the note names, the headings and the properties.
+    ///
+    #[serde(rename = "DateFormat")]
+    date_format: Option<String>,
+
+    ///
+    /// The style of the front matter tags (block or inline).
+    ///
+    #[serde(rename = "TagsStyle", default)]
+    tags_style: ListStyle,
+
+    ///
+    /// The IANA time zone of the note dates overriding the local one.
+    ///
+    #[serde(rename = "TimeZone")]
+    time_zone: Option<Tz>,
+
+    ///
+    /// The encoding of the notes which are not valid UTF-8, e.g. "windows-1251".
+    ///
+    #[serde(rename = "Encoding")]
+    encoding: Option<String>,
 }
 
 ///
@@ -123,176 +637,1067 @@ pub(crate) struct NASAAPoDAPIConfig {
     #[serde(rename = "Key")]
     key: Option<String>,
 
+    ///
+    /// The file containing the NASA Astronomy Picture of the Day API Key.
+    ///
+    #[serde(rename = "KeyFile")]
+    key_file: Option<PathBuf>,
+
     ///
     /// The NASA Astronomy Picture of the Day API Version.
     ///
     #[serde(rename = "Version")]
     version: apod::Version,
+
+    ///
+    /// The response fields added to the front matter of the note.
+    ///
+    #[serde(rename = "Fields", default)]
+    fields: Vec<String>,
+
+    ///
+    /// Set the downloaded image as the banner of the note.
+    ///
+    #[serde(rename = "Banner", default)]
+    banner: bool,
+
+    ///
+    /// The markup style of the embedded videos, iframe or markdown.
+    ///
+    #[serde(rename = "VideoEmbed", default)]
+    video_embed: apod::VideoEmbed,
 }
 
 ///
-/// The application configuration.
+/// The note templates names.
 ///
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Config {
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct NoteTemplatesConfig {
     ///
-    /// The notes application configuration.
+    /// The name of the note content template.
     ///
-    #[serde(rename = "Notes")]
-    notes: NotesConfig,
+    #[serde(rename = "Content")]
+    content: Option<String>,
 
     ///
-    /// The NASA Astronomy Picture of the Day API configuration.
+    /// The inline template of the daily note back-link.
     ///
-    #[serde(rename = "NASA APoD API")]
-    nasa_apod: NASAAPoDAPIConfig,
+    #[serde(rename = "DailyRef")]
+    daily_ref: Option<String>,
+
+    ///
+    /// The inline template of the media caption.
+    ///
+    #[serde(rename = "Caption")]
+    caption: Option<String>,
 }
 
-impl Config {
+///
+/// The templates configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct TemplatesConfig {
     ///
-    /// New instance of the application configuration.
+    /// The templates directory of the notes set.
     ///
-    pub async fn new(options: &Options) -> Result<Self, Error> {
-        if !options.config_file().exists() {
-            let mut notes_root = String::new();
-            print!("Enter the notes root path: ");
-            std::io::stdout().flush()?;
-            let _ = std::io::stdin().read_line(&mut notes_root).unwrap();
-            let notes_root = PathBuf::from(notes_root.trim());
-
-            let files_path = notes_root.join("Files");
-            let daily_path = notes_root.join("Daily");
-            let base_path = notes_root.join("Base");
-
-            let science_path = base_path.join("Science");
-            let astronomy_path = science_path.join("Astronomy");
-            let apod_path = astronomy_path.join("APoD");
-
-            let development_path = base_path.join("Development");
-            let rust_path = development_path.join("Rust");
-            let twir_path = rust_path.join("TWiR");
-
-            let mut apod_key = String::new();
-            print!("Enter the NASA Astronomy Picture of the Day API key: ");
-            std::io::stdout().flush()?;
-            let _ = std::io::stdin().read_line(&mut apod_key).unwrap();
-            let apod_key = apod_key.trim().to_owned();
-
-            let config = Self {
-                notes: NotesConfig {
-                    root: notes_root,
-                    files_path: Some(files_path),
-                    daily_path: Some(daily_path),
-                    apod_path: Some(apod_path),
-                    twir_path: Some(twir_path),
-                },
-                nasa_apod: NASAAPoDAPIConfig {
-                    key: Some(apod_key),
-                    version: apod::Version::V1_0,
-                },
-            };
-
-            let content = toml::to_string(&config)?;
-            {
-                let mut file = File::create(options.config_file()).await?;
-                file.write_all(content.as_bytes()).await?;
-                log::info!(
-                    "The configuration file \"{}\" has been created",
-                    options.config_file().display()
-                );
-            }
-        }
+    #[serde(rename = "Path")]
+    path: Option<PathBuf>,
 
-        let mut buffer = String::new();
-        {
-            let mut file = File::open(options.config_file()).await?;
-            file.read_to_string(&mut buffer).await?;
-        }
+    ///
+    /// The Astronomy Picture of the Day note templates.
+    ///
+    #[serde(rename = "APoD", default)]
+    apod: NoteTemplatesConfig,
 
-        let config = toml::from_str::<Self>(&buffer)?;
-        if !config.is_root_valid() {
-            return Err(Error::IllegalNotesRoot(config.notes.root));
+    ///
+    /// The This Week in Rust note templates.
+    ///
+    #[serde(rename = "TWiR", default)]
+    twir: NoteTemplatesConfig,
+}
+
+///
+/// The git integration configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct GitConfig {
+    ///
+    /// Commit the changes after each mutating command.
+    ///
+    #[serde(rename = "AutoCommit", default)]
+    auto_commit: bool,
+}
+
+///
+/// The links processing configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct LinksConfig {
+    ///
+    /// The tracking query keys stripped from the links of the grabbed notes.
+    ///
+    #[serde(rename = "TrackingKeys")]
+    tracking_keys: Option<Vec<String>>,
+}
+
+///
+/// The concurrency configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ConcurrencyConfig {
+    ///
+    /// The number of the concurrent network requests, 4 by default.
+    ///
+    #[serde(rename = "Network")]
+    network: Option<usize>,
+
+    ///
+    /// The number of the concurrently processed files, 16 by default.
+    ///
+    #[serde(rename = "IO")]
+    io: Option<usize>,
+}
+
+///
+/// The network configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct NetworkConfig {
+    ///
+    /// The maximum size of the downloaded response bodies in bytes, 64 MiB by default.
+    ///
+    #[serde(rename = "MaxBodySize")]
+    max_body_size: Option<usize>,
+}
+
+///
+/// The hooks configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct HooksConfig {
+    ///
+    /// The script run with the path of every grabbed note. It is any program
+    /// executed with the permissions of the user, so set only the trusted one.
+    ///
+    #[serde(rename = "PostGrab")]
+    post_grab: Option<PathBuf>,
+
+    ///
+    /// The time the hook is killed after, e.g. "30s", one minute by default.
+    ///
+    #[serde(rename = "Timeout")]
+    timeout: Option<String>,
+}
+
+///
+/// The task run by the watch command on the schedule.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct WatchTask {
+    ///
+    /// The command line of the task without the program name, e.g. "grab apod".
+    ///
+    #[serde(rename = "Command")]
+    pub(crate) command: String,
+
+    ///
+    /// The interval between the task runs, e.g. "6h" or "1d".
+    ///
+    #[serde(rename = "Every")]
+    pub(crate) every: Option<String>,
+
+    ///
+    /// The cron schedule of the task runs with the seconds, e.g. "0 0 9 * * *".
+    ///
+    #[serde(rename = "Cron")]
+    pub(crate) cron: Option<String>,
+}
+
+///
+/// The bookmarking service integration configuration.
+///
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct IntegrationConfig {
+    ///
+    /// The path of the imported notes, the Base directory of the notes set by default.
+    ///
+    #[serde(rename = "Path")]
+    path: Option<PathBuf>,
+
+    ///
+    /// The file name prefix of the imported notes.
+    ///
+    #[serde(rename = "Prefix")]
+    prefix: Option<String>,
+
+    ///
+    /// The API token.
+    ///
+    #[serde(rename = "Token")]
+    token: Option<String>,
+
+    ///
+    /// The file containing the API token.
+    ///
+    #[serde(rename = "TokenFile")]
+    token_file: Option<PathBuf>,
+
+    ///
+    /// The prefix of the imported tags, e.g. "bookmark/".
+    ///
+    #[serde(rename = "TagPrefix")]
+    tag_prefix: Option<String>,
+}
+
+impl IntegrationConfig {
+    ///
+    /// Read the API token from the configuration, from the environment
+    /// variable or from the token file in this order.
+    ///
+    async fn read_token(&self, var: &str) -> Result<Option<String>, Error> {
+        if let Some(ref token) = self.token {
+            return Ok(Some(token.clone()));
+        }
+
+        if let Ok(token) = env::var(var) {
+            if !token.trim().is_empty() {
+                return Ok(Some(token.trim().to_string()));
+            }
         }
 
-        Ok(config)
+        match self.token_file {
+            Some(ref token_file) => {
+                let token = fs::read_to_string(token_file).await?;
+                Ok(Some(token.trim().to_string()).filter(|token| !token.is_empty()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+///
+/// The application configuration.
+///
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Config {
+    ///
+    /// The configuration schema version, the files without it have the version 0.
+    ///
+    #[serde(rename = "Version", default)]
+    version: u32,
+
+    ///
+    /// The notes application configuration.
+    ///
+    #[serde(rename = "Notes")]
+    notes: NotesConfig,
+
+    ///
+    /// The NASA Astronomy Picture of the Day API configuration.
+    ///
+    #[serde(rename = "NASA APoD API")]
+    nasa_apod: NASAAPoDAPIConfig,
+
+    ///
+    /// The templates configuration.
+    ///
+    #[serde(rename = "Templates", default)]
+    templates: TemplatesConfig,
+
+    ///
+    /// The git integration configuration.
+    ///
+    #[serde(rename = "Git", default)]
+    git: GitConfig,
+
+    ///
+    /// The links processing configuration.
+    ///
+    #[serde(rename = "Links", default)]
+    links: LinksConfig,
+
+    ///
+    /// The front matter key names of the notes set.
+    ///
+    #[serde(rename = "Keys", default)]
+    keys: Keys,
+
+    ///
+    /// The concurrency configuration.
+    ///
+    #[serde(rename = "Concurrency", default)]
+    concurrency: ConcurrencyConfig,
+
+    ///
+    /// The network configuration.
+    ///
+    #[serde(rename = "Network", default)]
+    network: NetworkConfig,
+
+    ///
+    /// The hooks configuration, no hooks are run by default.
+    ///
+    #[serde(rename = "Hooks", default)]
+    hooks: HooksConfig,
+
+    ///
+    /// The Raindrop bookmarks integration configuration.
+    ///
+    #[serde(rename = "Raindrop", default)]
+    raindrop: IntegrationConfig,
+
+    ///
+    /// The Omnivore articles integration configuration.
+    ///
+    #[serde(rename = "Omnivore", default)]
+    omnivore: IntegrationConfig,
+
+    ///
+    /// The default banners of the notes by the note type.
+    ///
+    #[serde(rename = "Banners", default)]
+    banners: BTreeMap<String, String>,
+
+    ///
+    /// The tasks run by the watch command.
+    ///
+    #[serde(rename = "Watch", default, skip_serializing_if = "Vec::is_empty")]
+    watch: Vec<WatchTask>,
+
+    ///
+    /// The configured banners directory relative to the notes set root with
+    /// its file names, resolved by the first banner lookup.
+    ///
+    #[serde(skip)]
+    banner_files: OnceLock<Option<(String, BTreeSet<String>)>>,
+}
+
+impl Config {
+    ///
+    /// The configuration schema version supported by the application.
+    ///
+    pub const VERSION: u32 = 1;
+
+    ///
+    /// New instance of the application configuration.
+    ///
+    pub async fn new(options: &Options) -> Result<Self, Error> {
+        let mut config = match Self::read(options.config_file()).await? {
+            Some(config) => config,
+            None => {
+                let notes_root = prompt("Enter the notes root path: ")?;
+                let apod_key = prompt("Enter the NASA Astronomy Picture of the Day API key: ")?;
+
+                let config = Self::scaffold(
+                    PathBuf::from(notes_root),
+                    Some(apod_key).filter(|key| !key.is_empty()),
+                );
+                config.write(options.config_file()).await?;
+
+                config
+            }
+        };
+
+        if !config.is_root_valid() {
+            return Err(Error::IllegalNotesRoot(config.notes.root));
+        }
+
+        if let Some(max_depth) = options.max_depth() {
+            config.notes.max_depth = Some(max_depth);
+        }
+        if let Some(network_jobs) = options.network_jobs() {
+            config.concurrency.network = Some(network_jobs);
+        }
+        if let Some(io_jobs) = options.io_jobs() {
+            config.concurrency.io = Some(io_jobs);
+        }
+        if let Some(max_body_size) = options.max_body_size() {
+            config.network.max_body_size = Some(max_body_size);
+        }
+
+        Ok(config)
+    }
+
+    // Read the configuration file if it exists and is not blank.
+    pub(crate) async fn read(path: &Path) -> Result<Option<Self>, Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut buffer = String::new();
+        {
+            let mut file = File::open(path).await?;
+            file.read_to_string(&mut buffer).await?;
+        }
+
+        if buffer.trim().is_empty() {
+            log::warn!("The configuration file \"{}\" is empty", path.display());
+            return Ok(None);
+        }
+
+        let config = toml::from_str::<Self>(&buffer).map_err(|source| Error::IllegalConfig {
+            path: PathBuf::from(path),
+            source,
+        })?;
+
+        if config.version > Self::VERSION {
+            log::warn!(
+                "The configuration file \"{}\" version {} is newer than the supported {}",
+                path.display(),
+                config.version,
+                Self::VERSION
+            );
+        }
+
+        // Validate the date format which is used in the note names too.
+        if let Some(ref date_format) = config.notes.date_format {
+            validate_date_format(date_format)?;
+        }
+
+        if let Some(ref encoding) = config.notes.encoding {
+            if Encoding::for_label(encoding.as_bytes()).is_none() {
+                return Err(Error::IllegalEncoding(encoding.clone()));
+            }
+        }
+
+        Ok(Some(config))
+    }
+
+    ///
+    /// Set the configuration property keeping the comments and the formatting
+    /// of the configuration file.
+    ///
+    pub(crate) async fn set(path: &Path, property: &str, value: &str) -> Result<(), Error> {
+        if property == "Notes.TimeZone" && value.parse::<Tz>().is_err() {
+            return Err(Error::IllegalTimeZone(value.to_string()));
+        }
+        if property == "Notes.Encoding" && Encoding::for_label(value.as_bytes()).is_none() {
+            return Err(Error::IllegalEncoding(value.to_string()));
+        }
+        if property == "Notes.DateFormat" {
+            validate_date_format(value)?;
+        }
+
+        let mut document = Self::edit(path).await?;
+
+        // Parse the value as TOML and fallback to the plain string.
+        let mut value = value
+            .parse::<Value>()
+            .unwrap_or_else(|_| Value::from(value));
+
+        let (table, key) = Self::property_table(&mut document, property)?;
+        match table.get_mut(key) {
+            Some(Item::Value(old)) => {
+                *value.decor_mut() = old.decor().clone();
+                *old = value;
+            }
+            _ => {
+                table.insert(key, Item::Value(value));
+            }
+        }
+
+        Self::save(path, &document).await
+    }
+
+    ///
+    /// Unset the configuration property keeping the comments and the formatting
+    /// of the configuration file.
+    ///
+    pub(crate) async fn unset(path: &Path, property: &str) -> Result<(), Error> {
+        let mut document = Self::edit(path).await?;
+
+        let segments: Vec<&str> = property.split('.').collect();
+        let (key, tables) = segments.split_last().unwrap();
+
+        let mut table: &mut dyn TableLike = document.as_table_mut();
+        for name in tables {
+            match table.get_mut(name).and_then(Item::as_table_like_mut) {
+                Some(t) => table = t,
+                None => return Ok(()),
+            }
+        }
+        table.remove(key);
+
+        Self::save(path, &document).await
+    }
+
+    ///
+    /// Upgrade the configuration file to the supported schema version filling
+    /// in the new defaults, the original file is backed up. Returns the old
+    /// version if the file has been upgraded.
+    ///
+    pub(crate) async fn migrate(path: &Path) -> Result<Option<u32>, Error> {
+        let config = Self::read(path).await?.ok_or(Error::CannotFindConfig)?;
+        if config.version >= Self::VERSION {
+            return Ok(None);
+        }
+
+        let mut document = Self::edit(path).await?;
+        for version in config.version..Self::VERSION {
+            Self::upgrade(&mut document, version)?;
+        }
+        document.insert("Version", toml_edit::value(i64::from(Self::VERSION)));
+
+        let mut backup_path = path.as_os_str().to_owned();
+        backup_path.push(".bak");
+        fs::copy(path, &backup_path).await?;
+        log::info!(
+            "The configuration file \"{}\" has been backed up",
+            path.display()
+        );
+
+        Self::save(path, &document).await?;
+        Ok(Some(config.version))
+    }
+
+    // Upgrade the configuration document from the version to the next one.
+    fn upgrade(document: &mut Document, version: u32) -> Result<(), Error> {
+        let defaults: Vec<(&str, Value)> = match version {
+            // The implicit defaults become explicit.
+            0 => vec![
+                ("Notes.DateFormat", Value::from(DEFAULT_DATE_FORMAT)),
+                ("Notes.TagsStyle", Value::from("block")),
+                (
+                    "Concurrency.Network",
+                    Value::from(DEFAULT_NETWORK_JOBS as i64),
+                ),
+                ("Concurrency.IO", Value::from(DEFAULT_IO_JOBS as i64)),
+                (
+                    "Network.MaxBodySize",
+                    Value::from(DEFAULT_MAX_BODY_SIZE as i64),
+                ),
+            ],
+            _ => Vec::new(),
+        };
+
+        for (property, value) in defaults {
+            let (table, key) = Self::property_table(document, property)?;
+            if !table.contains_key(key) {
+                table.insert(key, Item::Value(value));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Get the table of the property creating the missing ones and the property key.
+    fn property_table<'d, 'p>(
+        document: &'d mut Document,
+        property: &'p str,
+    ) -> Result<(&'d mut dyn TableLike, &'p str), Error> {
+        let segments: Vec<&str> = property.split('.').collect();
+        let (key, tables) = segments.split_last().unwrap();
+
+        let mut table: &mut dyn TableLike = document.as_table_mut();
+        for name in tables {
+            if !table.contains_key(name) {
+                table.insert(name, Item::Table(Table::new()));
+            }
+
+            table = table
+                .get_mut(name)
+                .and_then(Item::as_table_like_mut)
+                .ok_or_else(|| Error::IllegalConfigProperty(property.to_string()))?;
+        }
+
+        Ok((table, key))
+    }
+
+    // Read the configuration file as the editable document.
+    async fn edit(path: &Path) -> Result<Document, Error> {
+        let mut buffer = String::new();
+        if path.exists() {
+            let mut file = File::open(path).await?;
+            file.read_to_string(&mut buffer).await?;
+        }
+
+        Ok(buffer.parse::<Document>()?)
+    }
+
+    // Validate and save the edited configuration document.
+    async fn save(path: &Path, document: &Document) -> Result<(), Error> {
+        let content = document.to_string();
+        toml::from_str::<Self>(&content).map_err(|source| Error::IllegalConfig {
+            path: PathBuf::from(path),
+            source,
+        })?;
+
+        let mut file = File::create(path).await?;
+        file.write_all(content.as_bytes()).await?;
+        log::info!(
+            "The configuration file \"{}\" has been updated",
+            path.display()
+        );
+
+        Ok(())
+    }
+
+    ///
+    /// Check if the configuration property can be changed.
+    ///
+    pub(crate) fn is_property(property: &str) -> bool {
+        PROPERTIES.contains(&property)
+            || PROPERTY_TABLES.iter().any(|table| {
+                property
+                    .strip_prefix(table)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .is_some_and(|key| !key.is_empty() && !key.contains('.'))
+            })
+    }
+
+    ///
+    /// Create the configuration of the notes set with the default directories layout.
+    ///
+    pub(crate) fn scaffold(notes_root: PathBuf, apod_key: Option<String>) -> Self {
+        let files_path = notes_root.join("Files");
+        let daily_path = notes_root.join("Daily");
+        let base_path = notes_root.join("Base");
+
+        let science_path = base_path.join("Science");
+        let astronomy_path = science_path.join("Astronomy");
+        let apod_path = astronomy_path.join("APoD");
+
+        let development_path = base_path.join("Development");
+        let rust_path = development_path.join("Rust");
+        let twir_path = rust_path.join("TWiR");
+
+        let templates_path = notes_root.join("Templates");
+
+        Self {
+            version: Self::VERSION,
+            notes: NotesConfig {
+                root: notes_root,
+                files_path: Some(files_path),
+                files_shard: 0,
+                daily_path: Some(daily_path),
+                banners_path: None,
+                apod_path: Some(apod_path),
+                twir_path: Some(twir_path),
+                twir_prefix: None,
+                language: None,
+                max_depth: None,
+                follow_links: false,
+                extensions: None,
+                date_format: None,
+                tags_style: ListStyle::default(),
+                time_zone: None,
+                encoding: None,
+            },
+            nasa_apod: NASAAPoDAPIConfig {
+                key: apod_key,
+                key_file: None,
+                fields: Vec::new(),
+                banner: false,
+                version: apod::Version::V1_0,
+                video_embed: apod::VideoEmbed::default(),
+            },
+            templates: TemplatesConfig {
+                path: Some(templates_path),
+                ..TemplatesConfig::default()
+            },
+            git: GitConfig::default(),
+            links: LinksConfig::default(),
+            keys: Keys::default(),
+            concurrency: ConcurrencyConfig::default(),
+            network: NetworkConfig::default(),
+            hooks: HooksConfig::default(),
+            raindrop: IntegrationConfig::default(),
+            omnivore: IntegrationConfig::default(),
+            banners: BTreeMap::new(),
+            watch: Vec::new(),
+            banner_files: OnceLock::new(),
+        }
+    }
+
+    ///
+    /// Create the directories of the notes set.
+    ///
+    pub(crate) async fn create_dirs(&self) -> Result<(), Error> {
+        for path in [
+            self.files_path(),
+            self.daily_path(),
+            self.apod_path(),
+            self.twir_path(),
+            self.templates_path(),
+        ] {
+            if !path.exists() {
+                fs::create_dir_all(&path).await?;
+                log::info!("The directory \"{}\" has been created", path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write the configuration file.
+    ///
+    pub(crate) async fn write(&self, path: &Path) -> Result<(), Error> {
+        let content = toml::to_string(self)?;
+        {
+            let mut file = File::create(path).await?;
+            file.write_all(content.as_bytes()).await?;
+            log::info!(
+                "The configuration file \"{}\" has been created",
+                path.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Get the root directory of the notes set.
+    ///
+    #[inline]
+    pub fn root(&self) -> &Path {
+        self.notes.root.as_path()
+    }
+
+    ///
+    /// Get the files directory of the notes set.
+    ///
+    #[inline]
+    pub fn files_path(&self) -> Cow<Path> {
+        if let Some(ref path) = self.notes.files_path {
+            Cow::Borrowed(path.as_path())
+        } else {
+            Cow::Owned(self.notes.root.join("Files"))
+        }
+    }
+
+    ///
+    /// Get the files directory of the notes set.
+    ///
+    #[inline]
+    pub fn daily_path(&self) -> Cow<Path> {
+        if let Some(ref path) = self.notes.daily_path {
+            Cow::Borrowed(path.as_path())
+        } else {
+            Cow::Owned(self.notes.root.join("Daily"))
+        }
+    }
+
+    ///
+    /// Get the default banners directory of the notes set.
+    ///
+    #[inline]
+    pub fn banners_path(&self) -> Cow<'_, Path> {
+        if let Some(ref path) = self.notes.banners_path {
+            Cow::Borrowed(path.as_path())
+        } else {
+            Cow::Owned(self.notes.root.join(DEFAULT_BANNERS_DIR))
+        }
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day directory of the notes set.
+    ///
+    #[inline]
+    pub fn apod_path(&self) -> Cow<Path> {
+        if let Some(ref path) = self.notes.apod_path {
+            Cow::Borrowed(path.as_path())
+        } else {
+            Cow::Owned(
+                self.notes
+                    .root
+                    .join("Base")
+                    .join("Science")
+                    .join("Astronomy")
+                    .join("APoD"),
+            )
+        }
+    }
+
+    ///
+    /// Get the This Week in Rust directory of the notes set.
+    ///
+    #[inline]
+    pub fn twir_path(&self) -> Cow<Path> {
+        if let Some(ref path) = self.notes.twir_path {
+            Cow::Borrowed(path.as_path())
+        } else {
+            Cow::Owned(
+                self.notes
+                    .root
+                    .join("Base")
+                    .join("Development")
+                    .join("Rust")
+                    .join("TWiR"),
+            )
+        }
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day note path for the date.
+    ///
+    #[inline]
+    pub fn apod_note_path(&self, date: &str) -> PathBuf {
+        self.apod_path().join(format!("APoD {}.md", date))
+    }
+
+    ///
+    /// Get the date format of the note names, the headings and the properties.
+    ///
+    #[inline]
+    pub fn date_format(&self) -> &str {
+        self.notes
+            .date_format
+            .as_deref()
+            .unwrap_or(DEFAULT_DATE_FORMAT)
+    }
+
+    ///
+    /// Format the date of the note names, the headings and the properties.
+    ///
+    #[inline]
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(self.date_format()).to_string()
+    }
+
+    ///
+    /// Get the number of the leading file name characters naming the
+    /// subdirectory of the attached file, zero if the files are not sharded.
+    ///
+    #[inline]
+    pub fn files_shard(&self) -> usize {
+        self.notes.files_shard
+    }
+
+    ///
+    /// Get the style of the front matter tags.
+    ///
+    #[inline]
+    pub fn tags_style(&self) -> ListStyle {
+        self.notes.tags_style
+    }
+
+    ///
+    /// Get the date of the time in the configured time zone or the local one.
+    ///
+    pub fn local_date<Z: TimeZone>(&self, time: &DateTime<Z>) -> NaiveDate {
+        match self.notes.time_zone {
+            Some(ref time_zone) => time.with_timezone(time_zone).date().naive_local(),
+            None => time.with_timezone(&Local).date().naive_local(),
+        }
+    }
+
+    ///
+    /// Get the today date in the configured time zone or the local one.
+    ///
+    #[inline]
+    pub fn today(&self) -> NaiveDate {
+        self.local_date(&Utc::now())
+    }
+
+    ///
+    /// Get the encoding of the notes which are not valid UTF-8.
+    ///
+    #[inline]
+    pub fn fallback_encoding(&self) -> Option<&'static Encoding> {
+        self.notes
+            .encoding
+            .as_deref()
+            .and_then(|encoding| Encoding::for_label(encoding.as_bytes()))
+    }
+
+    ///
+    /// Check if the time zone of the note dates is configured.
+    ///
+    #[inline]
+    pub fn has_time_zone(&self) -> bool {
+        self.notes.time_zone.is_some()
+    }
+
+    ///
+    /// Get the configured time zone, `None` if the local one is used.
+    ///
+    #[inline]
+    pub fn time_zone(&self) -> Option<Tz> {
+        self.notes.time_zone
+    }
+
+    ///
+    /// Get the daily note path for the date.
+    ///
+    #[inline]
+    pub fn daily_note_path(&self, date: NaiveDate) -> PathBuf {
+        self.daily_path()
+            .join(format!("{}.md", self.format_date(date)))
+    }
+
+    ///
+    /// Get the This Week in Rust note path for the issue.
+    ///
+    #[inline]
+    pub fn twir_note_path(&self, number: u32) -> PathBuf {
+        self.twir_path()
+            .join(format!("{}{}.md", self.twir_prefix(), number))
+    }
+
+    ///
+    /// Get the file name prefix of This Week in Rust notes.
+    ///
+    #[inline]
+    pub fn twir_prefix(&self) -> Cow<'_, str> {
+        match self.notes.twir_prefix {
+            Some(ref prefix) => Cow::Owned(entry::sanitize_name(prefix)),
+            None => Cow::Borrowed(DEFAULT_TWIR_PREFIX),
+        }
+    }
+
+    ///
+    /// Get the templates directory of the notes set.
+    ///
+    #[inline]
+    pub fn templates_path(&self) -> Cow<Path> {
+        if let Some(ref path) = self.templates.path {
+            Cow::Borrowed(path.as_path())
+        } else {
+            Cow::Owned(self.notes.root.join("Templates"))
+        }
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day note content template name.
+    ///
+    #[inline]
+    pub fn apod_template(&self) -> Option<&str> {
+        self.templates.apod.content.as_deref()
+    }
+
+    ///
+    /// Get the This Week in Rust note content template name.
+    ///
+    #[inline]
+    pub fn twir_template(&self) -> Option<&str> {
+        self.templates.twir.content.as_deref()
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day daily back-link template.
+    ///
+    #[inline]
+    pub fn apod_daily_ref(&self) -> Option<&str> {
+        self.templates.apod.daily_ref.as_deref()
+    }
+
+    ///
+    /// Get the Astronomy Picture of the Day media caption template.
+    ///
+    #[inline]
+    pub fn apod_caption(&self) -> Option<&str> {
+        self.templates.apod.caption.as_deref()
     }
 
     ///
-    /// Get the root directory of the notes set.
+    /// Get the This Week in Rust daily back-link template.
     ///
     #[inline]
-    pub fn root(&self) -> &Path {
-        self.notes.root.as_path()
+    pub fn twir_daily_ref(&self) -> Option<&str> {
+        self.templates.twir.daily_ref.as_deref()
     }
 
     ///
-    /// Get the files directory of the notes set.
+    /// Get the language of the generated notes content.
     ///
     #[inline]
-    pub fn files_path(&self) -> Cow<Path> {
-        if let Some(ref path) = self.notes.files_path {
-            Cow::Borrowed(path.as_path())
-        } else {
-            Cow::Owned(self.notes.root.join("Files"))
-        }
+    pub fn language(&self) -> Language {
+        self.notes.language.unwrap_or_default()
     }
 
     ///
-    /// Get the files directory of the notes set.
+    /// Get NASA Astronomy Picture of the Day API Key.
     ///
     #[inline]
-    pub fn daily_path(&self) -> Cow<Path> {
-        if let Some(ref path) = self.notes.daily_path {
-            Cow::Borrowed(path.as_path())
-        } else {
-            Cow::Owned(self.notes.root.join("Daily"))
+    pub fn apod_key(&self) -> Option<&str> {
+        self.nasa_apod.key.as_deref()
+    }
+
+    ///
+    /// Read NASA Astronomy Picture of the Day API Key from the configuration
+    /// or from the key file if the key is not configured.
+    ///
+    pub async fn read_apod_key(&self) -> Result<Option<String>, Error> {
+        if let Some(key) = self.apod_key() {
+            return Ok(Some(key.to_string()));
+        }
+
+        match self.nasa_apod.key_file {
+            Some(ref key_file) => {
+                let key = fs::read_to_string(key_file).await?;
+                Ok(Some(key.trim().to_string()).filter(|key| !key.is_empty()))
+            }
+            None => Ok(None),
         }
     }
 
     ///
-    /// Get the Astronomy Picture of the Day directory of the notes set.
+    /// Get the Raindrop notes path.
     ///
-    #[inline]
-    pub fn apod_path(&self) -> Cow<Path> {
-        if let Some(ref path) = self.notes.apod_path {
-            Cow::Borrowed(path.as_path())
-        } else {
-            Cow::Owned(
-                self.notes
-                    .root
-                    .join("Base")
-                    .join("Science")
-                    .join("Astronomy")
-                    .join("APoD"),
-            )
+    pub fn raindrop_path(&self) -> Cow<'_, Path> {
+        match self.raindrop.path {
+            Some(ref path) => Cow::Borrowed(path.as_path()),
+            None => Cow::Owned(self.notes.root.join("Base")),
         }
     }
 
     ///
-    /// Get the This Week in Rust directory of the notes set.
+    /// Get the file name prefix of the Raindrop notes.
     ///
     #[inline]
-    pub fn twir_path(&self) -> Cow<Path> {
-        if let Some(ref path) = self.notes.twir_path {
-            Cow::Borrowed(path.as_path())
-        } else {
-            Cow::Owned(
-                self.notes
-                    .root
-                    .join("Base")
-                    .join("Development")
-                    .join("Rust")
-                    .join("TWiR"),
-            )
+    pub fn raindrop_prefix(&self) -> Option<&str> {
+        self.raindrop.prefix.as_deref()
+    }
+
+    ///
+    /// Get the prefix of the tags of the Raindrop notes.
+    ///
+    #[inline]
+    pub fn raindrop_tag_prefix(&self) -> Option<&str> {
+        self.raindrop.tag_prefix.as_deref()
+    }
+
+    ///
+    /// Read the Raindrop API token.
+    ///
+    #[inline]
+    pub async fn read_raindrop_token(&self) -> Result<Option<String>, Error> {
+        self.raindrop.read_token(RAINDROP_TOKEN_VAR).await
+    }
+
+    ///
+    /// Get the Omnivore notes path.
+    ///
+    pub fn omnivore_path(&self) -> Cow<'_, Path> {
+        match self.omnivore.path {
+            Some(ref path) => Cow::Borrowed(path.as_path()),
+            None => Cow::Owned(self.notes.root.join("Base")),
         }
     }
 
     ///
-    /// Get NASA Astronomy Picture of the Day API Key.
+    /// Get the file name prefix of the Omnivore notes.
     ///
     #[inline]
-    pub fn apod_key(&self) -> Option<&str> {
-        self.nasa_apod.key.as_deref()
+    pub fn omnivore_prefix(&self) -> Option<&str> {
+        self.omnivore.prefix.as_deref()
+    }
+
+    ///
+    /// Get the prefix of the tags of the Omnivore notes.
+    ///
+    #[inline]
+    pub fn omnivore_tag_prefix(&self) -> Option<&str> {
+        self.omnivore.tag_prefix.as_deref()
+    }
+
+    ///
+    /// Read the Omnivore API token.
+    ///
+    #[inline]
+    pub async fn read_omnivore_token(&self) -> Result<Option<String>, Error> {
+        self.omnivore.read_token(OMNIVORE_TOKEN_VAR).await
     }
 
     ///
@@ -303,9 +1708,563 @@ impl Config {
         self.nasa_apod.version
     }
 
+    ///
+    /// Get NASA Astronomy Picture of the Day API response fields added to the note.
+    ///
+    #[inline]
+    pub fn apod_fields(&self) -> &[String] {
+        self.nasa_apod.fields.as_slice()
+    }
+
+    ///
+    /// Check if the downloaded NASA Astronomy Picture of the Day image
+    /// should be set as the banner of the note.
+    ///
+    #[inline]
+    pub fn apod_banner(&self) -> bool {
+        self.nasa_apod.banner
+    }
+
+    ///
+    /// Get the markup style of the embedded NASA Astronomy Picture of the Day videos.
+    ///
+    #[inline]
+    pub fn apod_video_embed(&self) -> apod::VideoEmbed {
+        self.nasa_apod.video_embed
+    }
+
+    ///
+    /// Check if the changes should be committed after each mutating command.
+    ///
+    #[inline]
+    pub fn git_auto_commit(&self) -> bool {
+        self.git.auto_commit
+    }
+
+    ///
+    /// Get the number of the concurrent network requests.
+    ///
+    #[inline]
+    pub fn network_jobs(&self) -> usize {
+        self.concurrency
+            .network
+            .unwrap_or(DEFAULT_NETWORK_JOBS)
+            .max(1)
+    }
+
+    ///
+    /// Get the number of the concurrently processed files.
+    ///
+    #[inline]
+    pub fn io_jobs(&self) -> usize {
+        self.concurrency.io.unwrap_or(DEFAULT_IO_JOBS).max(1)
+    }
+
+    ///
+    /// Get the maximum size of the downloaded response bodies.
+    ///
+    #[inline]
+    pub fn max_body_size(&self) -> usize {
+        self.network.max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE)
+    }
+
+    ///
+    /// Get the front matter key names of the notes set.
+    ///
+    #[inline]
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    ///
+    /// Get the tracking query keys stripped from the links of the grabbed notes.
+    ///
+    pub fn tracking_keys(&self) -> Vec<&str> {
+        match self.links.tracking_keys {
+            Some(ref keys) => keys.iter().map(String::as_str).collect(),
+            None => links::TRACKING_KEYS.to_vec(),
+        }
+    }
+
+    ///
+    /// Get the maximum depth of the notes set walks.
+    ///
+    #[inline]
+    pub fn max_depth(&self) -> Option<usize> {
+        self.notes.max_depth
+    }
+
+    ///
+    /// Check if the symbolic links should be followed during the notes set walks.
+    ///
+    #[inline]
+    pub fn follow_links(&self) -> bool {
+        self.notes.follow_links
+    }
+
+    ///
+    /// Get the recognized extensions of the notes.
+    ///
+    pub fn note_extensions(&self) -> Vec<&str> {
+        match self.notes.extensions {
+            Some(ref extensions) => extensions.iter().map(String::as_str).collect(),
+            None => walk::NOTE_EXTENSIONS.to_vec(),
+        }
+    }
+
+    ///
+    /// Get the default banner of the notes of the given type. The banner
+    /// found in the configured banners directory is referred by the path
+    /// relative to the notes set root, otherwise it is referred by the file name.
+    ///
+    pub fn banner(&self, note_type: &str) -> Option<Cow<'_, str>> {
+        let banner = self.banners.get(note_type)?;
+        if banner.contains('/') {
+            return Some(Cow::Borrowed(banner.as_str()));
+        }
+
+        match self.banner_files.get_or_init(|| self.read_banner_files()) {
+            Some((dir, files)) if files.contains(banner.as_str()) => {
+                Some(Cow::Owned(format!("{}/{}", dir, banner)))
+            }
+            _ => Some(Cow::Borrowed(banner.as_str())),
+        }
+    }
+
+    // Read the file names of the configured banners directory inside of the
+    // notes set root, the default directory is not read.
+    fn read_banner_files(&self) -> Option<(String, BTreeSet<String>)> {
+        let path = self.notes.banners_path.as_deref()?;
+        let dir: Vec<_> = path
+            .strip_prefix(self.root())
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+        if dir.is_empty() {
+            return None;
+        }
+
+        let files = std::fs::read_dir(path)
+            .ok()?
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .collect();
+
+        Some((dir.join("/"), files))
+    }
+
+    ///
+    /// Get the script run with the path of every grabbed note.
+    ///
+    #[inline]
+    pub fn post_grab_hook(&self) -> Option<&Path> {
+        self.hooks.post_grab.as_deref()
+    }
+
+    ///
+    /// Get the time the hook is killed after.
+    ///
+    pub fn hook_timeout(&self) -> Result<Duration, Error> {
+        match self.hooks.timeout {
+            Some(ref timeout) => Ok(Age::from_str(timeout)?.duration()),
+            None => Ok(DEFAULT_HOOK_TIMEOUT),
+        }
+    }
+
+    ///
+    /// Get the tasks run by the watch command.
+    ///
+    #[inline]
+    pub(crate) fn watch_tasks(&self) -> &[WatchTask] {
+        self.watch.as_slice()
+    }
+
+    ///
+    /// Check if any default banner is configured.
+    ///
+    #[inline]
+    pub fn has_banners(&self) -> bool {
+        !self.banners.is_empty()
+    }
+
     // Validate notes set root.
     #[inline]
     fn is_root_valid(&self) -> bool {
         self.notes.root.exists() && self.notes.root.is_dir()
     }
 }
+
+#[cfg(test)]
+impl Options {
+    ///
+    /// The options of the tests keeping the application data in the directory.
+    ///
+    pub(crate) fn with_data_dir(config_file: &Path, data_dir: &Path) -> Self {
+        Self {
+            identity: Identity {
+                qualifier: Application::QUALIFIER.to_string(),
+                author: Application::AUTHOR.to_string(),
+                application: Application::NAME.to_string(),
+            },
+            config_file: PathBuf::from(config_file),
+            log_file: data_dir.join("log/nta.log"),
+            cache_path: data_dir.join("cache"),
+            journal_file: data_dir.join("journal.json"),
+            backup_path: data_dir.join("backup"),
+            index_file: data_dir.join("index.json"),
+            state_file: data_dir.join("state.json"),
+            no_index: true,
+            max_depth: None,
+            include_hidden: false,
+            network_jobs: None,
+            io_jobs: None,
+            max_body_size: None,
+            color: false,
+        }
+    }
+}
+
+///
+/// Prompt the user for the value.
+///
+pub(crate) fn prompt(message: &str) -> Result<String, Error> {
+    let mut value = String::new();
+    print!("{}", message);
+    std::io::stdout().flush()?;
+    let _ = std::io::stdin().read_line(&mut value)?;
+
+    Ok(value.trim().to_owned())
+}
+
+// Check if the date format is legal and the formatted dates are valid
+// file names since they are used in the note names.
+fn validate_date_format(date_format: &str) -> Result<(), Error> {
+    match format_date(NaiveDate::from_ymd(2000, 1, 1), date_format) {
+        Some(date) if !date.contains(&['/', '\\'][..]) => Ok(()),
+        _ => Err(Error::IllegalDateFormat(date_format.to_string())),
+    }
+}
+
+///
+/// Format the date, `None` if the format is illegal or contains the time
+/// items which the date cannot be formatted with.
+///
+pub(crate) fn format_date(date: NaiveDate, date_format: &str) -> Option<String> {
+    use std::fmt::Write;
+
+    if StrftimeItems::new(date_format).any(|item| matches!(item, format::Item::Error)) {
+        return None;
+    }
+
+    let mut formatted = String::new();
+    write!(formatted, "{}", date.format(date_format)).ok()?;
+    Some(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn read_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", Uuid::new_v4()));
+        assert!(Config::read(path.as_path()).await.unwrap().is_none());
+
+        fs::write(path.as_path(), "").await.unwrap();
+        assert!(Config::read(path.as_path()).await.unwrap().is_none());
+
+        fs::write(path.as_path(), " \n\t\n").await.unwrap();
+        assert!(Config::read(path.as_path()).await.unwrap().is_none());
+
+        fs::write(path.as_path(), "[Notes]\nRoot = ").await.unwrap();
+        assert!(matches!(
+            Config::read(path.as_path()).await,
+            Err(Error::IllegalConfig { .. })
+        ));
+
+        let content = "[Notes]\nRoot = \"/notes\"\n\n[\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.root(), Path::new("/notes"));
+        assert!(config.read_apod_key().await.unwrap().is_none());
+
+        let key_file = path.with_extension("key");
+        fs::write(key_file.as_path(), " DEMO_KEY\n").await.unwrap();
+        let content = format!("{}KeyFile = {:?}\n", content, key_file);
+        fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(
+            config.read_apod_key().await.unwrap().as_deref(),
+            Some("DEMO_KEY")
+        );
+
+        fs::remove_file(key_file.as_path()).await.unwrap();
+        fs::remove_file(path.as_path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn language_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", Uuid::new_v4()));
+        let content = "[Notes]\nRoot = \"/notes\"\n\n[\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        fs::write(path.as_path(), content).await.unwrap();
+
+        // The unset language keeps the Russian calendar and the English notes.
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        let language = config.language();
+        assert_eq!(language.weekdays()[0], "Пн");
+        assert_eq!(language.apod(), "Astronomy Picture of the Day");
+        assert_eq!(language.explanation(), "Explanation");
+        assert_eq!(language.image_copyright(), "Image copyright");
+        assert_eq!(language.twir(454), "This Week in Rust 454");
+
+        Config::set(path.as_path(), "Notes.Language", "en")
+            .await
+            .unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.language(), Language::English);
+        assert_eq!(config.language().weekdays()[0], "Mo");
+
+        Config::set(path.as_path(), "Notes.Language", "ru")
+            .await
+            .unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.language().explanation(), "Пояснение");
+
+        fs::remove_file(path.as_path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dir_from_env_test() {
+        use clap::Parser;
+
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let vars: HashMap<&str, OsString> = HashMap::from([
+            (CONFIG_DIR_VAR, root.join("config").into_os_string()),
+            (DATA_DIR_VAR, root.join("data").into_os_string()),
+        ]);
+        let env = |var: &str| vars.get(var).cloned();
+
+        let args = Arguments::parse_from(["nta", "undo"]);
+        let options = Options::with_env(&args, env).await.unwrap();
+        assert_eq!(options.config_file(), root.join("config/nta.toml"));
+        assert_eq!(options.journal_file(), root.join("data/journal.json"));
+        assert_eq!(options.log_file(), root.join("data/log/nta.log"));
+
+        let args = Arguments::parse_from(["nta", "-c", "/notes/nta.toml", "undo"]);
+        let options = Options::with_env(&args, env).await.unwrap();
+        assert_eq!(options.config_file(), Path::new("/notes/nta.toml"));
+        assert_eq!(options.state_file(), root.join("data/state.json"));
+
+        let args = Arguments::parse_from(["nta", "--application", "nta-fork", "undo"]);
+        let options = Options::with_env(&args, env).await.unwrap();
+        assert_eq!(
+            options.identity().args(),
+            [
+                "--qualifier",
+                "ru",
+                "--author",
+                "new-divos",
+                "--application",
+                "nta-fork"
+            ]
+        );
+        assert!(options.cache_path().to_string_lossy().contains("nta-fork"));
+
+        fs::remove_dir_all(root.as_path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn migrate_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", Uuid::new_v4()));
+        let content = "# The notes set\n[Notes]\nRoot = \"/notes\"\n\n\
+                       [\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        fs::write(path.as_path(), content).await.unwrap();
+
+        assert_eq!(Config::migrate(path.as_path()).await.unwrap(), Some(0));
+        assert_eq!(Config::migrate(path.as_path()).await.unwrap(), None);
+
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.version, Config::VERSION);
+        assert_eq!(
+            config.notes.date_format.as_deref(),
+            Some(DEFAULT_DATE_FORMAT)
+        );
+        assert_eq!(config.concurrency.network, Some(DEFAULT_NETWORK_JOBS));
+
+        let migrated = fs::read_to_string(path.as_path()).await.unwrap();
+        assert!(migrated.starts_with("Version = 1\n# The notes set\n[Notes]\n"));
+
+        let backup_path = path.with_extension("toml.bak");
+        assert_eq!(
+            fs::read_to_string(backup_path.as_path()).await.unwrap(),
+            content
+        );
+
+        fs::remove_file(backup_path.as_path()).await.unwrap();
+        fs::remove_file(path.as_path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn date_format_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", Uuid::new_v4()));
+        let date = NaiveDate::from_ymd(2022, 8, 3);
+
+        let content = "[Notes]\nRoot = \"/notes\"\nDateFormat = \"%Y%m%d\"\n\n\
+                       [\"NASA APoD API\"]\nVersion = \"v1\"\n";
+        fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.format_date(date), "20220803");
+
+        let time = Utc.ymd(2022, 8, 3).and_hms(22, 30, 0);
+        Config::set(path.as_path(), "Notes.TimeZone", "Asia/Tokyo")
+            .await
+            .unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.local_date(&time), NaiveDate::from_ymd(2022, 8, 4));
+        assert!(matches!(
+            Config::set(path.as_path(), "Notes.TimeZone", "Mars/Olympus").await,
+            Err(Error::IllegalTimeZone(_))
+        ));
+        assert_eq!(
+            config.daily_note_path(date),
+            Path::new("/notes/Daily/20220803.md")
+        );
+        assert_eq!(
+            config.apod_note_path(config.format_date(date).as_str()),
+            Path::new("/notes/Base/Science/Astronomy/APoD/APoD 20220803.md")
+        );
+
+        for date_format in ["%Q", "%D", "%Y%m%d %H"] {
+            let content = content.replace("%Y%m%d", date_format);
+            fs::write(path.as_path(), content).await.unwrap();
+            assert!(matches!(
+                Config::read(path.as_path()).await,
+                Err(Error::IllegalDateFormat(_))
+            ));
+        }
+
+        fs::remove_file(path.as_path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn banner_test() {
+        let root = std::env::temp_dir().join(format!("nta-{}", Uuid::new_v4()));
+        let path = root.join("nta.toml");
+        fs::create_dir_all(root.join("Assets/Banners"))
+            .await
+            .unwrap();
+        fs::write(root.join("Assets/Banners/rust.png"), "")
+            .await
+            .unwrap();
+
+        let content = format!(
+            "[Notes]\nRoot = {:?}\n\n[\"NASA APoD API\"]\nVersion = \"v1\"\n\n\
+             [Banners]\nsoftware = \"rust.png\"\nscience = \"space.png\"\n",
+            root
+        );
+        fs::write(path.as_path(), content).await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.banners_path(), root.join("Banners"));
+        assert_eq!(config.banner("software").as_deref(), Some("rust.png"));
+
+        // The banners of the default directory are referred by the name.
+        fs::create_dir_all(root.join("Banners")).await.unwrap();
+        fs::write(root.join("Banners/rust.png"), "").await.unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.banner("software").as_deref(), Some("rust.png"));
+
+        // The banners outside of the notes set root are referred by the name.
+        Config::set(
+            path.as_path(),
+            "Notes.Banners",
+            std::env::temp_dir().to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.banner("software").as_deref(), Some("rust.png"));
+
+        Config::set(
+            path.as_path(),
+            "Notes.Banners",
+            root.join("Assets/Banners").to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(
+            config.banner("software").as_deref(),
+            Some("Assets/Banners/rust.png")
+        );
+        assert_eq!(config.banner("science").as_deref(), Some("space.png"));
+        assert!(config.banner("news").is_none());
+
+        fs::remove_dir_all(root.as_path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn set_test() {
+        let path = std::env::temp_dir().join(format!("nta-{}.toml", Uuid::new_v4()));
+        let content = [
+            "# The notes set.",
+            "[Notes]",
+            "# The root directory.",
+            "Root = \"/notes\" # keep me",
+            "",
+            "# The NASA API.",
+            "[\"NASA APoD API\"]",
+            "Key = \"DEMO_KEY\"",
+            "Version = \"v1\"",
+            "",
+        ]
+        .join("\n");
+        fs::write(path.as_path(), content.as_str()).await.unwrap();
+
+        Config::set(path.as_path(), "Notes.Root", "/vault")
+            .await
+            .unwrap();
+        Config::set(path.as_path(), "Git.AutoCommit", "true")
+            .await
+            .unwrap();
+        Config::unset(path.as_path(), "NASA APoD API.Key")
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(path.as_path()).await.unwrap();
+        assert!(content.contains("# The notes set.\n[Notes]\n"));
+        assert!(content.contains("# The root directory.\nRoot = \"/vault\" # keep me\n"));
+        assert!(content.contains("# The NASA API.\n"));
+        assert!(!content.contains("DEMO_KEY"));
+
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(config.root(), Path::new("/vault"));
+        assert!(config.git_auto_commit());
+        assert!(config.apod_key().is_none());
+
+        assert!(Config::set(path.as_path(), "Git.AutoCommit", "yes")
+            .await
+            .is_err());
+
+        // The illegal date format is not saved to keep the configuration readable.
+        assert!(matches!(
+            Config::set(path.as_path(), "Notes.DateFormat", "%Y/%m/%d").await,
+            Err(Error::IllegalDateFormat(_))
+        ));
+        Config::set(path.as_path(), "Notes.DateFormat", "%d.%m.%Y")
+            .await
+            .unwrap();
+        let config = Config::read(path.as_path()).await.unwrap().unwrap();
+        assert_eq!(
+            config.format_date(NaiveDate::from_ymd(2022, 8, 3)),
+            "03.08.2022"
+        );
+
+        fs::remove_file(path.as_path()).await.unwrap();
+    }
+}
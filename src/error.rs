@@ -1,9 +1,9 @@
-use std::{env, io, path};
+use std::{env, io, path, time::Duration};
 
 use thiserror::Error;
 
 ///
-/// Ошибка работы программы.
+/// The application error.
 ///
 #[derive(Debug, Error)]
 pub enum Error {
@@ -13,6 +13,9 @@ pub enum Error {
     #[error("cannot find configuration file")]
     CannotFindConfig,
 
+    #[error("configuration file {0} already exists, use --force to overwrite it")]
+    ConfigAlreadyExists(path::PathBuf),
+
     #[error("illegal issue number {0}")]
     IllegalIssue(String),
 
@@ -22,12 +25,39 @@ pub enum Error {
     #[error("illegal NASA Astronomy Picture of the Day API key")]
     IllegalNASAKey,
 
+    #[error("the {0} API token is not configured")]
+    MissingToken(String),
+
     #[error("illegal URL: {0}")]
     IllegalURL(url::Url),
 
+    #[error("illegal CSS selector \"{0}\"")]
+    IllegalSelector(String),
+
     #[error("illegal HTML content")]
     IllegalHTMLContent,
 
+    #[error("cannot extract HTML content from {url}: {reason}")]
+    HtmlExtraction { url: String, reason: String },
+
+    #[error("the request to {url} is not authorized, check the API key or the token")]
+    Unauthorized { url: String },
+
+    #[error("the requests to {url} are rate limited, retry {}", match .retry_after {
+        Some(duration) => format!("in {} seconds", duration.as_secs()),
+        None => "later".to_string(),
+    })]
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+
+    #[error("the request to {url} has failed with the HTTP status {status}")]
+    HttpStatus { url: String, status: u16 },
+
+    #[error("the response body of {url} exceeds {limit} bytes")]
+    BodyTooLarge { url: String, limit: usize },
+
     #[error("unknown media type")]
     UnknownMediaType,
 
@@ -37,9 +67,60 @@ pub enum Error {
     #[error("illegal month number {0}")]
     IllegalMonthNumber(u32),
 
+    #[error("illegal quarter number {0}")]
+    IllegalQuarterNumber(u32),
+
+    #[error("{0} requires the confirmation, use --yes to confirm it")]
+    NotConfirmed(String),
+
     #[error("illegal path {0}")]
     IllegalPath(String),
 
+    #[error("illegal glob pattern \"{0}\"")]
+    IllegalGlob(String),
+
+    #[error("illegal duration {0}")]
+    IllegalDuration(String),
+
+    #[error("illegal date tag granularity {0}, use year or month")]
+    IllegalDateTag(String),
+
+    #[error("illegal date format \"{0}\"")]
+    IllegalDateFormat(String),
+
+    #[error("illegal time zone \"{0}\", use the IANA name, e.g. Europe/Moscow")]
+    IllegalTimeZone(String),
+
+    #[error("illegal editor \"{0}\", check the EDITOR environment variable")]
+    IllegalEditor(String),
+
+    #[error("illegal schedule of the task \"{task}\": {reason}")]
+    IllegalSchedule { task: String, reason: String },
+
+    #[error("illegal grab manifest {path}: {reason}")]
+    IllegalManifest { path: path::PathBuf, reason: String },
+
+    #[error("set the note to grab or the --manifest option")]
+    MissingGrabNote,
+
+    #[error("illegal encoding \"{0}\", use the WHATWG label, e.g. windows-1251")]
+    IllegalEncoding(String),
+
+    #[error("illegal calendar style {0}, use table, list or callout")]
+    IllegalCalendarStyle(String),
+
+    #[error("illegal title source {0}, use h1 or front-matter")]
+    IllegalTitleSource(String),
+
+    #[error("illegal output format {0}, use text or json")]
+    IllegalFormat(String),
+
+    #[error("illegal table format {0}, use table or csv")]
+    IllegalTableFormat(String),
+
+    #[error("illegal graph format {0}, use dot or json")]
+    IllegalGraphFormat(String),
+
     #[error("found {0:?} failed executors")]
     MultipleExecutorsError(Vec<Error>),
 
@@ -58,6 +139,21 @@ pub enum Error {
     #[error("datetime parsing error: {0}")]
     DateTimeParseError(#[from] chrono::ParseError),
 
+    #[error(
+        "cannot parse configuration file {path}: {source}; \
+        fix the file or use the --config option to choose another one"
+    )]
+    IllegalConfig {
+        path: path::PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("illegal configuration property {0}")]
+    IllegalConfigProperty(String),
+
+    #[error("configuration editing error {0}")]
+    ConfigEditError(#[from] toml_edit::TomlError),
+
     #[error("configuration parsing error {0}")]
     ConfigParseError(#[from] toml::de::Error),
 
@@ -67,6 +163,220 @@ pub enum Error {
     #[error("logger initialization error {0}")]
     InitLoggerError(#[from] fern::InitError),
 
+    #[error("JSON error {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("CSV error {0}")]
+    CsvError(#[from] csv::Error),
+
+    #[error("template error {0}")]
+    TemplateError(#[from] tera::Error),
+
+    #[error("git error {0}")]
+    GitError(#[from] git2::Error),
+
+    #[error("task execution error {0}")]
+    JoinError(#[from] tokio::task::JoinError),
+
     #[error("set logger error {0}")]
     SetLoggerError(#[from] log::SetLoggerError),
 }
+
+impl Error {
+    ///
+    /// The exit code of the internal errors.
+    ///
+    pub const EXIT_INTERNAL: u8 = 1;
+
+    ///
+    /// The exit code of the configuration and the command line errors.
+    ///
+    pub const EXIT_CONFIG: u8 = 2;
+
+    ///
+    /// The exit code of the network errors.
+    ///
+    pub const EXIT_NETWORK: u8 = 3;
+
+    ///
+    /// The exit code of the file system errors.
+    ///
+    pub const EXIT_IO: u8 = 4;
+
+    ///
+    /// The exit code of the partially failed batches.
+    ///
+    pub const EXIT_PARTIAL: u8 = 5;
+
+    ///
+    /// Collect the errors of the concurrent executors ordered by their
+    /// messages, so the report does not depend on the completion order.
+    ///
+    pub(crate) fn multiple(mut errors: Vec<Error>) -> Self {
+        errors.sort_by_cached_key(Error::to_string);
+        Self::MultipleExecutorsError(errors)
+    }
+
+    ///
+    /// Get the name of the error variant.
+    ///
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::AppInitError => "AppInitError",
+            Self::CannotFindConfig => "CannotFindConfig",
+            Self::ConfigAlreadyExists(_) => "ConfigAlreadyExists",
+            Self::IllegalIssue(_) => "IllegalIssue",
+            Self::IllegalNotesRoot(_) => "IllegalNotesRoot",
+            Self::IllegalNASAKey => "IllegalNASAKey",
+            Self::MissingToken(_) => "MissingToken",
+            Self::IllegalURL(_) => "IllegalURL",
+            Self::IllegalSelector(_) => "IllegalSelector",
+            Self::IllegalHTMLContent => "IllegalHTMLContent",
+            Self::HtmlExtraction { .. } => "HtmlExtraction",
+            Self::Unauthorized { .. } => "Unauthorized",
+            Self::RateLimited { .. } => "RateLimited",
+            Self::HttpStatus { .. } => "HttpStatus",
+            Self::BodyTooLarge { .. } => "BodyTooLarge",
+            Self::UnknownMediaType => "UnknownMediaType",
+            Self::IllegalYearNumber(_) => "IllegalYearNumber",
+            Self::IllegalMonthNumber(_) => "IllegalMonthNumber",
+            Self::IllegalQuarterNumber(_) => "IllegalQuarterNumber",
+            Self::NotConfirmed(_) => "NotConfirmed",
+            Self::IllegalPath(_) => "IllegalPath",
+            Self::IllegalGlob(_) => "IllegalGlob",
+            Self::IllegalDuration(_) => "IllegalDuration",
+            Self::IllegalDateTag(_) => "IllegalDateTag",
+            Self::IllegalDateFormat(_) => "IllegalDateFormat",
+            Self::IllegalTimeZone(_) => "IllegalTimeZone",
+            Self::IllegalEditor(_) => "IllegalEditor",
+            Self::IllegalSchedule { .. } => "IllegalSchedule",
+            Self::IllegalManifest { .. } => "IllegalManifest",
+            Self::MissingGrabNote => "MissingGrabNote",
+            Self::IllegalEncoding(_) => "IllegalEncoding",
+            Self::IllegalCalendarStyle(_) => "IllegalCalendarStyle",
+            Self::IllegalTitleSource(_) => "IllegalTitleSource",
+            Self::IllegalFormat(_) => "IllegalFormat",
+            Self::IllegalTableFormat(_) => "IllegalTableFormat",
+            Self::IllegalGraphFormat(_) => "IllegalGraphFormat",
+            Self::MultipleExecutorsError(_) => "MultipleExecutorsError",
+            Self::IoError(_) => "IoError",
+            Self::VarError(_) => "VarError",
+            Self::RequestError(_) => "RequestError",
+            Self::URLParseError(_) => "URLParseError",
+            Self::DateTimeParseError(_) => "DateTimeParseError",
+            Self::IllegalConfig { .. } => "IllegalConfig",
+            Self::IllegalConfigProperty(_) => "IllegalConfigProperty",
+            Self::ConfigEditError(_) => "ConfigEditError",
+            Self::ConfigParseError(_) => "ConfigParseError",
+            Self::ConfigSerializeError(_) => "ConfigSerializeError",
+            Self::InitLoggerError(_) => "InitLoggerError",
+            Self::JsonError(_) => "JsonError",
+            Self::CsvError(_) => "CsvError",
+            Self::TemplateError(_) => "TemplateError",
+            Self::GitError(_) => "GitError",
+            Self::JoinError(_) => "JoinError",
+            Self::SetLoggerError(_) => "SetLoggerError",
+        }
+    }
+
+    ///
+    /// Get the process exit code of the error category.
+    ///
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::CannotFindConfig
+            | Self::ConfigAlreadyExists(_)
+            | Self::IllegalIssue(_)
+            | Self::IllegalNotesRoot(_)
+            | Self::IllegalNASAKey
+            | Self::MissingToken(_)
+            | Self::Unauthorized { .. }
+            | Self::IllegalSelector(_)
+            | Self::IllegalYearNumber(_)
+            | Self::IllegalMonthNumber(_)
+            | Self::IllegalQuarterNumber(_)
+            | Self::NotConfirmed(_)
+            | Self::IllegalGlob(_)
+            | Self::IllegalDuration(_)
+            | Self::IllegalDateTag(_)
+            | Self::IllegalDateFormat(_)
+            | Self::IllegalTimeZone(_)
+            | Self::IllegalEditor(_)
+            | Self::IllegalSchedule { .. }
+            | Self::IllegalManifest { .. }
+            | Self::MissingGrabNote
+            | Self::IllegalEncoding(_)
+            | Self::IllegalCalendarStyle(_)
+            | Self::IllegalTitleSource(_)
+            | Self::IllegalFormat(_)
+            | Self::IllegalTableFormat(_)
+            | Self::IllegalGraphFormat(_)
+            | Self::VarError(_)
+            | Self::IllegalConfig { .. }
+            | Self::IllegalConfigProperty(_)
+            | Self::ConfigEditError(_)
+            | Self::ConfigParseError(_)
+            | Self::ConfigSerializeError(_)
+            | Self::TemplateError(_) => Self::EXIT_CONFIG,
+
+            Self::IllegalURL(_)
+            | Self::IllegalHTMLContent
+            | Self::HtmlExtraction { .. }
+            | Self::RateLimited { .. }
+            | Self::HttpStatus { .. }
+            | Self::BodyTooLarge { .. }
+            | Self::UnknownMediaType
+            | Self::RequestError(_)
+            | Self::URLParseError(_) => Self::EXIT_NETWORK,
+
+            Self::IllegalPath(_) | Self::IoError(_) | Self::GitError(_) => Self::EXIT_IO,
+
+            Self::MultipleExecutorsError(_) => Self::EXIT_PARTIAL,
+
+            Self::AppInitError
+            | Self::DateTimeParseError(_)
+            | Self::InitLoggerError(_)
+            | Self::JsonError(_)
+            | Self::CsvError(_)
+            | Self::JoinError(_)
+            | Self::SetLoggerError(_) => Self::EXIT_INTERNAL,
+        }
+    }
+
+    ///
+    /// Convert the error to the machine-readable JSON value.
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "error": self.to_string(),
+            "kind": self.kind(),
+            "code": self.exit_code(),
+        });
+        if let Self::MultipleExecutorsError(errors) = self {
+            value["errors"] = errors.iter().map(Self::to_json).collect();
+        }
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_test() {
+        let error = Error::multiple(vec![
+            Error::IllegalIssue("1".to_string()),
+            Error::IllegalIssue("0".to_string()),
+        ]);
+        let value = error.to_json();
+
+        assert_eq!(value["kind"], "MultipleExecutorsError");
+        assert_eq!(value["code"], Error::EXIT_PARTIAL);
+        assert_eq!(value["errors"][0]["kind"], "IllegalIssue");
+        assert_eq!(value["errors"][0]["error"], "illegal issue number 0");
+        assert_eq!(value["errors"][0]["code"], Error::EXIT_CONFIG);
+        assert_eq!(value["errors"][1]["error"], "illegal issue number 1");
+    }
+}
@@ -1,4 +1,4 @@
-use clap::Parser;
+use std::process::ExitCode;
 
 use nta::{
     application::Application,
@@ -8,14 +8,30 @@ use nta::{
 };
 
 #[tokio::main]
-async fn main() -> Result<(), Error> {
-    let args = Arguments::parse();
-    let options = Options::new().await?;
+async fn main() -> ExitCode {
+    let args = Arguments::parse_args();
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            Application::report_error(&args, &error);
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+async fn run(args: &Arguments) -> Result<(), Error> {
+    let options = Options::new(args).await?;
+    if Application::print_paths(args, &options) {
+        return Ok(());
+    }
 
-    Application::setup_logger(&args, &options)?;
+    Application::setup_logger(args, &options)?;
+    if Application::run_standalone(args, &options).await? {
+        return Ok(());
+    }
 
     let config = Config::new(&options).await?;
-    let app = Application::new(config);
+    let app = Application::new(config, &options);
 
-    app.run(&args).await
+    app.run(args).await
 }